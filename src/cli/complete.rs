@@ -0,0 +1,163 @@
+use std::ffi::OsStr;
+
+use clap_complete::engine::CompletionCandidate;
+
+use crate::{
+    infrastructure::{
+        config::ConfigManager,
+        git::{DefaultWorktreeManager, WorktreeManager},
+    },
+    models::Config,
+    utils::get_wx_home,
+};
+
+/// Config used for completion; any error yields no candidates instead of breaking the shell
+fn load_config() -> Option<Config> {
+    ConfigManager::with_base_dir(&get_wx_home()?).load().ok()
+}
+
+/// Candidates among `values` that start with `current`
+fn candidates<I, S>(current: &OsStr, values: I) -> Vec<CompletionCandidate>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let current = current.to_string_lossy();
+    values
+        .into_iter()
+        .filter(|value| value.as_ref().starts_with(current.as_ref()))
+        .map(|value| CompletionCandidate::new(value.as_ref()))
+        .collect()
+}
+
+/// Branch names of a registered repository, without the `origin/` prefix
+fn branches(config: &Config, repo_name: &str) -> Vec<String> {
+    let Some(repo) = config.find_repository(repo_name) else {
+        return Vec::new();
+    };
+    let worktree_manager = DefaultWorktreeManager;
+    let local_path = &repo.bare_repo_path();
+
+    let mut branches: Vec<String> = worktree_manager
+        .get_local_branches(local_path)
+        .unwrap_or_default();
+    branches.extend(
+        worktree_manager
+            .get_remote_branches(local_path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|name| name.strip_prefix("origin/").map(str::to_string))
+            .filter(|name| name != "HEAD"),
+    );
+    branches.sort();
+    branches.dedup();
+    branches
+}
+
+/// en: Complete registered repository names
+///
+/// ja: 登録済みリポジトリ名を補完する
+pub fn repositories(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = load_config() else {
+        return Vec::new();
+    };
+    candidates(current, config.repositories.iter().map(|repo| &repo.name))
+}
+
+/// en: Complete group names
+///
+/// ja: グループ名を補完する
+pub fn groups(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = load_config() else {
+        return Vec::new();
+    };
+    candidates(current, config.groups.iter().map(|group| &group.name))
+}
+
+/// en: Complete registered workspace names
+///
+/// ja: 登録済みworkspace名を補完する
+pub fn workspaces(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = load_config() else {
+        return Vec::new();
+    };
+    candidates(current, config.workspaces.iter().map(|entry| &entry.name))
+}
+
+/// en: Complete `<repo>[:<branch>]`: repository names, then the branches of the repository after `:`
+///
+/// ja: `<repo>[:<branch>]` を補完する（リポジトリ名、`:` の後はそのリポジトリのブランチ）
+pub fn repository_branches(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = load_config() else {
+        return Vec::new();
+    };
+    let current_str = current.to_string_lossy();
+
+    match current_str.split_once(':') {
+        Some((repo_name, _)) => candidates(
+            current,
+            branches(&config, repo_name)
+                .into_iter()
+                .map(|branch| format!("{repo_name}:{branch}")),
+        ),
+        None => candidates(current, config.repositories.iter().map(|repo| &repo.name)),
+    }
+}
+
+/// en: Complete branch names of every registered repository
+///
+/// ja: 全登録済みリポジトリのブランチ名を補完する
+pub fn all_branches(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = load_config() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = config
+        .repositories
+        .iter()
+        .flat_map(|repo| branches(&config, &repo.name))
+        .collect();
+    names.sort();
+    names.dedup();
+    candidates(current, names)
+}
+
+/// en: Complete `<workspace>[/<repo>]`: workspace names, then the worktree directories after `/`
+///
+/// ja: `<workspace>[/<repo>]` を補完する（workspace名、`/` の後はworktreeのディレクトリ）
+pub fn workspace_members(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(config) = load_config() else {
+        return Vec::new();
+    };
+    let current_str = current.to_string_lossy();
+
+    match current_str.split_once('/') {
+        Some((workspace_name, _)) => {
+            let Some(entry) = config.find_workspace(workspace_name) else {
+                return Vec::new();
+            };
+            candidates(
+                current,
+                entry.members.iter().filter_map(|member| {
+                    let dir_name = member.path.file_name()?.to_string_lossy();
+                    Some(format!("{workspace_name}/{dir_name}"))
+                }),
+            )
+        }
+        None => candidates(current, config.workspaces.iter().map(|entry| &entry.name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_filters_by_prefix() {
+        let values: Vec<String> =
+            candidates(OsStr::new("fe"), ["feature-auth", "frontend", "feature-x"])
+                .iter()
+                .map(|candidate| candidate.get_value().to_string_lossy().to_string())
+                .collect();
+        assert_eq!(values, vec!["feature-auth", "feature-x"]);
+    }
+}
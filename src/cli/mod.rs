@@ -1,4 +1,17 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+
+use crate::{
+    models::{
+        shell::Shell,
+        workspace::{WorkspaceFormat, WorktreeLayout},
+    },
+    presentation::OutputFormat,
+};
+
+mod complete;
 
 #[derive(Parser)]
 #[command(name = "wx")]
@@ -7,24 +20,362 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+    /// en: Output format: `text`, or `json` for scripts
+    ///
+    /// ja: 出力形式: `text`、またはスクリプト向けの `json`
+    #[arg(long, global = true, value_name = "FORMAT", default_value_t)]
+    pub output: OutputFormat,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// en: Register a Git repository (bare clone)
+    /// en: Register Git repositories (bare clone), cloning several URLs concurrently
     ///
-    /// ja: Gitリポジトリを登録する（bare clone）
-    Register { url: String },
+    /// ja: Gitリポジトリを登録する（bare clone）。複数のURLは並行してcloneする
+    Register {
+        #[arg(required = true, value_name = "URL")]
+        urls: Vec<String>,
+        /// en: Name to register the repository under instead of the last segment of the URL
+        /// (only with a single URL)
+        ///
+        /// ja: URLの最後の要素の代わりに登録する名前（URLが1つの場合のみ）
+        #[arg(long, value_name = "ALIAS")]
+        name: Option<String>,
+        /// en: Number of clones to run at the same time (defaults to the number of CPUs)
+        ///
+        /// ja: 同時に実行するclone数（省略時はCPU数）
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// en: Ref new branches start from when no base is given (e.g. `origin/develop`)
+        ///
+        /// ja: base未指定時に新しいブランチの作成元となる参照（例: `origin/develop`）
+        #[arg(long)]
+        base: Option<String>,
+    },
     /// en: List registered repositories
     ///
     /// ja: 登録済みリポジトリを一覧表示する
     List,
+    /// en: Fetch registered repositories concurrently
+    ///
+    /// ja: 登録済みリポジトリを並行してフェッチする
+    Fetch {
+        #[arg(required_unless_present = "all", value_name = "NAME", add = ArgValueCompleter::new(complete::repositories))]
+        repos: Vec<String>,
+        /// en: Fetch every registered repository
+        ///
+        /// ja: 全ての登録済みリポジトリをフェッチする
+        #[arg(long, conflicts_with = "repos")]
+        all: bool,
+        /// en: Number of fetches to run at the same time (defaults to the number of CPUs)
+        ///
+        /// ja: 同時に実行するフェッチ数（省略時はCPU数）
+        #[arg(short, long)]
+        jobs: Option<usize>,
+    },
     /// en: Unregister a repository
     ///
     /// ja: リポジトリの登録を解除する
-    Unregister { name: String },
+    Unregister {
+        #[arg(add = ArgValueCompleter::new(complete::repositories))]
+        name: String,
+        /// en: Unregister even if worktrees still use the repository
+        ///
+        /// ja: worktreeが使用中でも登録を解除する
+        #[arg(long)]
+        force: bool,
+    },
+    /// en: Manage groups of repositories selected at once with `--group`
+    ///
+    /// ja: `--group` でまとめて選択するリポジトリのグループを管理する
+    Group {
+        #[command(subcommand)]
+        command: GroupCommands,
+    },
     /// en: Create a new workspace
     ///
     /// ja: 新しいワークスペースを作成する
-    New { workspace_name: String },
+    New {
+        /// en: Workspace name (defaults to the manifest name with --from)
+        ///
+        /// ja: workspace名（--from 指定時はマニフェストの名前が既定値）
+        #[arg(required_unless_present = "from")]
+        workspace_name: Option<String>,
+        /// en: Create the workspace from a `wx.toml` manifest
+        ///
+        /// ja: `wx.toml` マニフェストからworkspaceを作成する
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["repos", "groups", "all_repos", "branch", "layout", "base", "template", "formats"])]
+        from: Option<PathBuf>,
+        /// en: Worktree directory layout: `repo`, `repo-branch`, or a template such as `{repo}@{branch}`
+        ///
+        /// ja: worktreeディレクトリのレイアウト: `repo`、`repo-branch`、または `{repo}@{branch}` のようなテンプレート
+        #[arg(long)]
+        layout: Option<WorktreeLayout>,
+        /// en: Repository and branch as `<repo>:<branch>` (repeatable). Skips the interactive prompts
+        ///
+        /// ja: `<repo>:<branch>` 形式のリポジトリとブランチ（複数指定可）。対話形式をスキップする
+        #[arg(long = "repo", value_name = "REPO[:BRANCH]", add = ArgValueCompleter::new(complete::repository_branches))]
+        repos: Vec<String>,
+        /// en: Use the repositories of a group (repeatable). Without --branch they are preselected
+        /// in the interactive picker
+        ///
+        /// ja: グループのリポジトリを使用する（複数指定可）。--branch が無い場合は対話形式の選択で初期選択にする
+        #[arg(long = "group", value_name = "GROUP", add = ArgValueCompleter::new(complete::groups))]
+        groups: Vec<String>,
+        /// en: Use every registered repository (requires --branch)
+        ///
+        /// ja: 登録済みの全リポジトリを使用する（--branch が必要）
+        #[arg(long, conflicts_with_all = ["repos", "groups"], requires = "branch")]
+        all_repos: bool,
+        /// en: Branch for --all-repos, --group and for --repo entries without a branch
+        ///
+        /// ja: --all-repos・--group およびブランチ未指定の --repo に使うブランチ
+        #[arg(long, add = ArgValueCompleter::new(complete::all_branches))]
+        branch: Option<String>,
+        /// en: Ref new branches start from (e.g. `origin/develop`), overriding the repository default
+        ///
+        /// ja: 新しいブランチの作成元となる参照（例: `origin/develop`）。リポジトリの既定値を上書きする
+        #[arg(long)]
+        base: Option<String>,
+        /// en: Template in `~/.wx/templates/<name>.json` merged into the workspace file
+        ///
+        /// ja: workspaceファイルにマージする `~/.wx/templates/<name>.json` のテンプレート
+        #[arg(long, value_name = "NAME")]
+        template: Option<String>,
+        /// en: Editor project format: vscode, cursor, kiro, zed, sublime or jetbrains (repeatable, comma separated)
+        ///
+        /// ja: エディタのプロジェクト形式: vscode、cursor、kiro、zed、sublime、jetbrains（複数指定可、カンマ区切り）
+        #[arg(long = "format", value_name = "FORMAT", value_delimiter = ',')]
+        formats: Vec<WorkspaceFormat>,
+        /// en: Open the workspace in the configured editor once it is created
+        ///
+        /// ja: 作成後、設定されたエディタでworkspaceを開く
+        #[arg(long)]
+        open: bool,
+    },
+    /// en: Open a workspace in the configured editor
+    ///
+    /// ja: 設定されたエディタでworkspaceを開く
+    Open {
+        /// en: Registered workspace name or path (defaults to the workspace containing the current directory)
+        ///
+        /// ja: 登録済みworkspace名またはパス（省略時はカレントディレクトリを含むworkspace）
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        workspace: Option<String>,
+        /// en: Editor command overriding the configured one, e.g. `cursor` or `code -n {dir}`
+        ///
+        /// ja: 設定を上書きするエディタのコマンド（例: `cursor`、`code -n {dir}`）
+        #[arg(long)]
+        editor: Option<String>,
+    },
+    /// en: Add a worktree of a repository to an existing workspace
+    ///
+    /// ja: 既存のworkspaceにリポジトリのworktreeを追加する
+    Add {
+        /// en: Registered workspace name or path
+        ///
+        /// ja: 登録済みworkspace名またはパス
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        workspace: String,
+        /// en: Repository as <repo>[:<branch>] (defaults to the branch shared by the workspace)
+        ///
+        /// ja: <repo>[:<branch>] 形式のリポジトリ（ブランチ省略時はworkspace共通のブランチ）
+        #[arg(value_name = "REPO[:BRANCH]", add = ArgValueCompleter::new(complete::repository_branches))]
+        repo: String,
+        /// en: Ref a new branch starts from (e.g. `origin/develop`), overriding the repository default
+        ///
+        /// ja: 新しいブランチの作成元となる参照（例: `origin/develop`）。リポジトリの既定値を上書きする
+        #[arg(long)]
+        base: Option<String>,
+    },
+    /// en: Remove the worktree of a repository from a workspace
+    ///
+    /// ja: workspaceからリポジトリのworktreeを削除する
+    Remove {
+        /// en: Registered workspace name or path
+        ///
+        /// ja: 登録済みworkspace名またはパス
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        workspace: String,
+        /// en: Repository as <repo>[:<branch>]; the branch is only needed when the repository is checked out more than once
+        ///
+        /// ja: <repo>[:<branch>] 形式のリポジトリ（ブランチは複数回チェックアウトされている場合のみ必要）
+        #[arg(value_name = "REPO[:BRANCH]", add = ArgValueCompleter::new(complete::repository_branches))]
+        repo: String,
+        /// en: Remove the worktree even if it has uncommitted changes
+        ///
+        /// ja: 未コミットの変更があってもworktreeを削除する
+        #[arg(long)]
+        force: bool,
+    },
+    /// en: Create or reconcile a workspace from a `wx.toml` manifest
+    ///
+    /// ja: `wx.toml` マニフェストからworkspaceを作成・同期する
+    Apply {
+        #[arg(default_value = "wx.toml")]
+        manifest: PathBuf,
+    },
+    /// en: List registered workspaces
+    ///
+    /// ja: 登録済みworkspaceを一覧表示する
+    Workspaces,
+    /// en: Inspect a registered workspace
+    ///
+    /// ja: 登録済みworkspaceを参照する
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommands,
+    },
+    /// en: Show the git status of every worktree of a workspace
+    ///
+    /// ja: workspaceの全worktreeのGitの状態を表示する
+    Status {
+        /// en: Registered workspace name or path (defaults to the workspace containing the current directory)
+        ///
+        /// ja: 登録済みworkspace名またはパス（省略時はカレントディレクトリを含むworkspace）
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        workspace: Option<String>,
+        /// en: Only include worktrees of repositories in this group (repeatable)
+        ///
+        /// ja: 指定したグループのリポジトリのworktreeのみを対象にする（複数指定可）
+        #[arg(long = "group", value_name = "GROUP", add = ArgValueCompleter::new(complete::groups))]
+        groups: Vec<String>,
+        /// en: Print the status as JSON (same as `--output json`)
+        ///
+        /// ja: 状態をJSONで出力する（`--output json` と同じ）
+        #[arg(long)]
+        json: bool,
+    },
+    /// en: Fetch every repository of a workspace and fast-forward its worktrees
+    ///
+    /// ja: workspaceの全リポジトリをフェッチし、worktreeをfast-forwardする
+    Sync {
+        /// en: Registered workspace name or path (defaults to the workspace containing the current directory)
+        ///
+        /// ja: 登録済みworkspace名またはパス（省略時はカレントディレクトリを含むworkspace）
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        workspace: Option<String>,
+        /// en: Only include worktrees of repositories in this group (repeatable)
+        ///
+        /// ja: 指定したグループのリポジトリのworktreeのみを対象にする（複数指定可）
+        #[arg(long = "group", value_name = "GROUP", add = ArgValueCompleter::new(complete::groups))]
+        groups: Vec<String>,
+        /// en: Rebase local commits onto the upstream instead of skipping diverged worktrees
+        ///
+        /// ja: 分岐したworktreeをスキップせず、ローカルコミットを上流にrebaseする
+        #[arg(long)]
+        rebase: bool,
+    },
+    /// en: Run a command in every worktree of a workspace
+    ///
+    /// ja: workspaceの全worktreeでコマンドを実行する
+    Exec {
+        /// en: Registered workspace name or path
+        ///
+        /// ja: 登録済みworkspace名またはパス
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        workspace: String,
+        /// en: Only run in the worktree of this repository (repeatable)
+        ///
+        /// ja: 指定したリポジトリのworktreeでのみ実行する（複数指定可）
+        #[arg(long = "repo", value_name = "NAME", add = ArgValueCompleter::new(complete::repositories))]
+        repos: Vec<String>,
+        /// en: Only run in worktrees of repositories in this group (repeatable)
+        ///
+        /// ja: 指定したグループのリポジトリのworktreeでのみ実行する（複数指定可）
+        #[arg(long = "group", value_name = "GROUP", add = ArgValueCompleter::new(complete::groups))]
+        groups: Vec<String>,
+        /// en: Number of commands to run at the same time (defaults to the number of CPUs)
+        ///
+        /// ja: 同時に実行するコマンド数（省略時はCPU数）
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// en: Stop at the first failure and kill the commands still running
+        ///
+        /// ja: 最初の失敗で停止し、実行中のコマンドを終了させる
+        #[arg(long)]
+        fail_fast: bool,
+        /// en: Command to run after `--`; a single argument is run through the shell
+        ///
+        /// ja: `--` の後に実行するコマンド（引数が1つの場合はシェル経由で実行する）
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+    /// en: Print the directory of a workspace or one of its worktrees; `wx cd` changes to it once `wx shell-init` is set up
+    ///
+    /// ja: workspaceまたはそのworktreeのディレクトリを出力する（`wx shell-init` 設定後は `wx cd` でそこへ移動する）
+    Cd {
+        /// en: Workspace as <workspace>[/<repo>] (defaults to the workspace containing the current directory)
+        ///
+        /// ja: <workspace>[/<repo>] 形式のworkspace（省略時はカレントディレクトリを含むworkspace）
+        #[arg(value_name = "WORKSPACE[/REPO]", add = ArgValueCompleter::new(complete::workspace_members))]
+        target: Option<String>,
+    },
+    /// en: Open the full-screen dashboard (also opened by `wx` without arguments in a terminal)
+    ///
+    /// ja: フルスクリーンのダッシュボードを開く（ターミナルで引数無しの `wx` でも開く）
+    Tui,
+    /// en: Print the shell integration script: the `wx cd` function and completions
+    ///
+    /// ja: シェル統合のスクリプト（`wx cd` 関数と補完）を出力する
+    ShellInit {
+        /// en: Shell: bash, zsh or fish
+        ///
+        /// ja: シェル: bash、zsh、fish
+        shell: Shell,
+    },
+    /// en: Remove a workspace together with its worktrees
+    ///
+    /// ja: workspaceをworktreeごと削除する
+    Clean {
+        /// en: Registered workspace name, or workspace directory in the current directory
+        ///
+        /// ja: 登録済みworkspace名、またはカレントディレクトリ内のworkspaceディレクトリ
+        #[arg(required_unless_present = "all", conflicts_with = "all", add = ArgValueCompleter::new(complete::workspaces))]
+        workspace_name: Option<String>,
+        /// en: Clean every workspace in the current directory
+        ///
+        /// ja: カレントディレクトリ内の全workspaceを削除する
+        #[arg(long)]
+        all: bool,
+        /// en: Remove worktrees even if they have uncommitted changes
+        ///
+        /// ja: 未コミットの変更があってもworktreeを削除する
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommands {
+    /// en: Show details of a workspace by name or path
+    ///
+    /// ja: 名前またはパスを指定してworkspaceの詳細を表示する
+    Show {
+        #[arg(add = ArgValueCompleter::new(complete::workspaces))]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GroupCommands {
+    /// en: Create a group of registered repositories
+    ///
+    /// ja: 登録済みリポジトリのグループを作成する
+    Create {
+        name: String,
+        #[arg(required = true, value_name = "REPO", add = ArgValueCompleter::new(complete::repositories))]
+        repos: Vec<String>,
+    },
+    /// en: List groups and their repositories
+    ///
+    /// ja: グループとそのリポジトリを一覧表示する
+    List,
+    /// en: Remove a group; its repositories stay registered
+    ///
+    /// ja: グループを削除する（リポジトリの登録はそのまま残る）
+    Rm {
+        #[arg(add = ArgValueCompleter::new(complete::groups))]
+        name: String,
+    },
 }
@@ -0,0 +1,56 @@
+use crate::{
+    infrastructure::git::DefaultWorktreeManager,
+    models::{
+        workspace::{WorkspaceMember, WorktreeSelection},
+        WxError,
+    },
+    services::{RepositoryService, WorkspaceGenerationService, WorkspaceRegistryService},
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx add` command to add a worktree of `<repo>[:<branch>]` to a workspace
+/// Without a branch, the branch every worktree of the workspace is on is used
+///
+/// ja: `wx add` コマンドを実行し、`<repo>[:<branch>]` のworktreeをworkspaceに追加する
+/// ブランチ省略時は、workspaceの全worktreeが共通して使っているブランチを使用する
+pub fn execute(
+    workspace: &str,
+    spec: &str,
+    base: Option<String>,
+) -> Result<WorkspaceMember, WxError> {
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+    let workspace = WorkspaceRegistryService::with_base_dir(&wx_home)
+        .resolve(workspace, &get_current_dir()?)?;
+
+    let mut selection =
+        WorktreeSelection::parse(spec, workspace.common_branch()).map_err(|_| {
+            WxError::General(format!(
+                "Invalid repository '{spec}'. Expected <repo>:<branch>, as the worktrees of '{}' are on different branches",
+                workspace.name
+            ))
+        })?;
+    selection.base = base;
+
+    if !RepositoryService::with_base_dir(&wx_home)
+        .list()?
+        .iter()
+        .any(|repo| repo.name == selection.repo_name)
+    {
+        return Err(WxError::RepositoryNotFound(selection.repo_name));
+    }
+    if workspace
+        .members
+        .iter()
+        .any(|member| member.repo_name == selection.repo_name && member.branch == selection.branch)
+    {
+        return Err(WxError::AlreadyInWorkspace(
+            selection.repo_name,
+            workspace.name,
+        ));
+    }
+
+    let mut added = WorkspaceGenerationService::new(DefaultWorktreeManager, wx_home)?
+        .add_worktrees(&workspace, vec![selection])?;
+
+    Ok(added.remove(0))
+}
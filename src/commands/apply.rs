@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use crate::{
+    infrastructure::{filesystem::ManifestFileManager, git::DefaultWorktreeManager},
+    models::{manifest::ApplyResult, WxError},
+    services::WorkspaceManifestService,
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx apply` command (and `wx new --from`) to create or reconcile a workspace
+/// from a manifest. `name` overrides the workspace name of the manifest
+/// With `create_only`, an already registered workspace is an error
+///
+/// ja: `wx apply` コマンド（および `wx new --from`）を実行し、マニフェストからworkspaceを作成・同期する
+/// `name` はマニフェストのworkspace名を上書きする
+/// `create_only` 指定時は、登録済みのworkspaceをエラーとする
+pub fn execute(
+    manifest_path: &Path,
+    name: Option<String>,
+    create_only: bool,
+) -> Result<ApplyResult, WxError> {
+    let mut manifest = ManifestFileManager.read(manifest_path)?;
+    if let Some(name) = name {
+        manifest.name = name;
+    }
+
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+
+    WorkspaceManifestService::new(DefaultWorktreeManager, wx_home)?.apply(
+        &manifest,
+        &get_current_dir()?,
+        create_only,
+    )
+}
@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use crate::{
+    models::WxError,
+    services::WorkspaceRegistryService,
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx cd` command, returning the directory of `<workspace>[/<repo>]`,
+/// or of the workspace containing the current directory
+/// The shell function installed by `wx shell-init` changes to the printed directory
+///
+/// ja: `wx cd` コマンドを実行し、`<workspace>[/<repo>]`（省略時はカレントディレクトリを含むworkspace）のディレクトリを返却する
+/// `wx shell-init` で導入したシェル関数が、出力されたディレクトリへ移動する
+pub fn execute(target: Option<&str>) -> Result<PathBuf, WxError> {
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+    let registry = WorkspaceRegistryService::with_base_dir(&wx_home);
+    let current_dir = get_current_dir()?;
+
+    match target {
+        Some(target) => registry.resolve_dir(target, &current_dir),
+        None => Ok(registry.resolve_containing(&current_dir)?.path),
+    }
+}
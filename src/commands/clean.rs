@@ -0,0 +1,19 @@
+use crate::{
+    infrastructure::git::DefaultWorktreeManager,
+    models::{
+        workspace::{CleanResult, CleanTarget},
+        WxError,
+    },
+    services::WorkspaceCleanService,
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx clean` command to remove workspaces and their worktrees
+///
+/// ja: `wx clean` コマンドを実行し、workspaceとそのworktreeを削除する
+pub fn execute(target: CleanTarget, force: bool) -> Result<CleanResult, WxError> {
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+    let worktree_manager = DefaultWorktreeManager;
+
+    WorkspaceCleanService::new(worktree_manager, wx_home)?.clean(&get_current_dir()?, target, force)
+}
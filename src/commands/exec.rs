@@ -0,0 +1,36 @@
+use crate::{
+    models::{
+        exec::{ExecOptions, ExecReport, OutputStream},
+        WxError,
+    },
+    services::WorkspaceExecService,
+    utils::{default_jobs, get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx exec` command, passing every output line to `on_output` as it is written
+///
+/// ja: `wx exec` コマンドを実行し、出力された各行を逐次 `on_output` に渡す
+pub fn execute<F>(
+    workspace: &str,
+    command: Vec<String>,
+    repos: Vec<String>,
+    groups: Vec<String>,
+    jobs: Option<usize>,
+    fail_fast: bool,
+    on_output: F,
+) -> Result<Vec<ExecReport>, WxError>
+where
+    F: Fn(usize, &str, OutputStream, &str) + Sync,
+{
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+
+    let options = ExecOptions {
+        command,
+        repos,
+        groups,
+        jobs: jobs.unwrap_or_else(default_jobs),
+        fail_fast,
+    };
+
+    WorkspaceExecService::new(&wx_home).exec(workspace, &get_current_dir()?, &options, on_output)
+}
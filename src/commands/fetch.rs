@@ -0,0 +1,25 @@
+use crate::{
+    models::{
+        transfer::{FetchReport, TransferProgress},
+        WxError,
+    },
+    services::RepositoryService,
+    utils::default_jobs,
+};
+
+/// en: Execute the `wx fetch` command for the named repositories, or every registered
+/// repository when `repos` is empty
+/// The transfer of each fetch is passed to `on_progress` as it is received
+///
+/// ja: `wx fetch` コマンドを、指定したリポジトリ（`repos` が空の場合は全ての登録済みリポジトリ）に対して実行する
+/// 各フェッチの転送の進捗は、受信の都度 `on_progress` に渡される
+pub fn execute<F>(
+    repos: &[String],
+    jobs: Option<usize>,
+    on_progress: F,
+) -> Result<Vec<FetchReport>, WxError>
+where
+    F: Fn(usize, &str, &TransferProgress) + Sync,
+{
+    RepositoryService::new()?.fetch(repos, jobs.unwrap_or_else(default_jobs), on_progress)
+}
@@ -0,0 +1,25 @@
+use crate::{
+    models::{RepositoryGroup, WxError},
+    services::GroupService,
+};
+
+/// en: Execute the `wx group create` command
+///
+/// ja: `wx group create` コマンドを実行する
+pub fn create(name: &str, repos: &[String]) -> Result<RepositoryGroup, WxError> {
+    GroupService::new()?.create(name, repos)
+}
+
+/// en: Execute the `wx group list` command
+///
+/// ja: `wx group list` コマンドを実行する
+pub fn list() -> Result<Vec<RepositoryGroup>, WxError> {
+    GroupService::new()?.list()
+}
+
+/// en: Execute the `wx group rm` command and return the removed group
+///
+/// ja: `wx group rm` コマンドを実行し、削除したグループを返却する
+pub fn remove(name: &str) -> Result<RepositoryGroup, WxError> {
+    GroupService::new()?.remove(name)
+}
@@ -1,4 +1,18 @@
+pub mod add;
+pub mod apply;
+pub mod cd;
+pub mod clean;
+pub mod exec;
+pub mod fetch;
+pub mod group;
 pub mod list;
 pub mod new;
+pub mod open;
 pub mod register;
+pub mod remove;
+pub mod shell_init;
+pub mod status;
+pub mod sync;
 pub mod unregister;
+pub mod workspace;
+pub mod workspaces;
@@ -1,81 +1,534 @@
-use std::fs;
+use std::io::{stdin, IsTerminal};
 
-use dialoguer::{Input, MultiSelect};
+use dialoguer::{FuzzySelect, Input, MultiSelect, Select};
 
 use crate::{
-    infrastructure::git::DefaultWorktreeManager,
-    models::{workspace::WorktreeSelection, WxError},
-    services::{RepositoryService, WorkspaceGenerationService},
-    utils::{get_current_dir, get_wx_home},
+    infrastructure::git::{DefaultWorktreeManager, WorktreeManager},
+    models::{
+        transfer::TransferProgress,
+        workspace::{
+            BranchInfo, GenerationResult, WorkspaceFormat, WorktreeLayout, WorktreeSelection,
+        },
+        Repository, RepositoryGroup, WxError,
+    },
+    services::{GroupService, RepositoryService, WorkspaceGenerationService},
+    utils::{default_jobs, get_current_dir, get_wx_home},
 };
 
-/// en: Execute the `wx new` command to create worktrees and a workspace file interactively
+const CREATE_NEW_BRANCH: &str = "+ create new branch…";
+const NO_GROUP: &str = "(none)";
+
+/// en: Options for `wx new`; without `repos`, `all_repos` or `groups` with a `branch`
+/// the selection is interactive
+///
+/// ja: `wx new` のオプション（`repos`、`all_repos`、`branch` 付きの `groups` が無い場合は対話形式で選択する）
+#[derive(Debug, Default)]
+pub struct NewOptions {
+    pub layout: Option<WorktreeLayout>,
+    /// `<repo>[:<branch>]` specs
+    pub repos: Vec<String>,
+    /// Groups whose repositories are used, or preselected in the interactive picker without `branch`
+    pub groups: Vec<String>,
+    pub all_repos: bool,
+    /// Branch used for `all_repos`, `groups` and for specs without a branch
+    pub branch: Option<String>,
+    /// Ref new branches start from, overriding each repository's `default_base`
+    pub base: Option<String>,
+    /// Template in `~/.wx/templates` merged into the workspace file
+    pub template: Option<String>,
+    /// Editor formats written, overriding `formats` in config.json
+    pub formats: Vec<WorkspaceFormat>,
+}
+
+/// en: Execute the `wx new` command to create worktrees and a workspace file
+/// from flags, or interactively when no repositories are given
+/// The transfer of the fetches before the interactive branch selection is passed to `on_progress`
 ///
-/// ja: `wx new` コマンドを実行し、インタラクティブにworktreeとworkspaceファイルを作成する
-pub fn execute(workspace_name: String) -> Result<(), WxError> {
+/// ja: `wx new` コマンドを実行し、フラグから（指定が無ければ対話形式で）worktreeとworkspaceファイルを作成する
+/// 対話形式でのブランチ選択前のフェッチの進捗は `on_progress` に渡される
+pub fn execute<F>(
+    workspace_name: String,
+    options: NewOptions,
+    on_progress: F,
+) -> Result<GenerationResult, WxError>
+where
+    F: Fn(usize, &str, &TransferProgress) + Sync,
+{
     let repos = RepositoryService::new()?.list()?;
     if repos.is_empty() {
         return Err(WxError::General("No repositories registered. Please register a repository first using 'wx register <url>'".to_string()));
     }
 
+    let workspace_dir = get_current_dir()?.join(&workspace_name);
+    if workspace_dir.exists() {
+        return Err(WxError::General(format!(
+            "Workspace directory '{workspace_name}' already exists"
+        )));
+    }
+
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+    let worktree_manager = DefaultWorktreeManager;
+
+    let mut workspace_generation_service =
+        WorkspaceGenerationService::new(worktree_manager, wx_home)?
+            .with_template(options.template.clone());
+    if let Some(layout) = options.layout.clone() {
+        workspace_generation_service = workspace_generation_service.with_layout(layout);
+    }
+    if !options.formats.is_empty() {
+        workspace_generation_service =
+            workspace_generation_service.with_formats(options.formats.clone());
+    }
+
+    let group_service = GroupService::new()?;
+    let group_repos = group_service.repositories(&options.groups)?;
+
+    let worktree_selection = if options.all_repos
+        || !options.repos.is_empty()
+        || (!options.groups.is_empty() && options.branch.is_some())
+    {
+        select_from_flags(&repos, &group_repos, &options)?
+    } else {
+        select_interactively(
+            &repos,
+            &group_service.list()?,
+            &group_repos,
+            &workspace_generation_service,
+            options.base.as_deref(),
+            on_progress,
+        )?
+    };
+
+    workspace_generation_service.generate(&workspace_dir, worktree_selection, &workspace_name)
+}
+
+/// en: Build the selections from `--repo` / `--group` / `--all-repos` / `--branch`
+/// `group_repos` are the repositories of the groups, added after the `--repo` entries
+///
+/// ja: `--repo` / `--group` / `--all-repos` / `--branch` から選択を組み立てる
+/// `group_repos` はグループのリポジトリで、`--repo` の後に追加する
+fn select_from_flags(
+    repos: &[Repository],
+    group_repos: &[String],
+    options: &NewOptions,
+) -> Result<Vec<WorktreeSelection>, WxError> {
+    if options.all_repos {
+        let branch = options.branch.as_deref().ok_or_else(|| {
+            WxError::General("--all-repos requires --branch <branch>".to_string())
+        })?;
+        return Ok(repos
+            .iter()
+            .map(|repo| WorktreeSelection {
+                repo_name: repo.name.clone(),
+                branch: branch.to_string(),
+                base: options.base.clone(),
+                ..Default::default()
+            })
+            .collect());
+    }
+
+    let mut selections = options
+        .repos
+        .iter()
+        .map(|spec| {
+            let mut selection = WorktreeSelection::parse(spec, options.branch.as_deref())?;
+            if !repos.iter().any(|repo| repo.name == selection.repo_name) {
+                return Err(WxError::RepositoryNotFound(selection.repo_name));
+            }
+            selection.base = options.base.clone();
+            Ok(selection)
+        })
+        .collect::<Result<Vec<WorktreeSelection>, WxError>>()?;
+
+    for repo_name in group_repos {
+        if selections
+            .iter()
+            .any(|selection| selection.repo_name == *repo_name)
+        {
+            continue;
+        }
+        let branch = options
+            .branch
+            .as_deref()
+            .ok_or_else(|| WxError::General("--group requires --branch <branch>".to_string()))?;
+        selections.push(WorktreeSelection {
+            repo_name: repo_name.clone(),
+            branch: branch.to_string(),
+            base: options.base.clone(),
+            ..Default::default()
+        });
+    }
+
+    Ok(selections)
+}
+
+/// en: Ask for repositories and branches with dialoguer prompts
+/// The repositories of `group_repos` are preselected; without them, a group to preselect
+/// is asked first when there are groups
+///
+/// ja: dialoguerのプロンプトでリポジトリとブランチを選択させる
+/// `group_repos` のリポジトリは初期選択にする。無い場合、グループがあれば先に初期選択するグループを選択させる
+fn select_interactively<W, F>(
+    repos: &[Repository],
+    groups: &[RepositoryGroup],
+    group_repos: &[String],
+    workspace_generation_service: &WorkspaceGenerationService<W>,
+    base: Option<&str>,
+    on_progress: F,
+) -> Result<Vec<WorktreeSelection>, WxError>
+where
+    W: WorktreeManager,
+    F: Fn(usize, &str, &TransferProgress) + Sync,
+{
+    if !stdin().is_terminal() {
+        return Err(WxError::General(
+            "stdin is not a terminal. Use --repo <repo>:<branch>, --group <group> --branch <branch> or --all-repos --branch <branch> to create a workspace non-interactively".to_string(),
+        ));
+    }
+
+    let preselected_repos = if group_repos.is_empty() && !groups.is_empty() {
+        select_group(groups)?
+            .map(|group| group.repositories.clone())
+            .unwrap_or_default()
+    } else {
+        group_repos.to_vec()
+    };
+
     let repos_name: Vec<&str> = repos.iter().map(|repo| repo.name.as_str()).collect();
 
     let selected_repos = MultiSelect::new()
         .with_prompt("Select repositories")
         .items(&repos_name)
-        .interact();
+        .defaults(&preselected(repos, &preselected_repos))
+        .interact()
+        .map_err(|_| WxError::General("Repository selection was cancelled".to_string()))?;
+
+    if selected_repos.is_empty() {
+        return Err(WxError::General("No repositories selected".to_string()));
+    }
+
+    let selected_names: Vec<String> = selected_repos
+        .iter()
+        .map(|&idx| repos[idx].name.clone())
+        .collect();
+    fetch_selected(&selected_names, on_progress)?;
 
     let mut worktree_selection: Vec<WorktreeSelection> = Vec::new();
 
-    match selected_repos {
-        Ok(selected_repos) => {
-            if selected_repos.is_empty() {
-                return Err(WxError::General("No repositories selected".to_string()));
-            }
+    for idx in selected_repos {
+        let repo = &repos[idx];
+        let branches = workspace_generation_service.get_branches(&repo.name)?;
 
-            for idx in selected_repos {
-                let branch_name: String = Input::new()
-                    .with_prompt(format!("Branch for {name}", name = repos[idx].name))
-                    .validate_with(|input: &String| -> Result<(), &str> {
-                        let trimmed = input.trim();
+        let selection = match select_branch(&repo.name, &branches)? {
+            Some(branch) => WorktreeSelection {
+                repo_name: repo.name.clone(),
+                branch,
+                ..Default::default()
+            },
+            None => WorktreeSelection {
+                repo_name: repo.name.clone(),
+                branch: input_new_branch(&repo.name)?,
+                base: match base {
+                    Some(base) => Some(base.to_string()),
+                    None => Some(select_base(repo, &branches)?),
+                },
+                ..Default::default()
+            },
+        };
+        worktree_selection.push(selection);
+    }
 
-                        if trimmed.is_empty() {
-                            return Err("Branch name cannot be empty");
-                        }
+    Ok(worktree_selection)
+}
 
-                        Ok(())
-                    })
-                    .interact_text()?;
+/// en: Pick a group whose repositories are preselected. Returns `None` for the "(none)" entry
+///
+/// ja: 初期選択にするリポジトリのグループを選択させる（「(none)」が選ばれた場合は `None` を返却する）
+fn select_group(groups: &[RepositoryGroup]) -> Result<Option<&RepositoryGroup>, WxError> {
+    let selected = Select::new()
+        .with_prompt("Preselect the repositories of a group")
+        .items(group_items(groups))
+        .default(0)
+        .interact()
+        .map_err(|_| WxError::General("Group selection was cancelled".to_string()))?;
 
-                worktree_selection.push(WorktreeSelection {
-                    repo_name: repos[idx].name.clone(),
-                    branch: branch_name,
-                });
+    Ok(selected.checked_sub(1).map(|index| &groups[index]))
+}
+
+/// en: Group picker entries: "(none)" followed by each group with its repositories
+///
+/// ja: グループの選択肢（「(none)」の後に、各グループとそのリポジトリを並べる）
+fn group_items(groups: &[RepositoryGroup]) -> Vec<String> {
+    std::iter::once(NO_GROUP.to_string())
+        .chain(
+            groups
+                .iter()
+                .map(|group| format!("{} ({})", group.name, group.repositories.join(", "))),
+        )
+        .collect()
+}
+
+/// en: Checked state of each repository in the repository picker
+///
+/// ja: リポジトリ選択での各リポジトリの初期選択状態
+fn preselected(repos: &[Repository], repo_names: &[String]) -> Vec<bool> {
+    repos
+        .iter()
+        .map(|repo| repo_names.contains(&repo.name))
+        .collect()
+}
+
+/// en: Fetch the selected repositories concurrently so that new remote branches can be picked
+///
+/// ja: 新しいリモートブランチを選択できるよう、選択したリポジトリを並行してフェッチする
+fn fetch_selected<F>(repo_names: &[String], on_progress: F) -> Result<(), WxError>
+where
+    F: Fn(usize, &str, &TransferProgress) + Sync,
+{
+    let reports = RepositoryService::new()?.fetch(repo_names, default_jobs(), on_progress)?;
+
+    let failures: Vec<String> = reports
+        .into_iter()
+        .filter_map(|report| {
+            report
+                .error
+                .map(|error| format!("{}: {}", report.repo_name, error.message))
+        })
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(WxError::General(format!(
+            "Failed to fetch repositories: {}",
+            failures.join(", ")
+        )))
+    }
+}
+
+/// en: Pick a branch with fuzzy search. Returns `None` for the "create new branch…" entry
+///
+/// ja: ファジー検索でブランチを選択する（「create new branch…」が選ばれた場合は `None` を返却する）
+fn select_branch(repo_name: &str, branches: &[BranchInfo]) -> Result<Option<String>, WxError> {
+    let selected = FuzzySelect::new()
+        .with_prompt(format!("Branch for {repo_name}"))
+        .items(branch_items(branches))
+        .default(0)
+        .interact()
+        .map_err(|_| WxError::General("Branch selection was cancelled".to_string()))?;
+
+    Ok(branches.get(selected).map(|branch| branch.name.clone()))
+}
+
+/// en: Ask for the name of a new branch
+///
+/// ja: 新しいブランチ名を入力させる
+fn input_new_branch(repo_name: &str) -> Result<String, WxError> {
+    let branch_name: String = Input::new()
+        .with_prompt(format!("New branch for {repo_name}"))
+        .validate_with(|input: &String| -> Result<(), &str> {
+            let trimmed = input.trim();
+
+            if trimmed.is_empty() {
+                return Err("Branch name cannot be empty");
             }
 
-            let workspace_dir = get_current_dir()?.join(&workspace_name);
-            if workspace_dir.exists() {
-                return Err(WxError::General(format!(
-                    "Workspace directory '{workspace_name}' already exists"
-                )));
+            Ok(())
+        })
+        .interact_text()?;
+
+    Ok(branch_name.trim().to_string())
+}
+
+/// en: Pick the branch a new branch starts from, preselecting the repository's `default_base`
+///
+/// ja: 新しいブランチの作成元を選択させる（リポジトリの `default_base` を初期選択にする）
+fn select_base(repo: &Repository, branches: &[BranchInfo]) -> Result<String, WxError> {
+    if branches.is_empty() {
+        return Err(WxError::General(format!(
+            "{} has no branches to start a new branch from",
+            repo.name
+        )));
+    }
+
+    let items: Vec<&str> = branches.iter().map(|branch| branch.name.as_str()).collect();
+    let default = repo
+        .default_base
+        .as_deref()
+        .and_then(|default_base| {
+            let default_base = default_base.strip_prefix("origin/").unwrap_or(default_base);
+            branches
+                .iter()
+                .position(|branch| branch.name == default_base)
+        })
+        .unwrap_or(0);
+
+    let selected = FuzzySelect::new()
+        .with_prompt(format!("Base for the new branch of {}", repo.name))
+        .items(&items)
+        .default(default)
+        .interact()
+        .map_err(|_| WxError::General("Base selection was cancelled".to_string()))?;
+
+    Ok(branches[selected].name.clone())
+}
+
+/// en: Picker entries: one per branch, marking the default branch, followed by the
+/// "create new branch…" entry
+///
+/// ja: 選択肢（ブランチごとに1つ、デフォルトブランチには印を付け、末尾に「create new branch…」を置く）
+fn branch_items(branches: &[BranchInfo]) -> Vec<String> {
+    branches
+        .iter()
+        .map(|branch| {
+            if branch.is_default {
+                format!("{} (default)", branch.name)
+            } else {
+                branch.name.clone()
             }
-            fs::create_dir_all(&workspace_dir)?;
+        })
+        .chain(std::iter::once(CREATE_NEW_BRANCH.to_string()))
+        .collect()
+}
 
-            let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
-            let worktree_manager = DefaultWorktreeManager;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_helpers::create_test_repository;
 
-            WorkspaceGenerationService::new(worktree_manager, wx_home)?.generate(
-                &workspace_dir,
-                worktree_selection,
-                &workspace_name,
-            )?;
-        }
-        Err(_) => {
-            return Err(WxError::General(
-                "Repository selection was cancelled".to_string(),
-            ));
-        }
+    fn repos() -> Vec<Repository> {
+        vec![
+            create_test_repository("frontend"),
+            create_test_repository("backend"),
+        ]
+    }
+
+    #[test]
+    fn test_select_from_flags_repo_specs() {
+        let options = NewOptions {
+            repos: vec!["frontend:feature/x".to_string(), "backend".to_string()],
+            branch: Some("main".to_string()),
+            ..NewOptions::default()
+        };
+
+        let selection = select_from_flags(&repos(), &[], &options).unwrap();
+
+        assert_eq!(selection.len(), 2);
+        assert_eq!(selection[0].branch, "feature/x");
+        assert_eq!(selection[1].repo_name, "backend");
+        assert_eq!(selection[1].branch, "main");
+        assert!(selection.iter().all(|s| s.base.is_none()));
+    }
+
+    #[test]
+    fn test_select_from_flags_base() {
+        let options = NewOptions {
+            all_repos: true,
+            branch: Some("feature/x".to_string()),
+            base: Some("origin/develop".to_string()),
+            ..NewOptions::default()
+        };
+
+        let selection = select_from_flags(&repos(), &[], &options).unwrap();
+
+        assert!(selection
+            .iter()
+            .all(|s| s.base.as_deref() == Some("origin/develop")));
     }
 
-    Ok(())
+    #[test]
+    fn test_select_from_flags_all_repos() {
+        let options = NewOptions {
+            all_repos: true,
+            branch: Some("feature/x".to_string()),
+            ..NewOptions::default()
+        };
+
+        let selection = select_from_flags(&repos(), &[], &options).unwrap();
+
+        assert_eq!(selection.len(), 2);
+        assert!(selection.iter().all(|s| s.branch == "feature/x"));
+    }
+
+    #[test]
+    fn test_select_from_flags_errors() {
+        let unknown_repo = NewOptions {
+            repos: vec!["mobile:main".to_string()],
+            ..NewOptions::default()
+        };
+        assert!(matches!(
+            select_from_flags(&repos(), &[], &unknown_repo),
+            Err(WxError::RepositoryNotFound(_))
+        ));
+
+        let missing_branch = NewOptions {
+            all_repos: true,
+            ..NewOptions::default()
+        };
+        assert!(select_from_flags(&repos(), &[], &missing_branch).is_err());
+    }
+
+    #[test]
+    fn test_select_from_flags_groups() {
+        let options = NewOptions {
+            repos: vec!["backend:hotfix".to_string()],
+            groups: vec!["web".to_string()],
+            branch: Some("feature/x".to_string()),
+            ..NewOptions::default()
+        };
+        let group_repos = vec!["frontend".to_string(), "backend".to_string()];
+
+        let selection = select_from_flags(&repos(), &group_repos, &options).unwrap();
+
+        // --repo entries win over the branch given for the group
+        assert_eq!(selection.len(), 2);
+        assert_eq!(selection[0].repo_name, "backend");
+        assert_eq!(selection[0].branch, "hotfix");
+        assert_eq!(selection[1].repo_name, "frontend");
+        assert_eq!(selection[1].branch, "feature/x");
+
+        let missing_branch = NewOptions {
+            repos: vec!["backend:hotfix".to_string()],
+            groups: vec!["web".to_string()],
+            ..NewOptions::default()
+        };
+        assert!(select_from_flags(&repos(), &group_repos, &missing_branch).is_err());
+    }
+
+    #[test]
+    fn test_group_preselection() {
+        let groups = vec![RepositoryGroup::new(
+            "web".to_string(),
+            vec!["frontend".to_string(), "backend".to_string()],
+        )];
+
+        assert_eq!(
+            group_items(&groups),
+            vec![NO_GROUP, "web (frontend, backend)"]
+        );
+        assert_eq!(
+            preselected(&repos(), &["backend".to_string()]),
+            vec![false, true]
+        );
+        assert_eq!(preselected(&repos(), &[]), vec![false, false]);
+    }
+
+    #[test]
+    fn test_branch_items() {
+        let branches = vec![
+            BranchInfo {
+                name: "main".to_string(),
+                is_default: true,
+            },
+            BranchInfo {
+                name: "feature/x".to_string(),
+                is_default: false,
+            },
+        ];
+
+        assert_eq!(
+            branch_items(&branches),
+            vec!["main (default)", "feature/x", CREATE_NEW_BRANCH]
+        );
+        assert_eq!(branch_items(&[]), vec![CREATE_NEW_BRANCH]);
+    }
 }
@@ -0,0 +1,15 @@
+use crate::{
+    models::{editor::OpenTarget, WxError},
+    services::WorkspaceOpenService,
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx open` command for the named workspace, or the one containing
+/// the current directory
+///
+/// ja: `wx open` コマンドを、指定したworkspace（省略時はカレントディレクトリを含むworkspace）に対して実行する
+pub fn execute(workspace: Option<&str>, editor: Option<&str>) -> Result<OpenTarget, WxError> {
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+
+    WorkspaceOpenService::new(&wx_home).open(workspace, &get_current_dir()?, editor)
+}
@@ -1,7 +1,60 @@
-use crate::{models::WxError, services::RepositoryService};
+use crate::{
+    models::{
+        error::ErrorReport,
+        transfer::{RegisterReport, TransferProgress},
+        WxError,
+    },
+    services::RepositoryService,
+    utils::default_jobs,
+};
 
-pub fn execute(url: &str) -> Result<(), WxError> {
+/// en: Execute the `wx register` command, cloning the URLs concurrently and optionally recording
+/// the default base of each registered repository. `name` registers a single URL under an alias
+/// The transfer of each clone is passed to `on_progress` as it is received
+///
+/// ja: `wx register` コマンドを実行し、各URLを並行してcloneする。指定があれば登録したリポジトリのデフォルトbaseを記録する
+/// `name` を指定すると1つのURLをその名前で登録する
+/// 各cloneの転送の進捗は、受信の都度 `on_progress` に渡される
+pub fn execute<F>(
+    urls: &[String],
+    name: Option<&str>,
+    default_base: Option<String>,
+    jobs: Option<usize>,
+    on_progress: F,
+) -> Result<Vec<RegisterReport>, WxError>
+where
+    F: Fn(usize, &str, &TransferProgress) + Sync,
+{
     let mut repository_service = RepositoryService::new()?;
-    repository_service.register(url)?;
-    Ok(())
+    let results = repository_service.register_all(
+        urls,
+        name,
+        jobs.unwrap_or_else(default_jobs),
+        on_progress,
+    )?;
+
+    urls.iter()
+        .zip(results)
+        .map(|(url, result)| {
+            Ok(match result {
+                Ok(mut repository) => {
+                    if default_base.is_some() {
+                        repository_service
+                            .set_default_base(&repository.name, default_base.clone())?;
+                        repository.default_base = default_base.clone();
+                    }
+                    RegisterReport {
+                        url: url.clone(),
+                        repository: Some(repository),
+                        error: None,
+                    }
+                }
+                Err(e) => RegisterReport {
+                    url: url.clone(),
+                    repository: None,
+                    error: Some(ErrorReport::from(&e)),
+                },
+            })
+        })
+        .collect()
 }
@@ -0,0 +1,26 @@
+use crate::{
+    infrastructure::git::DefaultWorktreeManager,
+    models::{workspace::WorkspaceMember, WxError},
+    services::{WorkspaceGenerationService, WorkspaceRegistryService},
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx remove` command to prune the worktree of `<repo>[:<branch>]` from a workspace
+///
+/// ja: `wx remove` コマンドを実行し、`<repo>[:<branch>]` のworktreeをworkspaceから削除する
+pub fn execute(workspace: &str, spec: &str, force: bool) -> Result<WorkspaceMember, WxError> {
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+    let workspace = WorkspaceRegistryService::with_base_dir(&wx_home)
+        .resolve(workspace, &get_current_dir()?)?;
+
+    let (repo_name, branch) = match spec.split_once(':') {
+        Some((repo_name, branch)) => (repo_name, Some(branch)),
+        None => (spec, None),
+    };
+    let member = workspace.find_member(repo_name, branch)?.clone();
+
+    WorkspaceGenerationService::new(DefaultWorktreeManager, wx_home)?
+        .remove_worktree(&workspace, &member, force)?;
+
+    Ok(member)
+}
@@ -0,0 +1,52 @@
+use crate::models::shell::Shell;
+
+/// en: Execute the `wx shell-init` command, returning the script to evaluate in the shell's rc file
+/// The script wraps `wx` in a function so that `wx cd` changes the directory of the shell,
+/// and registers the completion of commands, repositories, workspaces and branches
+///
+/// ja: `wx shell-init` コマンドを実行し、シェルのrcファイルで評価するスクリプトを返却する
+/// `wx cd` がシェルのディレクトリを変更できるよう `wx` を関数でラップし、
+/// コマンド・リポジトリ・workspace・ブランチの補完を登録する
+pub fn execute(shell: Shell) -> String {
+    match shell {
+        Shell::Bash | Shell::Zsh => format!(
+            r#"# wx shell integration ({shell})
+wx() {{
+    if [ "$1" = "cd" ]; then
+        shift
+        local __wx_dir
+        __wx_dir="$(command wx cd "$@")" && builtin cd -- "$__wx_dir"
+    else
+        command wx "$@"
+    fi
+}}
+source <(COMPLETE={shell} command wx)
+"#
+        ),
+        Shell::Fish => r#"# wx shell integration (fish)
+function wx
+    if test "$argv[1]" = cd
+        set -l __wx_dir (command wx cd $argv[2..-1]); and builtin cd -- $__wx_dir
+    else
+        command wx $argv
+    end
+end
+COMPLETE=fish command wx | source
+"#
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute() {
+        let bash = execute(Shell::Bash);
+        assert!(bash.contains("command wx cd \"$@\""));
+        assert!(bash.contains("source <(COMPLETE=bash command wx)"));
+        assert!(execute(Shell::Zsh).contains("COMPLETE=zsh"));
+        assert!(execute(Shell::Fish).contains("COMPLETE=fish command wx | source"));
+    }
+}
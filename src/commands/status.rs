@@ -0,0 +1,18 @@
+use crate::{
+    infrastructure::git::DefaultWorktreeManager,
+    models::{status::WorkspaceStatus, WxError},
+    services::WorkspaceStatusService,
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx status` command for the named workspace, or the one containing
+/// the current directory
+///
+/// ja: `wx status` コマンドを、指定したworkspace（省略時はカレントディレクトリを含むworkspace）に対して実行する
+pub fn execute(workspace: Option<&str>, groups: Vec<String>) -> Result<WorkspaceStatus, WxError> {
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+
+    WorkspaceStatusService::new(DefaultWorktreeManager, wx_home)
+        .with_groups(groups)
+        .status(workspace, &get_current_dir()?)
+}
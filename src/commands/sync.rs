@@ -0,0 +1,29 @@
+use crate::{
+    infrastructure::git::DefaultWorktreeManager,
+    models::{sync::SyncResult, transfer::TransferProgress, WxError},
+    services::WorkspaceSyncService,
+    utils::{get_current_dir, get_wx_home},
+};
+
+/// en: Execute the `wx sync` command for the named workspace, or the one containing
+/// the current directory
+///
+/// The transfer of each fetch is passed to `on_progress` as it is received
+///
+/// ja: `wx sync` コマンドを、指定したworkspace（省略時はカレントディレクトリを含むworkspace）に対して実行する
+/// 各フェッチの転送の進捗は、受信の都度 `on_progress` に渡される
+pub fn execute<F>(
+    workspace: Option<&str>,
+    groups: Vec<String>,
+    rebase: bool,
+    on_progress: F,
+) -> Result<SyncResult, WxError>
+where
+    F: Fn(usize, &str, &TransferProgress) + Sync,
+{
+    let wx_home = get_wx_home().ok_or(WxError::HomeDirNotFound)?;
+
+    WorkspaceSyncService::new(DefaultWorktreeManager, wx_home)
+        .with_groups(groups)
+        .sync(workspace, &get_current_dir()?, rebase, on_progress)
+}
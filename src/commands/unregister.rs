@@ -1,7 +1,11 @@
+use std::path::PathBuf;
+
 use crate::{models::WxError, services::RepositoryService};
 
-pub fn execute(name: &str) -> Result<(), WxError> {
+/// en: Execute the `wx unregister` command and return the workspaces left orphaned
+///
+/// ja: `wx unregister` コマンドを実行し、孤立したworkspaceを返却する
+pub fn execute(name: &str, force: bool) -> Result<Vec<PathBuf>, WxError> {
     let mut repository_service = RepositoryService::new()?;
-    repository_service.unregister(name)?;
-    Ok(())
+    repository_service.unregister(name, force)
 }
@@ -0,0 +1,12 @@
+use crate::{
+    models::{workspace::WorkspaceEntry, WxError},
+    services::WorkspaceRegistryService,
+    utils::get_current_dir,
+};
+
+/// en: Execute the `wx workspace show` command to resolve a workspace by name or path
+///
+/// ja: `wx workspace show` コマンドを実行し、名前またはパスからworkspaceを解決する
+pub fn show(name: &str) -> Result<WorkspaceEntry, WxError> {
+    WorkspaceRegistryService::new()?.resolve(name, &get_current_dir()?)
+}
@@ -0,0 +1,11 @@
+use crate::{
+    models::{workspace::WorkspaceEntry, WxError},
+    services::WorkspaceRegistryService,
+};
+
+/// en: Execute the `wx workspaces` command to list registered workspaces
+///
+/// ja: `wx workspaces` コマンドを実行し、登録済みworkspaceを一覧表示する
+pub fn execute() -> Result<Vec<WorkspaceEntry>, WxError> {
+    WorkspaceRegistryService::new()?.list()
+}
@@ -100,6 +100,7 @@ mod tests {
                 "git@github.com:org/test.git".to_string(),
                 "/home/user/.wx/test.git".to_string(),
             )],
+            ..Config::default()
         }
     }
 
@@ -0,0 +1,274 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{remove_file, WorkspaceWriter};
+use crate::models::{
+    workspace::{WorkspaceFile, WorkspaceFolder},
+    WxError,
+};
+
+/// en: An `.idea` project with one module per worktree, opened by IntelliJ IDEA, WebStorm, RustRover and the other JetBrains IDEs
+///
+/// ja: worktreeごとに1つのモジュールを持つ `.idea` プロジェクト（IntelliJ IDEA・WebStorm・RustRoverなどのJetBrains IDEで開く）
+pub struct JetBrainsWriter;
+
+impl JetBrainsWriter {
+    fn idea_dir(workspace_dir: &Path) -> PathBuf {
+        workspace_dir.join(".idea")
+    }
+
+    /// Module name of a worktree folder, which is also the file name of its `.iml`
+    fn module_name(folder: &WorkspaceFolder) -> String {
+        Path::new(&folder.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| folder.path.clone())
+    }
+
+    /// Directory of a worktree folder, relative to `base` unless the folder path is absolute
+    fn folder_url(folder: &WorkspaceFolder, base: &str) -> String {
+        if Path::new(&folder.path).is_absolute() {
+            escape(&folder.path)
+        } else {
+            format!("{base}/{}", escape(&folder.path))
+        }
+    }
+
+    fn modules_xml(workspace_file: &WorkspaceFile) -> String {
+        let modules: String = workspace_file
+            .folders
+            .iter()
+            .map(|folder| {
+                let iml = format!(
+                    "$PROJECT_DIR$/.idea/{}.iml",
+                    escape(&Self::module_name(folder))
+                );
+                format!("      <module fileurl=\"file://{iml}\" filepath=\"{iml}\" />\n")
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"4\">\n  <component name=\"ProjectModuleManager\">\n    <modules>\n{modules}    </modules>\n  </component>\n</project>\n"
+        )
+    }
+
+    fn vcs_xml(workspace_file: &WorkspaceFile) -> String {
+        let mappings: String = workspace_file
+            .folders
+            .iter()
+            .map(|folder| {
+                format!(
+                    "    <mapping directory=\"{}\" vcs=\"Git\" />\n",
+                    Self::folder_url(folder, "$PROJECT_DIR$")
+                )
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"4\">\n  <component name=\"VcsDirectoryMappings\">\n{mappings}  </component>\n</project>\n"
+        )
+    }
+
+    /// Module files listed in the `modules.xml` written by wx
+    fn listed_modules(idea_dir: &Path) -> Vec<PathBuf> {
+        const PREFIX: &str = "filepath=\"$PROJECT_DIR$/.idea/";
+        let Ok(modules_xml) = fs::read_to_string(idea_dir.join("modules.xml")) else {
+            return Vec::new();
+        };
+        modules_xml
+            .split(PREFIX)
+            .skip(1)
+            .filter_map(|rest| {
+                rest.split_once('"')
+                    .map(|(file_name, _)| unescape(file_name))
+            })
+            // only plain `.iml` file names, never a path leaving `.idea`
+            .filter(|file_name| file_name.ends_with(".iml") && !file_name.contains(['/', '\\']))
+            .map(|file_name| idea_dir.join(file_name))
+            .collect()
+    }
+
+    fn module_iml(folder: &WorkspaceFolder) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<module type=\"WEB_MODULE\" version=\"4\">\n  <component name=\"NewModuleRootManager\" inherit-compiler-output=\"true\">\n    <exclude-output />\n    <content url=\"file://{}\" />\n    <orderEntry type=\"inheritedJdk\" />\n    <orderEntry type=\"sourceFolder\" forTests=\"false\" />\n  </component>\n</module>\n",
+            Self::folder_url(folder, "$MODULE_DIR$/..")
+        )
+    }
+}
+
+impl WorkspaceWriter for JetBrainsWriter {
+    fn paths(
+        &self,
+        workspace_dir: &Path,
+        _workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Vec<PathBuf> {
+        let idea_dir = Self::idea_dir(workspace_dir);
+        let mut paths = vec![
+            idea_dir.join(".name"),
+            idea_dir.join("modules.xml"),
+            idea_dir.join("vcs.xml"),
+        ];
+        paths.extend(
+            workspace_file
+                .folders
+                .iter()
+                .map(|folder| idea_dir.join(format!("{}.iml", Self::module_name(folder)))),
+        );
+        paths
+    }
+
+    fn project_path(&self, workspace_dir: &Path, _workspace_name: &str) -> PathBuf {
+        workspace_dir.to_path_buf()
+    }
+
+    /// `modules.xml` and `vcs.xml` are regenerated from the worktrees;
+    /// module files and the rest of `.idea` are left as the IDE wrote them
+    fn write(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
+        let idea_dir = Self::idea_dir(workspace_dir);
+        fs::create_dir_all(&idea_dir)?;
+
+        if !idea_dir.join(".name").exists() {
+            fs::write(idea_dir.join(".name"), workspace_name)?;
+        }
+        fs::write(
+            idea_dir.join("modules.xml"),
+            Self::modules_xml(workspace_file),
+        )?;
+        fs::write(idea_dir.join("vcs.xml"), Self::vcs_xml(workspace_file))?;
+
+        for folder in &workspace_file.folders {
+            let iml = idea_dir.join(format!("{}.iml", Self::module_name(folder)));
+            if !iml.exists() {
+                fs::write(iml, Self::module_iml(folder))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes only the files wx writes: `.name`, `modules.xml`, `vcs.xml` and the modules listed
+    /// in `modules.xml`. The IDE's own files are kept, and `.idea` is removed once it is empty
+    fn remove(&self, workspace_dir: &Path, _workspace_name: &str) -> Result<Vec<PathBuf>, WxError> {
+        let idea_dir = Self::idea_dir(workspace_dir);
+        if !idea_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut removed = Vec::new();
+        for module in Self::listed_modules(&idea_dir) {
+            remove_file(module, &mut removed)?;
+        }
+        for file_name in [".name", "modules.xml", "vcs.xml"] {
+            remove_file(idea_dir.join(file_name), &mut removed)?;
+        }
+
+        if fs::read_dir(&idea_dir)?.next().is_none() {
+            fs::remove_dir(&idea_dir)?;
+        }
+        Ok(removed)
+    }
+}
+
+/// Escape a value for an XML attribute
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Reverse `escape`
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_helpers::setup_test_dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_write_project() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let workspace_file =
+            WorkspaceFile::new(vec!["frontend".to_string(), "backend".to_string()]);
+
+        JetBrainsWriter
+            .write(dir.path(), "wx", &workspace_file)
+            .unwrap();
+
+        let idea_dir = dir.path().join(".idea");
+        assert_eq!(fs::read_to_string(idea_dir.join(".name")).unwrap(), "wx");
+        let modules = fs::read_to_string(idea_dir.join("modules.xml")).unwrap();
+        assert!(modules.contains("filepath=\"$PROJECT_DIR$/.idea/frontend.iml\""));
+        assert!(modules.contains("filepath=\"$PROJECT_DIR$/.idea/backend.iml\""));
+        let vcs = fs::read_to_string(idea_dir.join("vcs.xml")).unwrap();
+        assert!(vcs.contains("<mapping directory=\"$PROJECT_DIR$/backend\" vcs=\"Git\" />"));
+        let iml = fs::read_to_string(idea_dir.join("frontend.iml")).unwrap();
+        assert!(iml.contains("<content url=\"file://$MODULE_DIR$/../frontend\" />"));
+    }
+
+    #[test]
+    fn test_write_keeps_module_files() {
+        let (dir, _base_dir) = setup_test_dirs();
+        JetBrainsWriter
+            .write(
+                dir.path(),
+                "wx",
+                &WorkspaceFile::new(vec!["frontend".to_string()]),
+            )
+            .unwrap();
+        let iml = dir.path().join(".idea/frontend.iml");
+        fs::write(&iml, "<module />").unwrap();
+
+        JetBrainsWriter
+            .write(
+                dir.path(),
+                "wx",
+                &WorkspaceFile::new(vec!["frontend".to_string(), "a&b".to_string()]),
+            )
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(&iml).unwrap(), "<module />");
+        let vcs = fs::read_to_string(dir.path().join(".idea/vcs.xml")).unwrap();
+        assert!(vcs.contains("$PROJECT_DIR$/a&amp;b"));
+
+        assert!(JetBrainsWriter.exists(dir.path(), "wx"));
+        let removed = JetBrainsWriter.remove(dir.path(), "wx").unwrap();
+        assert_eq!(removed.len(), 5);
+        assert!(!dir.path().join(".idea").exists());
+    }
+
+    #[test]
+    fn test_remove_keeps_ide_files() {
+        let (dir, _base_dir) = setup_test_dirs();
+        JetBrainsWriter
+            .write(
+                dir.path(),
+                "wx",
+                &WorkspaceFile::new(vec!["frontend".to_string()]),
+            )
+            .unwrap();
+        let idea_dir = dir.path().join(".idea");
+        fs::write(idea_dir.join("workspace.xml"), "<project />").unwrap();
+        fs::write(idea_dir.join("other.iml"), "<module />").unwrap();
+
+        JetBrainsWriter.remove(dir.path(), "wx").unwrap();
+
+        assert!(!idea_dir.join("frontend.iml").exists());
+        assert!(!idea_dir.join("modules.xml").exists());
+        assert!(idea_dir.join("workspace.xml").exists());
+        assert!(idea_dir.join("other.iml").exists());
+    }
+}
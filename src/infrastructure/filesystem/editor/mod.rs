@@ -0,0 +1,109 @@
+mod jetbrains;
+mod sublime;
+mod vscode;
+mod zed;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+use crate::models::{
+    workspace::{WorkspaceFile, WorkspaceFormat, WorkspaceSettings},
+    WxError,
+};
+
+pub use jetbrains::JetBrainsWriter;
+pub use sublime::SublimeWriter;
+pub use vscode::VsCodeWriter;
+pub use zed::ZedWriter;
+
+/// en: Writes the project files of one editor format for a workspace
+///
+/// ja: workspaceに対して1つのエディタ形式のプロジェクトファイルを書き出す
+pub trait WorkspaceWriter {
+    /// en: Files this format writes for the workspace
+    ///
+    /// ja: この形式がworkspaceに書き出すファイル
+    fn paths(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Vec<PathBuf>;
+
+    /// en: Path the editor opens: the project file, or the workspace directory
+    ///
+    /// ja: エディタで開くパス（プロジェクトファイル、またはworkspaceディレクトリ）
+    fn project_path(&self, workspace_dir: &Path, workspace_name: &str) -> PathBuf;
+
+    /// en: Write the files from the workspace file model
+    /// Existing files are updated, keeping user edits where the format allows it
+    ///
+    /// ja: workspaceファイルのモデルからファイルを書き出す
+    /// 既存のファイルは更新し、形式が許す限りユーザーの編集を保持する
+    fn write(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError>;
+
+    /// en: Remove the files of this format, returning the removed paths
+    ///
+    /// ja: この形式のファイルを削除し、削除したパスを返却する
+    fn remove(&self, workspace_dir: &Path, workspace_name: &str) -> Result<Vec<PathBuf>, WxError>;
+
+    /// en: Check if any file of this format exists for the workspace
+    ///
+    /// ja: workspaceにこの形式のファイルが存在するか確認
+    fn exists(&self, workspace_dir: &Path, workspace_name: &str) -> bool {
+        self.paths(
+            workspace_dir,
+            workspace_name,
+            &WorkspaceFile::new(Vec::new()),
+        )
+        .iter()
+        .any(|path| path.exists())
+    }
+}
+
+/// en: Returns the writer of an editor format
+///
+/// ja: エディタ形式に対応するwriterを返却する
+pub fn workspace_writer(format: WorkspaceFormat) -> Box<dyn WorkspaceWriter> {
+    match format {
+        WorkspaceFormat::VsCode => Box::new(VsCodeWriter),
+        WorkspaceFormat::Zed => Box::new(ZedWriter),
+        WorkspaceFormat::Sublime => Box::new(SublimeWriter),
+        WorkspaceFormat::JetBrains => Box::new(JetBrainsWriter),
+    }
+}
+
+/// Remove a file when it exists, recording it in `removed`
+fn remove_file(path: PathBuf, removed: &mut Vec<PathBuf>) -> Result<(), WxError> {
+    if path.exists() {
+        fs::remove_file(&path)?;
+        removed.push(path);
+    }
+    Ok(())
+}
+
+/// A VS Code setting key, the editor's key, and the conversion of the value
+type SettingMapping = (&'static str, &'static str, fn(&Value) -> Option<Value>);
+
+/// Translate VS Code settings that have a counterpart in another editor
+fn translate_settings(
+    settings: &WorkspaceSettings,
+    mapping: &[SettingMapping],
+) -> Map<String, Value> {
+    mapping
+        .iter()
+        .filter_map(|(vscode_key, key, convert)| {
+            let value = convert(settings.values.get(*vscode_key)?)?;
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
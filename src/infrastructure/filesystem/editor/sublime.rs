@@ -0,0 +1,194 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Map, Value};
+
+use super::{remove_file, translate_settings, WorkspaceWriter};
+use crate::{
+    infrastructure::filesystem::jsonc,
+    models::{workspace::WorkspaceFile, WxError},
+};
+
+/// en: `<name>.sublime-project`, listing the worktrees as folders
+///
+/// ja: worktreeをフォルダとして並べる `<name>.sublime-project`
+pub struct SublimeWriter;
+
+impl SublimeWriter {
+    fn file_path(workspace_dir: &Path, workspace_name: &str) -> PathBuf {
+        workspace_dir.join(format!("{workspace_name}.sublime-project"))
+    }
+
+    /// Folders of the project, keeping the keys the user added to folders that stay
+    fn folders(workspace_file: &WorkspaceFile, existing: Option<&Value>) -> Value {
+        let existing = existing.and_then(Value::as_array);
+        let folders = workspace_file
+            .folders
+            .iter()
+            .map(|folder| {
+                let kept = existing.and_then(|existing| {
+                    existing
+                        .iter()
+                        .find(|value| value["path"].as_str() == Some(folder.path.as_str()))
+                });
+                match kept {
+                    Some(kept) => kept.clone(),
+                    None => {
+                        let mut value = Map::new();
+                        value.insert("path".to_string(), json!(folder.path));
+                        if let Some(name) = &folder.name {
+                            value.insert("name".to_string(), json!(name));
+                        }
+                        Value::Object(value)
+                    }
+                }
+            })
+            .collect();
+        Value::Array(folders)
+    }
+}
+
+impl WorkspaceWriter for SublimeWriter {
+    fn paths(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        _workspace_file: &WorkspaceFile,
+    ) -> Vec<PathBuf> {
+        vec![Self::file_path(workspace_dir, workspace_name)]
+    }
+
+    fn project_path(&self, workspace_dir: &Path, workspace_name: &str) -> PathBuf {
+        Self::file_path(workspace_dir, workspace_name)
+    }
+
+    /// An existing project only has its `folders` updated, in place
+    fn write(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
+        let path = Self::file_path(workspace_dir, workspace_name);
+
+        if let Ok(original) = fs::read_to_string(&path) {
+            if let Ok(mut project) = serde_json::from_str::<Value>(&jsonc::strip(&original)?) {
+                if let Some(project_map) = project.as_object_mut() {
+                    let folders = Self::folders(workspace_file, project_map.get("folders"));
+                    project_map.insert("folders".to_string(), folders);
+                    let updated = jsonc::update(&original, &project)
+                        .map_or_else(|| serde_json::to_string_pretty(&project), Ok)?;
+                    fs::write(&path, updated)?;
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut project = Map::new();
+        project.insert("folders".to_string(), Self::folders(workspace_file, None));
+        let settings = translate_settings(
+            &workspace_file.settings,
+            &[
+                ("editor.tabSize", "tab_size", |value| Some(value.clone())),
+                ("editor.insertSpaces", "translate_tabs_to_spaces", |value| {
+                    Some(value.clone())
+                }),
+                ("editor.rulers", "rulers", |value| Some(value.clone())),
+                (
+                    "files.trimTrailingWhitespace",
+                    "trim_trailing_white_space_on_save",
+                    |value| Some(value.clone()),
+                ),
+                (
+                    "files.insertFinalNewline",
+                    "ensure_newline_at_eof_on_save",
+                    |value| Some(value.clone()),
+                ),
+            ],
+        );
+        if !settings.is_empty() {
+            project.insert("settings".to_string(), Value::Object(settings));
+        }
+        fs::write(&path, serde_json::to_string_pretty(&project)?)?;
+        Ok(())
+    }
+
+    fn remove(&self, workspace_dir: &Path, workspace_name: &str) -> Result<Vec<PathBuf>, WxError> {
+        let mut removed = Vec::new();
+        remove_file(Self::file_path(workspace_dir, workspace_name), &mut removed)?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::workspace::{WorkspaceFolder, WorkspaceSettings},
+        utils::test_helpers::setup_test_dirs,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_write_new_project() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let settings: WorkspaceSettings =
+            serde_json::from_value(json!({"editor.tabSize": 2, "editor.insertSpaces": true}))
+                .unwrap();
+        let mut workspace_file =
+            WorkspaceFile::with_settings(vec!["frontend".to_string()], settings);
+        workspace_file
+            .folders
+            .push(WorkspaceFolder::for_worktree("backend-feature", "backend"));
+
+        SublimeWriter
+            .write(dir.path(), "wx", &workspace_file)
+            .unwrap();
+
+        let content = fs::read_to_string(dir.path().join("wx.sublime-project")).unwrap();
+        let project: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(
+            project,
+            json!({
+                "folders": [
+                    {"path": "frontend"},
+                    {"path": "backend-feature", "name": "backend"},
+                ],
+                "settings": {"tab_size": 2, "translate_tabs_to_spaces": true},
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_updates_folders_in_place() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let path = dir.path().join("wx.sublime-project");
+        fs::write(
+            &path,
+            "{\n  // worktrees\n  \"folders\": [\n    { \"path\": \"frontend\", \"folder_exclude_patterns\": [\"dist\"] },\n  ],\n  \"settings\": { \"tab_size\": 8 },\n}\n",
+        )
+        .unwrap();
+
+        let workspace_file =
+            WorkspaceFile::new(vec!["frontend".to_string(), "backend".to_string()]);
+        SublimeWriter
+            .write(dir.path(), "wx", &workspace_file)
+            .unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("// worktrees"));
+        let project: Value = serde_json::from_str(&jsonc::strip(&content).unwrap()).unwrap();
+        assert_eq!(
+            project["folders"],
+            json!([
+                {"path": "frontend", "folder_exclude_patterns": ["dist"]},
+                {"path": "backend"},
+            ])
+        );
+        assert_eq!(project["settings"], json!({"tab_size": 8}));
+
+        assert_eq!(SublimeWriter.remove(dir.path(), "wx").unwrap(), vec![path]);
+    }
+}
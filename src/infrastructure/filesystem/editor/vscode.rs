@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use super::{remove_file, WorkspaceWriter};
+use crate::{
+    infrastructure::filesystem::WorkspaceFileManager,
+    models::{workspace::WorkspaceFile, WxError},
+};
+
+/// en: `<name>.code-workspace`, opened by VS Code, Cursor and Kiro
+///
+/// ja: VS Code・Cursor・Kiroで開く `<name>.code-workspace`
+pub struct VsCodeWriter;
+
+impl WorkspaceWriter for VsCodeWriter {
+    fn paths(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        _workspace_file: &WorkspaceFile,
+    ) -> Vec<PathBuf> {
+        vec![workspace_dir.join(format!("{workspace_name}.code-workspace"))]
+    }
+
+    fn project_path(&self, workspace_dir: &Path, workspace_name: &str) -> PathBuf {
+        workspace_dir.join(format!("{workspace_name}.code-workspace"))
+    }
+
+    fn write(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
+        WorkspaceFileManager.write(
+            &workspace_dir.join(format!("{workspace_name}.code-workspace")),
+            workspace_file,
+        )
+    }
+
+    fn remove(&self, workspace_dir: &Path, workspace_name: &str) -> Result<Vec<PathBuf>, WxError> {
+        let mut removed = Vec::new();
+        remove_file(
+            workspace_dir.join(format!("{workspace_name}.code-workspace")),
+            &mut removed,
+        )?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::utils::test_helpers::setup_test_dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_write_and_remove() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let workspace_file = WorkspaceFile::new(vec!["frontend".to_string()]);
+
+        VsCodeWriter
+            .write(dir.path(), "wx", &workspace_file)
+            .unwrap();
+
+        let path = dir.path().join("wx.code-workspace");
+        assert!(fs::read_to_string(&path).unwrap().contains("frontend"));
+        assert!(VsCodeWriter.exists(dir.path(), "wx"));
+        assert_eq!(VsCodeWriter.remove(dir.path(), "wx").unwrap(), vec![path]);
+        assert!(!VsCodeWriter.exists(dir.path(), "wx"));
+    }
+}
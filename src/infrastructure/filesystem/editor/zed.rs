@@ -0,0 +1,129 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+
+use super::{remove_file, translate_settings, WorkspaceWriter};
+use crate::models::{workspace::WorkspaceFile, WxError};
+
+/// en: `.zed/settings.json` in the workspace directory, which Zed opens as a project
+/// Zed lists the worktrees from the directory itself, so only settings are written
+///
+/// ja: Zedがプロジェクトとして開くworkspaceディレクトリの `.zed/settings.json`
+/// worktreeはディレクトリから表示されるため、settingsのみを書き出す
+pub struct ZedWriter;
+
+impl ZedWriter {
+    fn settings_path(workspace_dir: &Path) -> PathBuf {
+        workspace_dir.join(".zed").join("settings.json")
+    }
+}
+
+impl WorkspaceWriter for ZedWriter {
+    fn paths(
+        &self,
+        workspace_dir: &Path,
+        _workspace_name: &str,
+        _workspace_file: &WorkspaceFile,
+    ) -> Vec<PathBuf> {
+        vec![Self::settings_path(workspace_dir)]
+    }
+
+    fn project_path(&self, workspace_dir: &Path, _workspace_name: &str) -> PathBuf {
+        workspace_dir.to_path_buf()
+    }
+
+    /// The settings do not depend on the worktrees, so an existing file is left as the user edited it
+    fn write(
+        &self,
+        workspace_dir: &Path,
+        _workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
+        let path = Self::settings_path(workspace_dir);
+        if path.exists() {
+            return Ok(());
+        }
+
+        let settings = translate_settings(
+            &workspace_file.settings,
+            &[
+                ("editor.tabSize", "tab_size", |value| Some(value.clone())),
+                ("editor.insertSpaces", "hard_tabs", |value| {
+                    Some(Value::Bool(!value.as_bool()?))
+                }),
+                ("editor.formatOnSave", "format_on_save", |value| {
+                    Some(json!(if value.as_bool()? { "on" } else { "off" }))
+                }),
+                ("editor.rulers", "wrap_guides", |value| Some(value.clone())),
+                ("files.exclude", "file_scan_exclusions", |value| {
+                    let patterns: Vec<&String> = value
+                        .as_object()?
+                        .iter()
+                        .filter(|(_, excluded)| excluded.as_bool() == Some(true))
+                        .map(|(pattern, _)| pattern)
+                        .collect();
+                    Some(json!(patterns))
+                }),
+            ],
+        );
+
+        fs::create_dir_all(workspace_dir.join(".zed"))?;
+        fs::write(&path, serde_json::to_string_pretty(&settings)?)?;
+        Ok(())
+    }
+
+    fn remove(&self, workspace_dir: &Path, _workspace_name: &str) -> Result<Vec<PathBuf>, WxError> {
+        let mut removed = Vec::new();
+        remove_file(Self::settings_path(workspace_dir), &mut removed)?;
+
+        let zed_dir = workspace_dir.join(".zed");
+        if zed_dir.is_dir() && fs::read_dir(&zed_dir)?.next().is_none() {
+            fs::remove_dir(&zed_dir)?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{models::workspace::WorkspaceSettings, utils::test_helpers::setup_test_dirs};
+
+    use super::*;
+
+    #[test]
+    fn test_write_translates_settings() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let settings: WorkspaceSettings = serde_json::from_value(json!({
+            "editor.tabSize": 2,
+            "editor.formatOnSave": true,
+            "files.exclude": {"**/target": true, "**/dist": false},
+            "workbench.colorTheme": "Default Dark+",
+        }))
+        .unwrap();
+        let workspace_file = WorkspaceFile::with_settings(vec!["frontend".to_string()], settings);
+
+        ZedWriter.write(dir.path(), "wx", &workspace_file).unwrap();
+
+        let path = dir.path().join(".zed/settings.json");
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            written,
+            json!({
+                "tab_size": 2,
+                "format_on_save": "on",
+                "file_scan_exclusions": ["**/target"],
+            })
+        );
+
+        // an existing file is kept as it is
+        fs::write(&path, "{ \"tab_size\": 8 }").unwrap();
+        ZedWriter.write(dir.path(), "wx", &workspace_file).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"tab_size\": 8 }");
+
+        assert_eq!(ZedWriter.remove(dir.path(), "wx").unwrap(), vec![path]);
+        assert!(!dir.path().join(".zed").exists());
+    }
+}
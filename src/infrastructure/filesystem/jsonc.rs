@@ -0,0 +1,737 @@
+use serde_json::Value;
+
+use crate::models::WxError;
+
+/// en: Blank out comments and trailing commas of JSONC text so that it parses as JSON
+/// Every byte offset stays the same, which lets spans found in the result be used on `text`
+///
+/// ja: JSONCのコメントと末尾のカンマを空白に置き換え、JSONとして解析できるようにする
+/// バイト位置は変わらないため、結果で見つけた範囲をそのまま `text` に使える
+pub fn strip(text: &str) -> Result<String, WxError> {
+    let bytes = text.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut pending_comma: Option<usize> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                pending_comma = None;
+                i = string_end(bytes, i);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    out[i] = b' ';
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = text[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+                for byte in &mut out[i..end] {
+                    if *byte != b'\n' {
+                        *byte = b' ';
+                    }
+                }
+                i = end;
+            }
+            b',' => {
+                pending_comma = Some(i);
+                i += 1;
+            }
+            b'}' | b']' => {
+                if let Some(comma) = pending_comma.take() {
+                    out[comma] = b' ';
+                }
+                i += 1;
+            }
+            byte if byte.is_ascii_whitespace() => i += 1,
+            _ => {
+                pending_comma = None;
+                i += 1;
+            }
+        }
+    }
+
+    // only whole characters outside strings were replaced, by ASCII spaces
+    String::from_utf8(out)
+        .map_err(|e| WxError::General(format!("Failed to strip JSONC comments: {e}")))
+}
+
+/// en: Rewrite the JSONC `original` so that it holds `new`
+/// Only values that differ are replaced, so comments and formatting around unchanged values are kept
+/// Returns `None` when `original` is not valid JSONC
+///
+/// ja: JSONCの `original` を `new` の内容に書き換える
+/// 異なる値のみを置き換えるため、変更されない値の周りのコメントや書式は保持される
+/// `original` が正しいJSONCでない場合は `None` を返却する
+pub fn update(original: &str, new: &Value) -> Option<String> {
+    let stripped = strip(original).ok()?;
+    let old: Value = serde_json::from_str(&stripped).ok()?;
+    let (node, _) = Parser {
+        text: stripped.as_bytes(),
+    }
+    .value(0)?;
+
+    let mut edits: Vec<Edit> = Vec::new();
+    Differ {
+        original,
+        stripped: stripped.as_bytes(),
+        edits: &mut edits,
+    }
+    .diff(&node, &old, new);
+
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+    let mut updated = original.to_string();
+    for edit in edits {
+        updated.replace_range(edit.start..edit.end, &edit.text);
+    }
+
+    Some(updated)
+}
+
+/// Returns the offset just past the string starting at `start`
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// A value in the text, with the byte range it spans
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Object(Vec<Item>),
+    Array(Vec<Item>),
+    Scalar,
+}
+
+/// An object member or array element; `start` is the start of the key for members
+#[derive(Debug)]
+struct Item {
+    key: Option<String>,
+    start: usize,
+    value: Node,
+}
+
+/// Finds the spans of values in stripped JSONC text that is known to be valid JSON
+struct Parser<'a> {
+    text: &'a [u8],
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&self, mut i: usize) -> usize {
+        while i < self.text.len() && self.text[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        i
+    }
+
+    fn value(&self, i: usize) -> Option<(Node, usize)> {
+        let start = self.skip_whitespace(i);
+        match *self.text.get(start)? {
+            b'{' => self.items(start, b'}', true),
+            b'[' => self.items(start, b']', false),
+            b'"' => {
+                let end = string_end(self.text, start);
+                Some((scalar(start, end), end))
+            }
+            _ => {
+                let mut end = start;
+                while end < self.text.len()
+                    && !matches!(self.text[end], b',' | b'}' | b']')
+                    && !self.text[end].is_ascii_whitespace()
+                {
+                    end += 1;
+                }
+                Some((scalar(start, end), end))
+            }
+        }
+    }
+
+    fn items(&self, start: usize, close: u8, keyed: bool) -> Option<(Node, usize)> {
+        let mut items: Vec<Item> = Vec::new();
+        let mut i = self.skip_whitespace(start + 1);
+
+        while self.text.get(i) != Some(&close) {
+            let item_start = i;
+            let key = if keyed {
+                let key_end = string_end(self.text, i);
+                let key: String = serde_json::from_slice(&self.text[i..key_end]).ok()?;
+                i = self.skip_whitespace(key_end);
+                if self.text.get(i) != Some(&b':') {
+                    return None;
+                }
+                i += 1;
+                Some(key)
+            } else {
+                None
+            };
+
+            let (value, end) = self.value(i)?;
+            items.push(Item {
+                key,
+                start: item_start,
+                value,
+            });
+
+            i = self.skip_whitespace(end);
+            if self.text.get(i) == Some(&b',') {
+                i = self.skip_whitespace(i + 1);
+            }
+        }
+
+        let end = i + 1;
+        let kind = if keyed {
+            NodeKind::Object(items)
+        } else {
+            NodeKind::Array(items)
+        };
+        Some((Node { start, end, kind }, end))
+    }
+}
+
+fn scalar(start: usize, end: usize) -> Node {
+    Node {
+        start,
+        end,
+        kind: NodeKind::Scalar,
+    }
+}
+
+/// A replacement of `start..end` in the original text
+#[derive(Debug)]
+struct Edit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Collects the edits that turn the original text into the new value
+struct Differ<'a> {
+    original: &'a str,
+    stripped: &'a [u8],
+    edits: &'a mut Vec<Edit>,
+}
+
+impl Differ<'_> {
+    fn diff(&mut self, node: &Node, old: &Value, new: &Value) {
+        if old == new {
+            return;
+        }
+
+        let spliced = match (&node.kind, old, new) {
+            (NodeKind::Object(items), Value::Object(old), Value::Object(new)) => {
+                let removed: Vec<usize> = (0..items.len())
+                    .filter(|&index| {
+                        items[index]
+                            .key
+                            .as_ref()
+                            .is_some_and(|key| !new.contains_key(key))
+                    })
+                    .collect();
+                let appended: Vec<String> = new
+                    .iter()
+                    .filter(|(key, _)| !old.contains_key(*key))
+                    .map(|(key, value)| (Some(key.as_str()), value))
+                    .map(|(key, value)| self.format_item(key, value, items))
+                    .collect();
+
+                self.splice(items, &removed, &appended) && {
+                    for item in items {
+                        let key = item.key.as_deref().unwrap_or_default();
+                        if let (Some(old), Some(new)) = (old.get(key), new.get(key)) {
+                            self.diff(&item.value, old, new);
+                        }
+                    }
+                    true
+                }
+            }
+            (NodeKind::Array(items), Value::Array(old), Value::Array(new)) => {
+                self.diff_array(items, old, new)
+            }
+            _ => false,
+        };
+
+        if !spliced {
+            self.edits.push(Edit {
+                start: node.start,
+                end: node.end,
+                text: self.format_value(new, node.start),
+            });
+        }
+    }
+
+    /// Edit arrays whose elements were changed in place, appended or removed; anything else
+    /// is replaced as a whole
+    fn diff_array(&mut self, items: &[Item], old: &[Value], new: &[Value]) -> bool {
+        if old.len() == new.len() {
+            for ((item, old), new) in items.iter().zip(old).zip(new) {
+                self.diff(&item.value, old, new);
+            }
+            return true;
+        }
+
+        if new.len() > old.len() && new.starts_with(old) {
+            let appended: Vec<String> = new[old.len()..]
+                .iter()
+                .map(|value| self.format_item(None, value, items))
+                .collect();
+            return self.splice(items, &[], &appended);
+        }
+
+        // elements removed while the order of the others is kept
+        let mut removed: Vec<usize> = Vec::new();
+        let mut remaining = new.iter().peekable();
+        for (index, value) in old.iter().enumerate() {
+            if remaining.peek() == Some(&value) {
+                remaining.next();
+            } else {
+                removed.push(index);
+            }
+        }
+        remaining.peek().is_none() && self.splice(items, &removed, &[])
+    }
+
+    /// Remove and append items of a non-empty container, leaving the rest of its text alone
+    /// Returns false when the edits would touch each other, so the container must be replaced
+    fn splice(&mut self, items: &[Item], removed: &[usize], appended: &[String]) -> bool {
+        let Some(last) = items.len().checked_sub(1) else {
+            return false;
+        };
+        if removed.len() == items.len()
+            || (removed.contains(&last) && (removed.len() > 1 || !appended.is_empty()))
+        {
+            return false;
+        }
+
+        for &index in removed {
+            let edits = self.removal(&items[index]);
+            self.edits.extend(edits);
+        }
+
+        if !appended.is_empty() {
+            let value_end = items[last].value.end;
+            let trailing_comma = Some(self.skip_comments(value_end))
+                .filter(|&position| self.original.as_bytes().get(position) == Some(&b','));
+
+            let edit = match trailing_comma {
+                // keep the trailing comma and any comment after it on the previous item's line
+                Some(comma) => {
+                    let line_end = self.skip_spaces(comma + 1);
+                    let mut text = appended.join(",");
+                    let position = if self.stripped.get(line_end) == Some(&b'\n') {
+                        // past a comment, the items have to start on the next line
+                        if !text.starts_with('\n') {
+                            text = format!(
+                                "\n{}{}",
+                                self.indent(items[last].start),
+                                text.trim_start()
+                            );
+                        }
+                        line_end
+                    } else {
+                        comma + 1
+                    };
+                    Edit {
+                        start: position,
+                        end: position,
+                        text,
+                    }
+                }
+                None => Edit {
+                    start: value_end,
+                    end: value_end,
+                    text: appended.iter().map(|item| format!(",{item}")).collect(),
+                },
+            };
+            self.edits.push(edit);
+        }
+
+        true
+    }
+
+    /// The ranges removing an item with its comma and, when it is alone on its lines, its line break
+    /// The last item takes the comma of the previous one, leaving that item's comment alone
+    fn removal(&self, item: &Item) -> Vec<Edit> {
+        let line_start = self.line_start(item.start);
+        let alone = self.is_blank(line_start, item.start);
+        let start = if alone { line_start } else { item.start };
+        let remove = |start: usize, end: usize| Edit {
+            start,
+            end,
+            text: String::new(),
+        };
+
+        // the comma may follow a comment on a later line, e.g. `"a": 1 // note\n, "b": 2`
+        let after = self.skip_comments(item.value.end);
+        if self.stripped.get(after) == Some(&b',') {
+            let line_end = self.skip_spaces(after + 1);
+            let end = match self.stripped.get(line_end) {
+                Some(b'\n') if alone => line_end + 1,
+                Some(b'\n') => line_end,
+                _ => after + 1,
+            };
+            return vec![remove(start, end)];
+        }
+
+        // blanked trailing commas and comments up to the end of the line go with the item
+        let end = self.skip_spaces(item.value.end);
+        let mut before = item.start;
+        while before > 0 && self.stripped[before - 1].is_ascii_whitespace() {
+            before -= 1;
+        }
+        match before.checked_sub(1) {
+            // the line break before the item goes only when one follows it, so that a line
+            // comment before the item never runs into the closing bracket
+            Some(comma)
+                if self.stripped[comma] == b','
+                    && alone
+                    && self.stripped.get(end) == Some(&b'\n') =>
+            {
+                vec![remove(comma, comma + 1), remove(line_start - 1, end)]
+            }
+            Some(comma) if self.stripped[comma] == b',' && alone => {
+                vec![remove(comma, comma + 1), remove(line_start, end)]
+            }
+            Some(comma) if self.stripped[comma] == b',' => vec![remove(comma, end)],
+            _ => vec![remove(start, end)],
+        }
+    }
+
+    /// Format an appended item, starting with the separator from the previous item
+    fn format_item(&self, key: Option<&str>, value: &Value, items: &[Item]) -> String {
+        let anchor = items.last().map_or(0, |item| item.start);
+        let indent = self.indent(anchor);
+        let value = self.format_value(value, anchor);
+        let item = match key {
+            Some(key) => format!("{}: {value}", Value::from(key)),
+            None => value,
+        };
+
+        if self.is_blank(self.line_start(anchor), anchor) {
+            format!("\n{indent}{item}")
+        } else {
+            format!(" {item}")
+        }
+    }
+
+    /// Pretty-print a value, indenting its following lines like the line at `position`
+    fn format_value(&self, value: &Value, position: usize) -> String {
+        let indent = self.indent(position);
+        serde_json::to_string_pretty(value)
+            .unwrap_or_default()
+            .replace('\n', &format!("\n{indent}"))
+    }
+
+    fn line_start(&self, position: usize) -> usize {
+        self.original[..position].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn indent(&self, position: usize) -> &str {
+        let line_start = self.line_start(position);
+        let line = &self.original[line_start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    fn is_blank(&self, start: usize, end: usize) -> bool {
+        self.stripped[start..end]
+            .iter()
+            .all(|byte| *byte == b' ' || *byte == b'\t')
+    }
+
+    /// Offset of the first character from `i` that is neither whitespace nor in a comment
+    fn skip_comments(&self, mut i: usize) -> usize {
+        let text = self.original.as_bytes();
+        loop {
+            while i < text.len() && text[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let rest = &self.original[i..];
+            if rest.starts_with("//") {
+                i += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                i += comment.find("*/").map_or(rest.len(), |end| end + 4);
+            } else {
+                return i;
+            }
+        }
+    }
+
+    fn skip_spaces(&self, mut i: usize) -> usize {
+        while i < self.stripped.len() && matches!(self.stripped[i], b' ' | b'\t' | b'\r') {
+            i += 1;
+        }
+        i
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use serde_json::{json, Map};
+
+    use super::*;
+
+    const WORKSPACE: &str = r#"{
+  // repositories of the workspace
+  "folders": [
+    { "path": "frontend", "name": "Web" }, // the UI
+    { "path": "backend" },
+  ],
+  "settings": {
+    /* shared formatting */
+    "editor.tabSize": 2,
+    "files.exclude": { "**/target": true },
+  },
+}
+"#;
+
+    fn parse(text: &str) -> Value {
+        serde_json::from_str(&strip(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_strip() {
+        let stripped = strip(WORKSPACE).unwrap();
+        assert_eq!(stripped.len(), WORKSPACE.len());
+        assert_eq!(
+            parse(WORKSPACE)["folders"][0],
+            json!({"path": "frontend", "name": "Web"})
+        );
+
+        // comment markers and commas inside strings are kept
+        let text = r#"{"url": "https://example.com/*x*/", "list": ["a,", "b" ,],}"#;
+        assert_eq!(
+            parse(text),
+            json!({"url": "https://example.com/*x*/", "list": ["a,", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_update_unchanged() {
+        assert_eq!(update(WORKSPACE, &parse(WORKSPACE)).unwrap(), WORKSPACE);
+    }
+
+    #[test]
+    fn test_update_appends_folder_and_keeps_comments() {
+        let mut value = parse(WORKSPACE);
+        value["folders"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({"path": "docs"}));
+
+        let updated = update(WORKSPACE, &value).unwrap();
+
+        assert_eq!(parse(&updated), value);
+        assert!(updated.contains("// the UI"));
+        assert!(updated.contains("/* shared formatting */"));
+        assert!(updated
+            .contains("{ \"path\": \"backend\" },\n    {\n      \"path\": \"docs\"\n    }\n  ],"));
+    }
+
+    #[test]
+    fn test_update_removes_folder() {
+        let mut value = parse(WORKSPACE);
+        value["folders"].as_array_mut().unwrap().remove(0);
+
+        let updated = update(WORKSPACE, &value).unwrap();
+
+        assert_eq!(parse(&updated), value);
+        assert!(!updated.contains("frontend"));
+        assert!(updated.contains(
+            "// repositories of the workspace\n  \"folders\": [\n    { \"path\": \"backend\" },"
+        ));
+
+        // the comment of the remaining folder stays when the last one is removed
+        let mut value = parse(WORKSPACE);
+        value["folders"].as_array_mut().unwrap().remove(1);
+        let updated = update(WORKSPACE, &value).unwrap();
+        assert_eq!(parse(&updated), value);
+        assert!(updated.contains("{ \"path\": \"frontend\", \"name\": \"Web\" } // the UI\n  ],"));
+
+        value["folders"].as_array_mut().unwrap().remove(0);
+        let updated = update(WORKSPACE, &value).unwrap();
+        assert_eq!(parse(&updated)["folders"], json!([]));
+        assert!(updated.contains("// repositories of the workspace"));
+    }
+
+    #[test]
+    fn test_update_changes_and_adds_keys() {
+        let mut value = parse(WORKSPACE);
+        value["settings"]["editor.tabSize"] = json!(4);
+        value["settings"]
+            .as_object_mut()
+            .unwrap()
+            .remove("files.exclude");
+        value["extensions"] = json!({"recommendations": ["rust-lang.rust-analyzer"]});
+
+        let updated = update(WORKSPACE, &value).unwrap();
+
+        assert_eq!(parse(&updated), value);
+        assert!(updated.contains("/* shared formatting */\n    \"editor.tabSize\": 4\n  },"));
+        assert!(updated.contains("// the UI"));
+    }
+
+    #[test]
+    fn test_update_removes_member_before_comment_and_comma() {
+        let text = "{ \"a\": 1 // c\n , \"b\": 2 }";
+        let updated = update(text, &json!({"b": 2})).unwrap();
+        assert_eq!(parse(&updated), json!({"b": 2}));
+
+        let text = "[\n  1 /* one */\n  , 2,\n]";
+        let updated = update(text, &json!([2, 3])).unwrap();
+        assert_eq!(parse(&updated), json!([2, 3]));
+    }
+
+    /// Whitespace and comments put between the tokens of a generated document
+    const GAPS: [&str; 7] = [
+        "",
+        " ",
+        "\n  ",
+        " // note, here\n",
+        " /* a, b */ ",
+        "\n/* x */\n",
+        "\r\n\t// 日本\r\n",
+    ];
+
+    /// Generated numbers handed out in turn, cycling when they run out
+    struct Choices {
+        values: Vec<usize>,
+        next: usize,
+    }
+
+    impl Choices {
+        fn take(&mut self) -> usize {
+            let value = self.values[self.next % self.values.len()];
+            self.next += 1;
+            value
+        }
+
+        fn gap(&mut self) -> &'static str {
+            GAPS[self.take() % GAPS.len()]
+        }
+    }
+
+    /// Render `value` as JSONC with the gaps and trailing commas picked by `choices`
+    fn render(value: &Value, choices: &mut Choices) -> String {
+        let (open, close, items): (char, char, Vec<(Option<&String>, &Value)>) = match value {
+            Value::Object(map) => ('{', '}', map.iter().map(|(k, v)| (Some(k), v)).collect()),
+            Value::Array(values) => ('[', ']', values.iter().map(|v| (None, v)).collect()),
+            scalar => return scalar.to_string(),
+        };
+
+        let mut text = open.to_string();
+        for (index, (key, item)) in items.iter().enumerate() {
+            if index > 0 {
+                text.push(',');
+            }
+            text.push_str(choices.gap());
+            if let Some(key) = key {
+                text.push_str(&Value::from(key.as_str()).to_string());
+                text.push_str(choices.gap());
+                text.push(':');
+                text.push_str(choices.gap());
+            }
+            text.push_str(&render(item, choices));
+            text.push_str(choices.gap());
+        }
+        if !items.is_empty() && choices.take().is_multiple_of(2) {
+            text.push(',');
+            text.push_str(choices.gap());
+        }
+        text.push(close);
+        text
+    }
+
+    /// Change `value` as picked by `choices`: keep, remove, recurse into or replace each item,
+    /// and sometimes append one
+    fn mutate(value: &Value, choices: &mut Choices) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut mutated = Map::new();
+                for (key, item) in map {
+                    let item = match choices.take() % 4 {
+                        0 => item.clone(),
+                        1 => continue,
+                        2 => mutate(item, choices),
+                        _ => json!(choices.take()),
+                    };
+                    mutated.insert(key.clone(), item);
+                }
+                if choices.take().is_multiple_of(2) {
+                    mutated.insert(format!("new{}", choices.take()), json!({"path": "docs"}));
+                }
+                Value::Object(mutated)
+            }
+            Value::Array(values) => {
+                let mut mutated = Vec::new();
+                for item in values {
+                    match choices.take() % 4 {
+                        0 => mutated.push(item.clone()),
+                        1 => {}
+                        2 => mutated.push(mutate(item, choices)),
+                        _ => mutated.push(json!(choices.take())),
+                    }
+                }
+                if choices.take().is_multiple_of(2) {
+                    mutated.push(json!("docs"));
+                }
+                Value::Array(mutated)
+            }
+            _ => json!(choices.take()),
+        }
+    }
+
+    fn json_value() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            (0..100i64).prop_map(Value::from),
+            r#"[a-z ,/*"\\é日]{0,6}"#.prop_map(Value::from),
+        ];
+        leaf.prop_recursive(3, 24, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(Value::from),
+                prop::collection::btree_map("[a-d]", inner, 0..4)
+                    .prop_map(|map| Value::Object(map.into_iter().collect())),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_update_output_parses_to_target(
+            value in json_value(),
+            gaps in prop::collection::vec(0..12usize, 1..32),
+            edits in prop::collection::vec(0..64usize, 1..32),
+        ) {
+            let text = render(&value, &mut Choices { values: gaps, next: 0 });
+            prop_assert_eq!(&parse(&text), &value);
+
+            let target = mutate(&value, &mut Choices { values: edits, next: 0 });
+            let updated = update(&text, &target).unwrap();
+            let parsed: Value = serde_json::from_str(&strip(&updated).unwrap())
+                .map_err(|e| TestCaseError::fail(format!("{e} in {updated:?} from {text:?}")))?;
+            prop_assert_eq!(parsed, target);
+        }
+    }
+
+    #[test]
+    fn test_update_invalid() {
+        assert_eq!(update("{ \"folders\": [", &json!({})), None);
+    }
+}
@@ -0,0 +1,69 @@
+use std::{fs, path::Path};
+
+use crate::models::{manifest::WorkspaceManifest, WxError};
+
+#[derive(Default)]
+pub struct ManifestFileManager;
+
+impl ManifestFileManager {
+    /// en: Read and validate a `wx.toml` workspace manifest
+    ///
+    /// ja: `wx.toml` workspaceマニフェストを読み込み、検証する
+    pub fn read(&self, path: &Path) -> Result<WorkspaceManifest, WxError> {
+        let content = fs::read_to_string(path)?;
+        let manifest: WorkspaceManifest = toml::from_str(&content)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_helpers::setup_test_dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let path = dir.path().join("wx.toml");
+        fs::write(
+            &path,
+            "name = \"feature-auth\"\n\n[[repos]]\nname = \"frontend\"\nbranch = \"feature/auth\"\n",
+        )
+        .unwrap();
+
+        let manifest = ManifestFileManager.read(&path).unwrap();
+
+        assert_eq!(manifest.name, "feature-auth");
+        assert_eq!(manifest.repos[0].name, "frontend");
+    }
+
+    #[test]
+    fn test_read_rejects_paths_outside_workspace() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let path = dir.path().join("wx.toml");
+        fs::write(
+            &path,
+            "name = \"feature-auth\"\n\n[[repos]]\nname = \"frontend\"\nbranch = \"main\"\ndir = \"../escaped\"\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            ManifestFileManager.read(&path),
+            Err(WxError::InvalidDirectoryName(name)) if name == "../escaped"
+        ));
+    }
+
+    #[test]
+    fn test_read_invalid_toml() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let path = dir.path().join("wx.toml");
+        fs::write(&path, "name = ").unwrap();
+
+        assert!(matches!(
+            ManifestFileManager.read(&path),
+            Err(WxError::ManifestError(_))
+        ));
+    }
+}
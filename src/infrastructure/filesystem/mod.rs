@@ -1,3 +1,10 @@
+mod editor;
+mod jsonc;
+mod manifest_file;
+mod template_file;
 mod workspace_file;
 
+pub use editor::{workspace_writer, WorkspaceWriter};
+pub use manifest_file::ManifestFileManager;
+pub use template_file::TemplateFileManager;
 pub use workspace_file::WorkspaceFileManager;
@@ -0,0 +1,115 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::jsonc;
+use crate::{
+    models::{template::WorkspaceTemplate, WxError},
+    utils::is_single_component,
+};
+
+/// en: Reads workspace templates from `~/.wx/templates`
+/// `<name>.json` holds named templates (`default.json` applies to every workspace), and
+/// `repos/<repo>.json` holds the blocks of a repository
+///
+/// ja: `~/.wx/templates` からworkspaceテンプレートを読み込む
+/// `<name>.json` は名前付きテンプレート（`default.json` は全workspaceに適用）、
+/// `repos/<repo>.json` はリポジトリごとのブロック
+pub struct TemplateFileManager {
+    templates_dir: PathBuf,
+}
+
+impl TemplateFileManager {
+    pub fn with_base_dir(wx_home: &Path) -> Self {
+        Self {
+            templates_dir: wx_home.join("templates"),
+        }
+    }
+
+    /// en: Read the named template, or `None` when it does not exist
+    /// Names that would point outside `~/.wx/templates` are rejected
+    ///
+    /// ja: 名前付きテンプレートを読み込む（存在しない場合は `None`）
+    /// `~/.wx/templates` の外を指す名前はエラーとする
+    pub fn load(&self, name: &str) -> Result<Option<WorkspaceTemplate>, WxError> {
+        if !is_single_component(name) {
+            return Err(WxError::InvalidTemplateName(name.to_string()));
+        }
+        self.read(&self.templates_dir.join(format!("{name}.json")))
+    }
+
+    /// en: Read the template of a repository, or `None` when it does not exist
+    ///
+    /// ja: リポジトリのテンプレートを読み込む（存在しない場合は `None`）
+    pub fn load_repo(&self, repo_name: &str) -> Result<Option<WorkspaceTemplate>, WxError> {
+        self.read(
+            &self
+                .templates_dir
+                .join("repos")
+                .join(format!("{repo_name}.json")),
+        )
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<WorkspaceTemplate>, WxError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&jsonc::strip(&content)?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_helpers::setup_test_dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let templates_dir = base_dir.join("templates");
+        fs::create_dir_all(templates_dir.join("repos")).unwrap();
+        fs::write(
+            templates_dir.join("fullstack.json"),
+            "{\n  // shared\n  \"settings\": { \"editor.tabSize\": 2, },\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            templates_dir.join("repos/backend.json"),
+            r#"{"extensions": {"recommendations": ["rust-lang.rust-analyzer"]}}"#,
+        )
+        .unwrap();
+
+        let template_file_manager = TemplateFileManager::with_base_dir(&base_dir);
+
+        let fullstack = template_file_manager.load("fullstack").unwrap().unwrap();
+        assert_eq!(
+            fullstack.settings.values["editor.tabSize"],
+            serde_json::json!(2)
+        );
+        let backend = template_file_manager.load_repo("backend").unwrap().unwrap();
+        assert!(backend.extensions.is_some());
+        assert!(template_file_manager.load("default").unwrap().is_none());
+        assert!(template_file_manager
+            .load_repo("frontend")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_names_outside_templates() {
+        let (dir, base_dir) = setup_test_dirs();
+        fs::write(dir.path().join("secret.json"), "{}").unwrap();
+
+        let template_file_manager = TemplateFileManager::with_base_dir(&base_dir);
+        for name in ["../../secret", "../secret", "repos/backend", "..", "a\\b"] {
+            assert!(matches!(
+                template_file_manager.load(name),
+                Err(WxError::InvalidTemplateName(_))
+            ));
+        }
+    }
+}
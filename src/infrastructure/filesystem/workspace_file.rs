@@ -1,5 +1,9 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
+use super::jsonc;
 use crate::models::{workspace::WorkspaceFile, WxError};
 
 #[derive(Default)]
@@ -15,7 +19,18 @@ impl WorkspaceFileManager {
         workspace_name: &str,
         folders: Vec<String>,
     ) -> Result<(), WxError> {
-        let workspace_file = WorkspaceFile::new(folders);
+        self.create(workspace_dir, workspace_name, &WorkspaceFile::new(folders))
+    }
+
+    /// en: Create a new workspace file, failing if it already exists
+    ///
+    /// ja: 新しいworkspaceファイルを作成する（既に存在する場合はエラー）
+    pub fn create(
+        &self,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
         let workspace_file_path = workspace_dir.join(format!("{workspace_name}.code-workspace"));
 
         if workspace_file_path.exists() {
@@ -24,18 +39,34 @@ impl WorkspaceFileManager {
             ));
         }
 
-        let workspace_file_json_string = serde_json::to_string_pretty(&workspace_file)?;
-        fs::write(workspace_file_path, workspace_file_json_string)?;
+        self.write(&workspace_file_path, workspace_file)
+    }
+
+    /// en: Write a workspace file
+    /// An existing file is updated in place, keeping its comments and formatting around unchanged values
+    ///
+    /// ja: workspaceファイルを書き込む
+    /// 既存のファイルはその場で更新し、変更されない値の周りのコメントや書式を保持する
+    pub fn write(&self, path: &Path, workspace_file: &WorkspaceFile) -> Result<(), WxError> {
+        let updated = match fs::read_to_string(path) {
+            Ok(original) => jsonc::update(&original, &serde_json::to_value(workspace_file)?),
+            Err(_) => None,
+        };
+        let workspace_file_json_string = match updated {
+            Some(updated) => updated,
+            None => serde_json::to_string_pretty(workspace_file)?,
+        };
+        fs::write(path, workspace_file_json_string)?;
 
         Ok(())
     }
 
-    /// en: Read a workspace file
+    /// en: Read a workspace file, accepting comments and trailing commas (JSONC)
     ///
-    /// ja: workspaceファイルを読み込む
+    /// ja: workspaceファイルを読み込む（コメントと末尾のカンマ（JSONC）を許容する）
     pub fn read(&self, path: &Path) -> Result<WorkspaceFile, WxError> {
         let content = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content)?)
+        Ok(serde_json::from_str(&jsonc::strip(&content)?)?)
     }
 
     /// en: Check if a workspace file exists at the specified path
@@ -46,6 +77,24 @@ impl WorkspaceFileManager {
         workspace_file_path.exists()
     }
 
+    /// en: Find the workspace file of a workspace directory, preferring `<dir>/<dir>.code-workspace`
+    ///
+    /// ja: workspaceディレクトリのworkspaceファイルを探す（`<dir>/<dir>.code-workspace` を優先する）
+    pub fn find(&self, workspace_dir: &Path) -> Option<PathBuf> {
+        let dir_name = workspace_dir.file_name()?.to_string_lossy().to_string();
+        if self.exists(workspace_dir, &dir_name) {
+            return Some(workspace_dir.join(format!("{dir_name}.code-workspace")));
+        }
+
+        let mut candidates: Vec<PathBuf> = fs::read_dir(workspace_dir)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "code-workspace"))
+            .collect();
+        candidates.sort();
+        candidates.into_iter().next()
+    }
+
     /// en: Delete a workspace file at the specified path
     ///
     /// ja: 指定されたパスのworkspaceファイルを削除
@@ -58,6 +107,7 @@ impl WorkspaceFileManager {
 
 #[cfg(test)]
 mod tests {
+    use crate::models::workspace::WorkspaceFolder;
     use crate::utils::test_helpers::{
         create_test_git_repo, setup_test_dirs, test_create_workspace_file,
     };
@@ -155,6 +205,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_write_keeps_settings_and_unknown_keys() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let parent_path = dir.path().join("work");
+        fs::create_dir_all(&parent_path).unwrap();
+        let path = parent_path.join("wx.code-workspace");
+        fs::write(
+            &path,
+            r#"{"folders":[{"path":"frontend","name":"Web"}],"settings":{"editor.tabSize":2},"extensions":{"recommendations":["rust-lang.rust-analyzer"]}}"#,
+        )
+        .unwrap();
+
+        let workspace_file_manager = WorkspaceFileManager;
+        let mut workspace_file = workspace_file_manager.read(&path).unwrap();
+        workspace_file
+            .folders
+            .push(WorkspaceFolder::new("backend".to_string()));
+        workspace_file_manager
+            .write(&path, &workspace_file)
+            .unwrap();
+
+        let workspace_file = workspace_file_manager.read(&path).unwrap();
+        assert_eq!(workspace_file.folders.len(), 2);
+        assert_eq!(
+            workspace_file.settings.values["editor.tabSize"],
+            serde_json::json!(2)
+        );
+        assert_eq!(workspace_file.folders[0].name.as_deref(), Some("Web"));
+        assert_eq!(
+            workspace_file.extra["extensions"],
+            serde_json::json!({"recommendations": ["rust-lang.rust-analyzer"]})
+        );
+    }
+
+    #[test]
+    fn test_write_keeps_comments() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let parent_path = dir.path().join("work");
+        fs::create_dir_all(&parent_path).unwrap();
+        let path = parent_path.join("wx.code-workspace");
+        fs::write(
+            &path,
+            "{\n  // worktrees\n  \"folders\": [\n    { \"path\": \"frontend\" },\n  ],\n  \"launch\": { /* debug */ \"configurations\": [] },\n}\n",
+        )
+        .unwrap();
+
+        let workspace_file_manager = WorkspaceFileManager;
+        let mut workspace_file = workspace_file_manager.read(&path).unwrap();
+        assert!(workspace_file.extra.contains_key("launch"));
+        workspace_file
+            .folders
+            .push(WorkspaceFolder::new("backend".to_string()));
+        workspace_file_manager
+            .write(&path, &workspace_file)
+            .unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("// worktrees"));
+        assert!(content.contains("/* debug */"));
+        let workspace_file = workspace_file_manager.read(&path).unwrap();
+        assert_eq!(workspace_file.folders[1].path, "backend");
+    }
+
     #[test]
     fn test_read_nonexistent_file() {
         let workspace_file_manager = WorkspaceFileManager;
@@ -198,6 +311,28 @@ mod tests {
         assert!(!workspace_file_manager.exists(&parent_path, "nonexistent"));
     }
 
+    #[test]
+    fn test_find() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let workspace_dir = dir.path().join("feature");
+        fs::create_dir_all(&workspace_dir).unwrap();
+        let workspace_file_manager = WorkspaceFileManager;
+
+        assert_eq!(workspace_file_manager.find(&workspace_dir), None);
+
+        test_create_workspace_file(&workspace_dir, "other", vec![]);
+        assert_eq!(
+            workspace_file_manager.find(&workspace_dir),
+            Some(workspace_dir.join("other.code-workspace"))
+        );
+
+        test_create_workspace_file(&workspace_dir, "feature", vec![]);
+        assert_eq!(
+            workspace_file_manager.find(&workspace_dir),
+            Some(workspace_dir.join("feature.code-workspace"))
+        );
+    }
+
     #[test]
     fn test_delete() {
         let (_dir, _base_dir) = setup_test_dirs();
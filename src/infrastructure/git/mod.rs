@@ -1,5 +1,7 @@
+pub mod credentials;
 pub mod operations;
 pub mod worktree;
 
-pub use operations::GitOperations;
+pub use credentials::CredentialHandler;
+pub use operations::{fetch_options, GitOperations, ORIGIN_FETCH_REFSPEC};
 pub use worktree::{DefaultWorktreeManager, MockWorktreeManager, WorktreeManager};
@@ -1,9 +1,18 @@
-use std::path::Path;
+use std::path::{absolute, Path, PathBuf};
 
-use git2::build::RepoBuilder;
+use git2::{build::RepoBuilder, FetchOptions, Progress, RemoteCallbacks, Repository};
 use regex::Regex;
 
-use crate::models::WxError;
+use super::CredentialHandler;
+use crate::models::{
+    transfer::{TransferObserver, TransferProgress},
+    WxError,
+};
+
+/// en: Fetch refspec that keeps `origin` branches under `refs/remotes/origin/*`
+///
+/// ja: `origin` のブランチを `refs/remotes/origin/*` に保持するフェッチrefspec
+pub const ORIGIN_FETCH_REFSPEC: &str = "+refs/heads/*:refs/remotes/origin/*";
 
 #[derive(Default)]
 pub struct GitOperations;
@@ -18,7 +27,9 @@ impl GitOperations {
         let https_pattern = Regex::new(r"^https://[\w\.\-]+/[\w\.\-_/]+?(?:\.git)?$").unwrap();
         let ssh_pattern = Regex::new(r"^git@[\w\.\-]+:[\w\.\-_/]+?(?:\.git)?$").unwrap();
 
-        if https_pattern.is_match(url) || ssh_pattern.is_match(url) {
+        if (https_pattern.is_match(url) || ssh_pattern.is_match(url))
+            && is_valid_host(&split_remote(url).0)
+        {
             Ok(())
         } else {
             Err(WxError::InvalidUrl(url.to_string()))
@@ -41,15 +52,157 @@ impl GitOperations {
         Ok(repo_name)
     }
 
-    pub fn bare_clone(&self, url: &str, target_path: &Path) -> Result<(), WxError> {
-        RepoBuilder::new()
+    /// en: Path of the bare clone of `url` relative to `~/.wx/repos`: `<host>/<owner>/<repo>.git`,
+    /// or `local/<absolute path>.git` for a local repository, so that equally named
+    /// repositories of different owners never share a directory
+    /// A URL whose host is empty, `.` or `..` is rejected
+    ///
+    /// ja: `~/.wx/repos` からの `url` のbare cloneの相対パス（`<host>/<owner>/<repo>.git`、
+    /// ローカルのリポジトリは `local/<絶対パス>.git`）。所有者が異なる同名のリポジトリがディレクトリを共有しないようにする
+    /// ホストが空、`.`、`..` のURLはエラーにする
+    pub fn storage_path(&self, url: &str) -> Result<PathBuf, WxError> {
+        let (host, path) = if Path::new(url).exists() {
+            let path = absolute(url).unwrap_or_else(|_| PathBuf::from(url));
+            ("local".to_string(), path.to_string_lossy().to_string())
+        } else {
+            split_remote(url)
+        };
+        if !is_valid_host(&host) {
+            return Err(WxError::InvalidUrl(url.to_string()));
+        }
+
+        let segments: Vec<&str> = path
+            .split(['/', '\\'])
+            .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+            .collect();
+        let mut storage_path = PathBuf::from(host);
+        if let Some((last, owners)) = segments.split_last() {
+            storage_path.extend(owners);
+            storage_path.push(format!("{}.git", last.strip_suffix(".git").unwrap_or(last)));
+        }
+        Ok(storage_path)
+    }
+
+    /// en: Clone the repository as a bare repository, reporting the transfer to `observer`
+    /// and authenticating with `CredentialHandler::load`
+    ///
+    /// ja: リポジトリをbareリポジトリとしてcloneし、転送の進捗を `observer` に報告する
+    /// 認証には `CredentialHandler::load` を使用する
+    pub fn bare_clone(
+        &self,
+        url: &str,
+        target_path: &Path,
+        observer: &dyn TransferObserver,
+    ) -> Result<(), WxError> {
+        let credentials = CredentialHandler::load();
+        let repo = RepoBuilder::new()
             .bare(true)
+            .fetch_options(fetch_options(observer, &credentials))
             .clone(url, target_path)
-            .map(|_| ())
-            .map_err(WxError::GitError)
+            .map_err(|e| credentials.error(e))?;
+
+        self.configure_remote_tracking(&repo)?;
+
+        Ok(())
+    }
+
+    /// en: Make `origin` fetch into `refs/remotes/origin/*`
+    /// Refspecs that write into local branches (e.g. the `+refs/*:refs/*` of a mirror clone)
+    /// are removed so that fetching never moves a branch checked out in a worktree
+    /// Returns whether the configuration was changed
+    ///
+    /// ja: `origin` のフェッチ先を `refs/remotes/origin/*` にする
+    /// ローカルブランチに書き込むrefspec（mirror cloneの `+refs/*:refs/*` など）は、
+    /// worktreeでチェックアウト中のブランチをフェッチで動かさないよう削除する
+    /// 設定を変更したかどうかを返却する
+    pub fn configure_remote_tracking(&self, repo: &Repository) -> Result<bool, WxError> {
+        let remote = repo.find_remote("origin")?;
+        let refspecs: Vec<String> = remote
+            .fetch_refspecs()?
+            .iter()
+            .flatten()
+            .map(str::to_string)
+            .collect();
+
+        let mut config = repo.config()?;
+        let mut changed = false;
+
+        for refspec in &refspecs {
+            let destination = refspec.rsplit(':').next().unwrap_or_default();
+            if destination == "refs/*" || destination.starts_with("refs/heads/") {
+                config.remove_multivar(
+                    "remote.origin.fetch",
+                    &format!("^{}$", regex::escape(refspec)),
+                )?;
+                changed = true;
+            }
+        }
+
+        if !refspecs
+            .iter()
+            .any(|refspec| refspec == ORIGIN_FETCH_REFSPEC)
+        {
+            repo.remote_add_fetch("origin", ORIGIN_FETCH_REFSPEC)?;
+            changed = true;
+        }
+
+        Ok(changed)
+    }
+}
+
+/// en: Fetch options that report the transfer progress to `observer` and ask `credentials`
+/// when the remote requires authentication
+///
+/// ja: 転送の進捗を `observer` に報告し、リモートが認証を要求した場合は `credentials` に問い合わせるフェッチオプション
+pub fn fetch_options<'a>(
+    observer: &'a dyn TransferObserver,
+    credentials: &'a CredentialHandler,
+) -> FetchOptions<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(|url, username_from_url, allowed| {
+        credentials.credentials(url, username_from_url, allowed)
+    });
+    callbacks.transfer_progress(move |progress: Progress| {
+        observer.on_progress(&TransferProgress {
+            received_objects: progress.received_objects(),
+            total_objects: progress.total_objects(),
+            indexed_objects: progress.indexed_objects(),
+            received_bytes: progress.received_bytes(),
+            indexed_deltas: progress.indexed_deltas(),
+            total_deltas: progress.total_deltas(),
+        });
+        true
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    fetch_options
+}
+
+/// en: Split a remote URL into its host and path, for both `scheme://` and SCP-like SSH URLs
+///
+/// ja: リモートURLをホストとパスに分割する（`scheme://` 形式とSCP形式のSSHの両方に対応）
+fn split_remote(url: &str) -> (String, String) {
+    if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        let host = host.split(':').next().unwrap_or(host);
+        (host.to_string(), path.to_string())
+    } else {
+        // SCP-like SSH: git@host:owner/repo.git
+        let (authority, path) = url.split_once(':').unwrap_or((url, ""));
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        (host.to_string(), path.to_string())
     }
 }
 
+/// en: Whether `host` can be used as a directory under `~/.wx/repos`
+///
+/// ja: `host` を `~/.wx/repos` 配下のディレクトリとして使用できるかどうか
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty() && host != "." && host != ".."
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,6 +257,56 @@ mod tests {
         assert!(git_operations
             .validate_url("git@github.com/org/repo.git")
             .is_err());
+
+        assert!(git_operations.validate_url("https://../x/y.git").is_err());
+
+        assert!(git_operations.validate_url("https://./x/y.git").is_err());
+
+        assert!(git_operations.validate_url("git@..:x/y.git").is_err());
+    }
+
+    #[test]
+    fn test_storage_path() {
+        let git_operations = GitOperations;
+
+        assert_eq!(
+            git_operations
+                .storage_path("https://github.com/org-a/api.git")
+                .unwrap(),
+            PathBuf::from("github.com/org-a/api.git")
+        );
+        assert_eq!(
+            git_operations
+                .storage_path("git@github.com:org-b/api")
+                .unwrap(),
+            PathBuf::from("github.com/org-b/api.git")
+        );
+        assert_eq!(
+            git_operations
+                .storage_path("https://user@gitlab.example.com:8443/group/sub/api.git")
+                .unwrap(),
+            PathBuf::from("gitlab.example.com/group/sub/api.git")
+        );
+        assert_eq!(
+            git_operations
+                .storage_path("https://github.com/../../api.git")
+                .unwrap(),
+            PathBuf::from("github.com/api.git")
+        );
+        for url in ["https://../x/y.git", "https://./x/y.git", "git@:x/y.git"] {
+            assert!(matches!(
+                git_operations.storage_path(url),
+                Err(WxError::InvalidUrl(_))
+            ));
+        }
+
+        let (dir, _base_dir) = setup_test_dirs();
+        let source_repo = create_test_git_repo(dir.path(), "source.git");
+        let storage_path = git_operations
+            .storage_path(source_repo.to_str().unwrap())
+            .unwrap();
+        assert!(storage_path.starts_with("local"));
+        assert!(storage_path.ends_with("source.git"));
     }
 
     #[test]
@@ -170,8 +373,65 @@ mod tests {
         let git_operations = GitOperations;
 
         assert!(git_operations
-            .bare_clone(source_repo.to_str().unwrap(), &target_path)
+            .bare_clone(
+                source_repo.to_str().unwrap(),
+                &target_path,
+                &|_: &TransferProgress| {},
+            )
             .is_ok());
         assert!(git2::Repository::open_bare(&target_path).is_ok());
     }
+
+    #[test]
+    fn test_bare_clone_configures_remote_tracking() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let target_path = dir.path().join("target.git");
+        let source_repo = create_test_git_repo(dir.path(), "source");
+
+        GitOperations
+            .bare_clone(
+                source_repo.to_str().unwrap(),
+                &target_path,
+                &|_: &TransferProgress| {},
+            )
+            .unwrap();
+
+        let repo = Repository::open_bare(&target_path).unwrap();
+        let refspecs = repo
+            .find_remote("origin")
+            .unwrap()
+            .fetch_refspecs()
+            .unwrap();
+        assert_eq!(
+            refspecs.iter().flatten().collect::<Vec<_>>(),
+            vec![ORIGIN_FETCH_REFSPEC]
+        );
+    }
+
+    #[test]
+    fn test_configure_remote_tracking_migrates_mirror() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "mirror");
+        let repo = Repository::open_bare(&bare_repo_path).unwrap();
+        repo.remote_with_fetch("origin", "https://example.com/mirror.git", "+refs/*:refs/*")
+            .unwrap();
+        repo.remote_add_fetch("origin", "+refs/pull/*:refs/remotes/origin/pull/*")
+            .unwrap();
+
+        assert!(GitOperations.configure_remote_tracking(&repo).unwrap());
+        assert!(!GitOperations.configure_remote_tracking(&repo).unwrap());
+
+        let refspecs = repo
+            .find_remote("origin")
+            .unwrap()
+            .fetch_refspecs()
+            .unwrap();
+        assert_eq!(
+            refspecs.iter().flatten().collect::<Vec<_>>(),
+            vec![
+                "+refs/pull/*:refs/remotes/origin/pull/*",
+                ORIGIN_FETCH_REFSPEC
+            ]
+        );
+    }
 }
@@ -1,27 +1,53 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use git2::{BranchType, Repository, WorktreeAddOptions, WorktreePruneOptions};
+use git2::{
+    build::CheckoutBuilder, BranchType, FetchPrune, Repository, Status, StatusOptions,
+    WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions,
+};
 use mockall::automock;
 
-use crate::models::WxError;
+use super::{fetch_options, CredentialHandler, GitOperations};
+use crate::models::{
+    status::WorktreeStatus,
+    sync::SyncOutcome,
+    transfer::TransferObserver,
+    workspace::{BranchBase, CreatedWorktree},
+    WxError,
+};
 
 #[automock]
 pub trait WorktreeManager {
-    fn fetch(&self, bare_repo_path: &Path) -> Result<(), WxError>;
+    fn fetch(&self, bare_repo_path: &Path, observer: &dyn TransferObserver) -> Result<(), WxError>;
     fn get_remote_branches(&self, bare_repo_path: &Path) -> Result<Vec<String>, WxError>;
+    fn get_local_branches(&self, bare_repo_path: &Path) -> Result<Vec<String>, WxError>;
+    fn default_branch(&self, bare_repo_path: &Path) -> Result<Option<String>, WxError>;
     fn branch_exists(
         &self,
         bare_repo_path: &Path,
         target_branch_name: &str,
     ) -> Result<bool, WxError>;
-    fn create_worktree(
+    // automock cannot elide the lifetime of a reference inside `Option`
+    #[allow(clippy::needless_lifetimes)]
+    fn create_worktree<'a>(
         &self,
         bare_repo_path: &Path,
         target_path: &Path,
         branch: &str,
-    ) -> Result<(), WxError>;
+        base: Option<&'a str>,
+    ) -> Result<CreatedWorktree, WxError>;
+    fn delete_branch(&self, bare_repo_path: &Path, branch: &str) -> Result<(), WxError>;
     fn list_worktrees(&self, bare_repo_path: &Path) -> Result<Vec<String>, WxError>;
     fn remove_worktree(&self, bare_repo_path: &Path, worktree_name: &str) -> Result<(), WxError>;
+    fn worktree_path(&self, bare_repo_path: &Path, worktree_name: &str)
+        -> Result<PathBuf, WxError>;
+    fn has_uncommitted_changes(&self, worktree_path: &Path) -> Result<bool, WxError>;
+    fn worktree_status(
+        &self,
+        bare_repo_path: &Path,
+        worktree_path: &Path,
+    ) -> Result<WorktreeStatus, WxError>;
+    fn sync_worktree(&self, worktree_path: &Path, rebase: bool) -> Result<SyncOutcome, WxError>;
+    fn bare_repo_of(&self, worktree_path: &Path) -> Result<PathBuf, WxError>;
 }
 
 /// en: Manager for Git worktree operations
@@ -31,15 +57,24 @@ pub trait WorktreeManager {
 pub struct DefaultWorktreeManager;
 
 impl WorktreeManager for DefaultWorktreeManager {
-    /// en: Fetch latest changes from remote repository
+    /// en: Fetch latest changes from remote repository into `refs/remotes/origin/*`,
+    /// pruning deleted branches and setting upstreams of local branches that lack one
     ///
-    /// ja: リモートリポジトリから最新の変更をフェッチ
-    fn fetch(&self, bare_repo_path: &Path) -> Result<(), WxError> {
-        let repo = Repository::open_bare(bare_repo_path)?;
+    /// ja: リモートリポジトリから最新の変更を `refs/remotes/origin/*` にフェッチ
+    /// 削除されたブランチを取り除き、上流が未設定のローカルブランチに上流を設定する
+    fn fetch(&self, bare_repo_path: &Path, observer: &dyn TransferObserver) -> Result<(), WxError> {
+        let repo = open_bare_repo(bare_repo_path)?;
 
         let mut remote = repo.find_remote("origin")?;
 
-        remote.fetch(&[] as &[&str], None, None)?;
+        let credentials = CredentialHandler::load();
+        let mut fetch_options = fetch_options(observer, &credentials);
+        fetch_options.prune(FetchPrune::On);
+        remote
+            .fetch(&[] as &[&str], Some(&mut fetch_options), None)
+            .map_err(|e| credentials.error(e))?;
+
+        set_missing_upstreams(&repo)?;
 
         Ok(())
     }
@@ -63,6 +98,38 @@ impl WorktreeManager for DefaultWorktreeManager {
         Ok(remote_branches)
     }
 
+    /// en: Get all local branches from the bare repository
+    ///
+    /// ja: bareリポジトリから全てのローカルブランチを取得
+    fn get_local_branches(&self, bare_repo_path: &Path) -> Result<Vec<String>, WxError> {
+        let repo = Repository::open_bare(bare_repo_path)?;
+        let branches = repo.branches(Some(BranchType::Local))?;
+
+        let mut local_branches: Vec<String> = Vec::new();
+
+        for branch in branches {
+            let (branch, _branch_type) = branch?;
+            if let Some(branch_name) = branch.name()? {
+                local_branches.push(branch_name.to_string());
+            }
+        }
+
+        Ok(local_branches)
+    }
+
+    /// en: Get the default branch, i.e. the branch HEAD of the bare repository points at
+    ///
+    /// ja: デフォルトブランチ（bareリポジトリのHEADが指すブランチ）を取得
+    fn default_branch(&self, bare_repo_path: &Path) -> Result<Option<String>, WxError> {
+        let repo = Repository::open_bare(bare_repo_path)?;
+        let head = repo.find_reference("HEAD")?;
+
+        Ok(head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .map(str::to_string))
+    }
+
     /// en: Check if a branch exists in the remote repository
     ///
     /// ja: リモートリポジトリにブランチが存在するか確認
@@ -88,55 +155,97 @@ impl WorktreeManager for DefaultWorktreeManager {
     }
 
     /// en: Create a worktree from a bare repository for the specified branch
+    /// A branch missing both locally and on origin is created from `base` (or HEAD when omitted)
+    /// Newly created branches track `origin/<branch>`
     ///
     /// ja: bareリポジトリから指定ブランチのworktreeを作成
+    /// ローカルにもoriginにも無いブランチは `base`（省略時はHEAD）から作成する
+    /// 新しく作成したブランチの上流には `origin/<branch>` を設定する
     fn create_worktree(
         &self,
         bare_repo_path: &Path,
         target_path: &Path,
-        branch: &str,
-    ) -> Result<(), WxError> {
-        let repo = Repository::open_bare(bare_repo_path)?;
+        branch_name: &str,
+        base: Option<&str>,
+    ) -> Result<CreatedWorktree, WxError> {
+        let repo = open_bare_repo(bare_repo_path)?;
+
+        let mut branch_base: Option<BranchBase> = None;
 
         // Try to find local branch first
-        let branch = match repo.find_branch(branch, BranchType::Local) {
+        let mut branch = match repo.find_branch(branch_name, BranchType::Local) {
             Ok(b) => b,
             Err(_) => {
-                // Try to find remote branch
-                match repo.find_branch(&format!("origin/{branch}"), BranchType::Remote) {
-                    Ok(remote) => {
-                        // Create local branch from remote
-                        let commit = remote.get().peel_to_commit()?;
-                        repo.branch(branch, &commit, false)?
-                    }
-                    Err(_) => {
-                        // Branch doesn't exist remotely, create new branch from HEAD
-                        let head = repo.head()?;
-                        let commit = head.peel_to_commit()?;
-                        repo.branch(branch, &commit, false)?
-                    }
-                }
+                let remote_branch = format!("origin/{branch_name}");
+                // Try to find remote branch, otherwise start from the base (or HEAD)
+                let (reference, commit) = match repo.find_branch(&remote_branch, BranchType::Remote)
+                {
+                    Ok(remote) => (remote_branch, remote.get().peel_to_commit()?),
+                    Err(_) => match base {
+                        Some(base) => {
+                            let commit = resolve_base_commit(&repo, base).ok_or_else(|| {
+                                WxError::BranchNotFound(
+                                    base.to_string(),
+                                    bare_repo_path.to_string_lossy().to_string(),
+                                )
+                            })?;
+                            (base.to_string(), commit)
+                        }
+                        None => ("HEAD".to_string(), repo.head()?.peel_to_commit()?),
+                    },
+                };
+
+                let branch = repo.branch(branch_name, &commit, false)?;
+
+                branch_base = Some(BranchBase {
+                    reference,
+                    commit: short_id(&commit),
+                    summary: commit.summary().unwrap_or_default().to_string(),
+                });
+                branch
             }
         };
 
-        let reference = branch.into_reference();
+        let result = (|| {
+            if branch_base.is_some() {
+                set_upstream(&repo, branch_name)?;
+            }
 
-        let mut opts = WorktreeAddOptions::new();
-        opts.reference(Some(&reference));
+            let mut opts = WorktreeAddOptions::new();
+            opts.reference(Some(branch.get()));
 
-        let worktree_name = target_path.file_name().and_then(|n| n.to_str());
+            let worktree_name = unique_worktree_name(&repo, target_path)?;
+            repo.worktree(&worktree_name, target_path, Some(&opts))?;
+            Ok(worktree_name)
+        })();
 
-        match worktree_name {
-            Some(name) => {
-                repo.worktree(name, target_path, Some(&opts))?;
-                Ok(())
+        match result {
+            Ok(worktree_name) => Ok(CreatedWorktree {
+                name: worktree_name,
+                branch_created: branch_base.is_some(),
+                base: branch_base,
+            }),
+            Err(e) => {
+                // the branch created above would otherwise be left behind without a worktree
+                // (`Branch::delete` also removes its `branch.<name>` config section)
+                if branch_base.is_some() {
+                    let _ = branch.delete();
+                }
+                Err(e)
             }
-            None => Err(WxError::InvalidPath(
-                target_path.to_string_lossy().to_string(),
-            )),
         }
     }
 
+    /// en: Delete a local branch from the bare repository
+    ///
+    /// ja: bareリポジトリからローカルブランチを削除
+    fn delete_branch(&self, bare_repo_path: &Path, branch: &str) -> Result<(), WxError> {
+        let repo = Repository::open_bare(bare_repo_path)?;
+        repo.find_branch(branch, BranchType::Local)?.delete()?;
+
+        Ok(())
+    }
+
     /// en: List all worktrees associated with the bare repository
     ///
     /// ja: bareリポジトリに関連付けられた全てのworktreeを一覧表示
@@ -161,14 +270,377 @@ impl WorktreeManager for DefaultWorktreeManager {
 
         Ok(())
     }
+
+    /// en: Returns the working directory path of a worktree registered in the bare repository
+    ///
+    /// ja: bareリポジトリに登録されたworktreeの作業ディレクトリパスを返却する
+    fn worktree_path(
+        &self,
+        bare_repo_path: &Path,
+        worktree_name: &str,
+    ) -> Result<PathBuf, WxError> {
+        let repo = Repository::open_bare(bare_repo_path)?;
+        let worktree = repo.find_worktree(worktree_name)?;
+
+        Ok(worktree.path().to_path_buf())
+    }
+
+    /// en: Check if the worktree has modified, staged or untracked files
+    ///
+    /// ja: worktreeに変更・ステージ済み・未追跡のファイルがあるか確認
+    fn has_uncommitted_changes(&self, worktree_path: &Path) -> Result<bool, WxError> {
+        let repo = Repository::open(worktree_path)?;
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true).include_ignored(false);
+
+        let statuses = repo.statuses(Some(&mut opts))?;
+
+        Ok(!statuses.is_empty())
+    }
+
+    /// en: Collect branch, upstream divergence, change counts, stashes and lock state of a worktree
+    /// A worktree whose directory is gone is reported as missing instead of failing
+    ///
+    /// ja: worktreeのブランチ、上流との差分、変更数、stash数、ロック状態を取得
+    /// ディレクトリが存在しないworktreeはエラーにせず、missingとして返却する
+    fn worktree_status(
+        &self,
+        bare_repo_path: &Path,
+        worktree_path: &Path,
+    ) -> Result<WorktreeStatus, WxError> {
+        let bare_repo = Repository::open_bare(bare_repo_path)?;
+        let locked = find_worktree_by_path(&bare_repo, worktree_path)?
+            .map(|worktree| matches!(worktree.is_locked(), Ok(WorktreeLockStatus::Locked(_))))
+            .unwrap_or(false);
+
+        if !worktree_path.exists() {
+            return Ok(WorktreeStatus {
+                locked,
+                missing: true,
+                ..WorktreeStatus::default()
+            });
+        }
+
+        let mut repo = Repository::open(worktree_path)?;
+        let mut status = WorktreeStatus {
+            locked,
+            ..WorktreeStatus::default()
+        };
+
+        if let Ok(head) = repo.head() {
+            if head.is_branch() {
+                let branch = git2::Branch::wrap(head);
+                status.branch = branch.name()?.map(str::to_string);
+
+                if let Ok(upstream) = branch.upstream() {
+                    status.upstream = upstream.name()?.map(str::to_string);
+                    if let (Some(local), Some(remote)) =
+                        (branch.get().target(), upstream.get().target())
+                    {
+                        (status.ahead, status.behind) = repo.graph_ahead_behind(local, remote)?;
+                    }
+                }
+            }
+        }
+
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        {
+            let statuses = repo.statuses(Some(&mut options))?;
+            for entry in statuses.iter() {
+                let flags = entry.status();
+                if flags.intersects(
+                    Status::INDEX_NEW
+                        | Status::INDEX_MODIFIED
+                        | Status::INDEX_DELETED
+                        | Status::INDEX_RENAMED
+                        | Status::INDEX_TYPECHANGE,
+                ) {
+                    status.staged += 1;
+                }
+                if flags.intersects(
+                    Status::WT_MODIFIED
+                        | Status::WT_DELETED
+                        | Status::WT_RENAMED
+                        | Status::WT_TYPECHANGE,
+                ) {
+                    status.dirty += 1;
+                }
+                if flags.contains(Status::WT_NEW) {
+                    status.untracked += 1;
+                }
+            }
+        }
+
+        // stashes are shared by every worktree of the repository; count those made on this branch,
+        // whose messages read "WIP on <branch>: ..." or "On <branch>: ..."
+        let stash_branch = status.branch.as_deref().unwrap_or("(no branch)");
+        repo.stash_foreach(|_, message, _| {
+            let on_branch = message
+                .strip_prefix("WIP on ")
+                .or_else(|| message.strip_prefix("On "))
+                .and_then(|rest| rest.split_once(": "))
+                .is_some_and(|(branch, _)| branch == stash_branch);
+            if on_branch {
+                status.stashes += 1;
+            }
+            true
+        })?;
+
+        Ok(status)
+    }
+
+    /// en: Bring the checked-out branch of a worktree up to its already fetched upstream
+    /// Fast-forwards when possible, replays local commits with `rebase`, and otherwise
+    /// leaves dirty or diverged worktrees untouched
+    ///
+    /// ja: worktreeでチェックアウト中のブランチを、フェッチ済みの上流に追従させる
+    /// 可能であればfast-forwardし、`rebase` 指定時はローカルコミットを付け替える
+    /// それ以外の場合、変更のある・分岐したworktreeは変更しない
+    fn sync_worktree(&self, worktree_path: &Path, rebase: bool) -> Result<SyncOutcome, WxError> {
+        if !worktree_path.exists() {
+            return Ok(SyncOutcome::Missing);
+        }
+
+        let repo = Repository::open(worktree_path)?;
+        let head = repo.head()?;
+        if !head.is_branch() {
+            return Ok(SyncOutcome::Detached);
+        }
+        let branch = git2::Branch::wrap(head);
+        let Ok(upstream) = branch.upstream() else {
+            return Ok(SyncOutcome::NoUpstream);
+        };
+        let (Some(local_oid), Some(upstream_oid)) =
+            (branch.get().target(), upstream.get().target())
+        else {
+            return Ok(SyncOutcome::NoUpstream);
+        };
+
+        let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid)?;
+        if behind == 0 {
+            return Ok(SyncOutcome::UpToDate { ahead });
+        }
+        if has_tracked_changes(&repo)? {
+            return Ok(SyncOutcome::SkippedDirty);
+        }
+
+        if ahead == 0 {
+            let target = repo.find_object(upstream_oid, None)?;
+            repo.checkout_tree(&target, Some(CheckoutBuilder::new().safe()))?;
+            branch
+                .into_reference()
+                .set_target(upstream_oid, "wx sync: fast-forward")?;
+            return Ok(SyncOutcome::FastForwarded { commits: behind });
+        }
+
+        if !rebase {
+            return Ok(SyncOutcome::SkippedDiverged { ahead, behind });
+        }
+
+        let local = repo.reference_to_annotated_commit(&branch.into_reference())?;
+        let onto = repo.reference_to_annotated_commit(upstream.get())?;
+        let mut rebase = repo.rebase(Some(&local), Some(&onto), None, None)?;
+
+        while let Some(operation) = rebase.next() {
+            let operation = operation?;
+            if repo.index()?.has_conflicts() {
+                rebase.abort()?;
+                return Ok(SyncOutcome::SkippedConflict);
+            }
+            let committer = match repo.signature() {
+                Ok(signature) => signature,
+                Err(_) => repo.find_commit(operation.id())?.committer().to_owned(),
+            };
+            if let Err(e) = rebase.commit(None, &committer, None) {
+                // a commit whose changes are already upstream becomes empty and is dropped
+                if e.code() != git2::ErrorCode::Applied {
+                    rebase.abort()?;
+                    return Err(e.into());
+                }
+            }
+        }
+        rebase.finish(None)?;
+
+        Ok(SyncOutcome::Rebased { commits: ahead })
+    }
+
+    /// en: Returns the bare repository a worktree was created from
+    ///
+    /// ja: worktreeの作成元のbareリポジトリを返却する
+    fn bare_repo_of(&self, worktree_path: &Path) -> Result<PathBuf, WxError> {
+        let repo = Repository::open(worktree_path)?;
+        let common_dir = repo.commondir();
+
+        Ok(common_dir
+            .canonicalize()
+            .unwrap_or_else(|_| common_dir.to_path_buf()))
+    }
+}
+
+/// en: Whether tracked files have staged or unstaged changes (untracked files are ignored)
+///
+/// ja: 追跡対象のファイルにステージ済み・未ステージの変更があるか（未追跡ファイルは無視する）
+fn has_tracked_changes(repo: &Repository) -> Result<bool, WxError> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options))?;
+
+    Ok(!statuses.is_empty())
+}
+
+/// en: Find the worktree of the bare repository checked out at `worktree_path`
+///
+/// ja: `worktree_path` にチェックアウトされたbareリポジトリのworktreeを探す
+fn find_worktree_by_path(
+    repo: &Repository,
+    worktree_path: &Path,
+) -> Result<Option<git2::Worktree>, WxError> {
+    let worktree_path = worktree_path
+        .canonicalize()
+        .unwrap_or_else(|_| worktree_path.to_path_buf());
+
+    for name in repo.worktrees()?.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        let path = worktree
+            .path()
+            .canonicalize()
+            .unwrap_or_else(|_| worktree.path().to_path_buf());
+        if path == worktree_path {
+            return Ok(Some(worktree));
+        }
+    }
+
+    Ok(None)
+}
+
+/// en: Resolve a base ref such as `develop`, `origin/develop` or a commit id to a commit
+/// A branch name prefers the freshly fetched `origin/<base>` over the local branch
+///
+/// ja: `develop`、`origin/develop`、コミットIDなどのベース参照をコミットに解決する
+/// ブランチ名はローカルブランチよりもフェッチ済みの `origin/<base>` を優先する
+fn resolve_base_commit<'r>(repo: &'r Repository, base: &str) -> Option<git2::Commit<'r>> {
+    [format!("origin/{base}"), base.to_string()]
+        .iter()
+        .find_map(|spec| repo.revparse_single(spec).ok())
+        .and_then(|object| object.peel_to_commit().ok())
+}
+
+/// en: Open a bare repository, migrating its `origin` fetch refspec to remote-tracking refs
+/// Repositories registered before wx configured the refspec are fixed on first use
+///
+/// ja: bareリポジトリを開き、`origin` のフェッチrefspecをリモート追跡refへ移行する
+/// refspec設定前に登録されたリポジトリは初回使用時に修正される
+fn open_bare_repo(bare_repo_path: &Path) -> Result<Repository, WxError> {
+    let repo = Repository::open_bare(bare_repo_path)?;
+    if repo.find_remote("origin").is_ok() {
+        GitOperations.configure_remote_tracking(&repo)?;
+    }
+
+    Ok(repo)
+}
+
+/// en: Set `origin/<branch>` as upstream of local branches that have none and exist on origin
+///
+/// ja: 上流が未設定で、originに存在するローカルブランチの上流を `origin/<branch>` に設定する
+fn set_missing_upstreams(repo: &Repository) -> Result<(), WxError> {
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _branch_type) = branch?;
+        let Some(name) = branch.name()? else {
+            continue;
+        };
+
+        let has_upstream = repo
+            .config()?
+            .get_string(&format!("branch.{name}.remote"))
+            .is_ok();
+        if !has_upstream
+            && repo
+                .find_branch(&format!("origin/{name}"), BranchType::Remote)
+                .is_ok()
+        {
+            set_upstream(repo, name)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// en: Make the local branch track `origin/<branch>`, even before it is pushed
+///
+/// ja: ローカルブランチの上流を `origin/<branch>` に設定する（push前でも設定する）
+fn set_upstream(repo: &Repository, branch: &str) -> Result<(), WxError> {
+    let mut config = repo.config()?;
+    config.set_str(&format!("branch.{branch}.remote"), "origin")?;
+    config.set_str(
+        &format!("branch.{branch}.merge"),
+        &format!("refs/heads/{branch}"),
+    )?;
+
+    Ok(())
+}
+
+/// en: Abbreviated commit id as shown by `git log --oneline`
+///
+/// ja: `git log --oneline` と同様の短縮コミットID
+fn short_id(commit: &git2::Commit) -> String {
+    commit
+        .as_object()
+        .short_id()
+        .ok()
+        .and_then(|id| id.as_str().map(str::to_string))
+        .unwrap_or_else(|| commit.id().to_string()[..7].to_string())
+}
+
+/// en: Returns a worktree name not yet used in the bare repository
+/// Uses the directory name, then `<workspace>-<directory>`, then a numeric suffix
+///
+/// ja: bareリポジトリで未使用のworktree名を返却する
+/// ディレクトリ名、`<workspace>-<ディレクトリ名>`、連番付きの順に試す
+fn unique_worktree_name(repo: &Repository, target_path: &Path) -> Result<String, WxError> {
+    let dir_name = target_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| WxError::InvalidPath(target_path.to_string_lossy().to_string()))?;
+
+    let existing = repo.worktrees()?;
+    let is_taken = |name: &str| {
+        existing.iter().flatten().any(|w| w == name)
+            || repo.path().join("worktrees").join(name).exists()
+    };
+
+    if !is_taken(dir_name) {
+        return Ok(dir_name.to_string());
+    }
+
+    let base = match target_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+    {
+        Some(workspace_name) => format!("{workspace_name}-{dir_name}"),
+        None => dir_name.to_string(),
+    };
+
+    if !is_taken(&base) {
+        return Ok(base);
+    }
+
+    Ok((2..)
+        .map(|i| format!("{base}-{i}"))
+        .find(|name| !is_taken(name))
+        .unwrap())
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::models::transfer::TransferProgress;
     use crate::utils::test_helpers::{
-        add_test_remote_branch, create_test_bare_repo, setup_test_dirs,
+        add_test_remote_branch, create_test_bare_repo, create_test_git_repo, setup_test_dirs,
     };
     use git2::Repository;
+    use std::fs;
 
     use super::*;
 
@@ -191,6 +663,82 @@ mod tests {
         assert_eq!(remote_branches.len(), 2);
     }
 
+    #[test]
+    fn test_worktree_manager_fetch_migrates_mirror_clone() {
+        let (dir, base_dir) = setup_test_dirs();
+        let source_path = create_test_git_repo(dir.path(), "source");
+        let source = Repository::open(&source_path).unwrap();
+        let sig = git2::Signature::now("m4i", "test@example.com").unwrap();
+        let tree = source
+            .find_tree(source.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let commit = source
+            .commit(Some("refs/heads/main"), &sig, &sig, "Init", &tree, &[])
+            .unwrap();
+        source
+            .branch("feature", &source.find_commit(commit).unwrap(), false)
+            .unwrap();
+
+        // a bare repository cloned as a mirror, without remote-tracking refs
+        let bare_repo_path = create_test_bare_repo(&base_dir, "source");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        bare_repo
+            .remote_with_fetch("origin", source_path.to_str().unwrap(), "+refs/*:refs/*")
+            .unwrap()
+            .fetch(&[] as &[&str], None, None)
+            .unwrap();
+        assert!(bare_repo
+            .find_branch("origin/main", BranchType::Remote)
+            .is_err());
+
+        DefaultWorktreeManager
+            .fetch(&bare_repo_path, &|_: &TransferProgress| {})
+            .unwrap();
+
+        let remote_branches = DefaultWorktreeManager
+            .get_remote_branches(&bare_repo_path)
+            .unwrap();
+        assert!(remote_branches.contains(&"origin/main".to_string()));
+        assert!(remote_branches.contains(&"origin/feature".to_string()));
+        assert!(DefaultWorktreeManager
+            .branch_exists(&bare_repo_path, "feature")
+            .unwrap());
+        let config = bare_repo.config().unwrap();
+        assert_eq!(config.get_string("branch.main.remote").unwrap(), "origin");
+        assert_eq!(
+            config.get_string("branch.main.merge").unwrap(),
+            "refs/heads/main"
+        );
+    }
+
+    #[test]
+    fn test_worktree_manager_local_and_default_branches() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        let commit = bare_repo
+            .find_reference("refs/remotes/origin/main")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap();
+        bare_repo.branch("main", &commit, false).unwrap();
+        bare_repo.set_head("refs/heads/main").unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+
+        assert_eq!(
+            worktree_manager
+                .get_local_branches(&bare_repo_path)
+                .unwrap(),
+            vec!["main".to_string()]
+        );
+        assert_eq!(
+            worktree_manager.default_branch(&bare_repo_path).unwrap(),
+            Some("main".to_string())
+        );
+    }
+
     #[test]
     fn test_worktree_manager_branch_exists() {
         let (_dir, base_dir) = setup_test_dirs();
@@ -218,4 +766,442 @@ mod tests {
             .branch_exists(&bare_repo_path, "feature")
             .unwrap());
     }
+
+    #[test]
+    fn test_worktree_manager_worktree_path() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        let target_path = dir.path().join("work/main");
+        fs::create_dir_all(target_path.parent().unwrap()).unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+        worktree_manager
+            .create_worktree(&bare_repo_path, &target_path, "main", None)
+            .unwrap();
+
+        let path = worktree_manager
+            .worktree_path(&bare_repo_path, "main")
+            .unwrap();
+
+        assert_eq!(
+            path.canonicalize().unwrap(),
+            target_path.canonicalize().unwrap()
+        );
+        assert!(worktree_manager
+            .worktree_path(&bare_repo_path, "missing")
+            .is_err());
+    }
+
+    #[test]
+    fn test_worktree_manager_has_uncommitted_changes() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        let target_path = dir.path().join("work/main");
+        fs::create_dir_all(target_path.parent().unwrap()).unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+        worktree_manager
+            .create_worktree(&bare_repo_path, &target_path, "main", None)
+            .unwrap();
+
+        assert!(!worktree_manager
+            .has_uncommitted_changes(&target_path)
+            .unwrap());
+
+        fs::write(target_path.join("notes.txt"), "wip").unwrap();
+
+        assert!(worktree_manager
+            .has_uncommitted_changes(&target_path)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_worktree_manager_create_worktree_unique_names() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        add_test_remote_branch(&bare_repo, "auth");
+        add_test_remote_branch(&bare_repo, "dashboard");
+
+        let worktree_manager = DefaultWorktreeManager;
+        for (workspace, branch) in [
+            ("feat-a", "main"),
+            ("feat-b", "auth"),
+            ("feat-b2", "dashboard"),
+        ] {
+            let target_path = dir.path().join(workspace).join("frontend");
+            fs::create_dir_all(target_path.parent().unwrap()).unwrap();
+            worktree_manager
+                .create_worktree(&bare_repo_path, &target_path, branch, None)
+                .unwrap();
+        }
+
+        let mut names = worktree_manager.list_worktrees(&bare_repo_path).unwrap();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["feat-b-frontend", "feat-b2-frontend", "frontend"]
+        );
+    }
+
+    #[test]
+    fn test_worktree_manager_create_worktree_reports_created_branch() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+
+        let worktree_manager = DefaultWorktreeManager;
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::create_dir_all(dir.path().join("b")).unwrap();
+
+        let created = worktree_manager
+            .create_worktree(&bare_repo_path, &dir.path().join("a/main"), "main", None)
+            .unwrap();
+        assert_eq!(created.name, "main");
+        assert!(created.branch_created);
+        assert_eq!(created.base.unwrap().reference, "origin/main");
+
+        worktree_manager
+            .remove_worktree(&bare_repo_path, &created.name)
+            .unwrap();
+
+        let created = worktree_manager
+            .create_worktree(&bare_repo_path, &dir.path().join("b/main"), "main", None)
+            .unwrap();
+        assert!(!created.branch_created);
+        assert_eq!(created.base, None);
+
+        worktree_manager
+            .remove_worktree(&bare_repo_path, &created.name)
+            .unwrap();
+        assert!(worktree_manager
+            .delete_branch(&bare_repo_path, "main")
+            .is_ok());
+        assert!(bare_repo.find_branch("main", BranchType::Local).is_err());
+    }
+
+    #[test]
+    fn test_worktree_manager_create_worktree_failure_deletes_created_branch() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        bare_repo.set_head("refs/remotes/origin/main").unwrap();
+
+        // a file in the way makes adding the worktree fail after the branch was created
+        let target_path = dir.path().join("work/feature");
+        fs::create_dir_all(dir.path().join("work")).unwrap();
+        fs::write(&target_path, "not a directory").unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+        assert!(worktree_manager
+            .create_worktree(&bare_repo_path, &target_path, "feature", None)
+            .is_err());
+        assert!(bare_repo.find_branch("feature", BranchType::Local).is_err());
+        let config = bare_repo.config().unwrap().snapshot().unwrap();
+        assert!(config.get_string("branch.feature.remote").is_err());
+        assert!(config.get_string("branch.feature.merge").is_err());
+
+        // an existing branch is kept
+        bare_repo
+            .branch(
+                "feature",
+                &bare_repo.head().unwrap().peel_to_commit().unwrap(),
+                false,
+            )
+            .unwrap();
+        assert!(worktree_manager
+            .create_worktree(&bare_repo_path, &target_path, "feature", None)
+            .is_err());
+        assert!(bare_repo.find_branch("feature", BranchType::Local).is_ok());
+    }
+
+    #[test]
+    fn test_worktree_manager_create_worktree_from_base() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        bare_repo.set_head("refs/remotes/origin/main").unwrap();
+        let main = bare_repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("m4i", "test@example.com").unwrap();
+        bare_repo
+            .commit(
+                Some("refs/remotes/origin/develop"),
+                &sig,
+                &sig,
+                "Develop",
+                &main.tree().unwrap(),
+                &[&main],
+            )
+            .unwrap();
+        fs::create_dir_all(dir.path().join("work")).unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+        let created = worktree_manager
+            .create_worktree(
+                &bare_repo_path,
+                &dir.path().join("work/feature"),
+                "feature",
+                Some("develop"),
+            )
+            .unwrap();
+
+        let base = created.base.unwrap();
+        assert_eq!(base.reference, "develop");
+        assert_eq!(base.summary, "Develop");
+        let config = bare_repo.config().unwrap();
+        assert_eq!(
+            config.get_string("branch.feature.remote").unwrap(),
+            "origin"
+        );
+        assert_eq!(
+            config.get_string("branch.feature.merge").unwrap(),
+            "refs/heads/feature"
+        );
+
+        let develop = bare_repo
+            .find_reference("refs/remotes/origin/develop")
+            .unwrap()
+            .target()
+            .unwrap();
+        let feature = bare_repo
+            .find_branch("feature", BranchType::Local)
+            .unwrap()
+            .get()
+            .target()
+            .unwrap();
+        assert_eq!(feature, develop);
+        assert_ne!(feature, main.id());
+
+        assert!(matches!(
+            worktree_manager.create_worktree(
+                &bare_repo_path,
+                &dir.path().join("work/other"),
+                "other",
+                Some("missing"),
+            ),
+            Err(WxError::BranchNotFound(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_worktree_manager_worktree_status_counts_stashes_of_its_branch() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        add_test_remote_branch(&bare_repo, "auth");
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::create_dir_all(dir.path().join("b")).unwrap();
+        let main_path = dir.path().join("a/frontend");
+        let auth_path = dir.path().join("b/frontend");
+
+        let worktree_manager = DefaultWorktreeManager;
+        worktree_manager
+            .create_worktree(&bare_repo_path, &main_path, "main", None)
+            .unwrap();
+        worktree_manager
+            .create_worktree(&bare_repo_path, &auth_path, "auth", None)
+            .unwrap();
+
+        let mut repo = Repository::open(&auth_path).unwrap();
+        let sig = git2::Signature::now("m4i", "test@example.com").unwrap();
+        for (file, message) in [("a.txt", None), ("b.txt", Some("login"))] {
+            fs::write(auth_path.join(file), "wip").unwrap();
+            repo.stash_save2(&sig, message, Some(git2::StashFlags::INCLUDE_UNTRACKED))
+                .unwrap();
+        }
+
+        let stashes = |path: &Path| {
+            worktree_manager
+                .worktree_status(&bare_repo_path, path)
+                .unwrap()
+                .stashes
+        };
+        assert_eq!(stashes(&auth_path), 2);
+        assert_eq!(stashes(&main_path), 0);
+    }
+
+    #[test]
+    fn test_worktree_manager_worktree_status() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        bare_repo
+            .remote("origin", "https://example.com/frontend.git")
+            .unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        let worktree_path = dir.path().join("work/frontend");
+        fs::create_dir_all(dir.path().join("work")).unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+        let created = worktree_manager
+            .create_worktree(&bare_repo_path, &worktree_path, "main", None)
+            .unwrap();
+
+        let status = worktree_manager
+            .worktree_status(&bare_repo_path, &worktree_path)
+            .unwrap();
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert!(status.is_clean());
+
+        // one local commit, one staged file, one untracked file
+        let repo = Repository::open(&worktree_path).unwrap();
+        let sig = git2::Signature::now("m4i", "test@example.com").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "Local",
+            &head.tree().unwrap(),
+            &[&head],
+        )
+        .unwrap();
+        fs::write(worktree_path.join("staged.txt"), "staged").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+        fs::write(worktree_path.join("untracked.txt"), "untracked").unwrap();
+        bare_repo
+            .find_worktree(&created.name)
+            .unwrap()
+            .lock(None)
+            .unwrap();
+
+        let status = worktree_manager
+            .worktree_status(&bare_repo_path, &worktree_path)
+            .unwrap();
+        assert_eq!((status.ahead, status.behind), (1, 0));
+        assert_eq!(status.staged, 1);
+        assert_eq!(status.untracked, 1);
+        assert_eq!(status.dirty, 0);
+        assert!(status.locked);
+        assert!(!status.missing);
+
+        fs::remove_dir_all(&worktree_path).unwrap();
+        let status = worktree_manager
+            .worktree_status(&bare_repo_path, &worktree_path)
+            .unwrap();
+        assert!(status.missing);
+        assert!(status.locked);
+    }
+
+    /// Commit a single file on top of `parent` and point `refname` at the new commit
+    fn commit_file(
+        repo: &Repository,
+        refname: &str,
+        parent: git2::Oid,
+        file: &str,
+        content: &str,
+    ) -> git2::Oid {
+        let parent = repo.find_commit(parent).unwrap();
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut tree_builder = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+        tree_builder.insert(file, blob, 0o100644).unwrap();
+        let tree = repo.find_tree(tree_builder.write().unwrap()).unwrap();
+        let sig = git2::Signature::now("m4i", "test@example.com").unwrap();
+        let oid = repo
+            .commit(None, &sig, &sig, file, &tree, &[&parent])
+            .unwrap();
+        repo.reference(refname, oid, true, "test").unwrap();
+        oid
+    }
+
+    #[test]
+    fn test_worktree_manager_sync_worktree() {
+        let (dir, base_dir) = setup_test_dirs();
+        let bare_repo_path = create_test_bare_repo(&base_dir, "frontend");
+        let bare_repo = Repository::open_bare(&bare_repo_path).unwrap();
+        bare_repo
+            .remote("origin", "https://example.com/frontend.git")
+            .unwrap();
+        add_test_remote_branch(&bare_repo, "main");
+        let upstream_ref = "refs/remotes/origin/main";
+        let worktree_path = dir.path().join("work/frontend");
+        fs::create_dir_all(dir.path().join("work")).unwrap();
+
+        let worktree_manager = DefaultWorktreeManager;
+        worktree_manager
+            .create_worktree(&bare_repo_path, &worktree_path, "main", None)
+            .unwrap();
+        assert_eq!(
+            worktree_manager
+                .sync_worktree(&worktree_path, false)
+                .unwrap(),
+            SyncOutcome::UpToDate { ahead: 0 }
+        );
+
+        // upstream moves ahead: fast-forward
+        let upstream = bare_repo.refname_to_id(upstream_ref).unwrap();
+        let upstream = commit_file(&bare_repo, upstream_ref, upstream, "a.txt", "a");
+        assert_eq!(
+            worktree_manager
+                .sync_worktree(&worktree_path, false)
+                .unwrap(),
+            SyncOutcome::FastForwarded { commits: 1 }
+        );
+        let repo = Repository::open(&worktree_path).unwrap();
+        assert_eq!(repo.head().unwrap().target().unwrap(), upstream);
+        assert_eq!(
+            fs::read_to_string(worktree_path.join("a.txt")).unwrap(),
+            "a"
+        );
+
+        // uncommitted changes block the update
+        let upstream = commit_file(&bare_repo, upstream_ref, upstream, "b.txt", "b");
+        fs::write(worktree_path.join("a.txt"), "changed").unwrap();
+        assert_eq!(
+            worktree_manager
+                .sync_worktree(&worktree_path, false)
+                .unwrap(),
+            SyncOutcome::SkippedDirty
+        );
+        fs::write(worktree_path.join("a.txt"), "a").unwrap();
+
+        // local commits diverge from upstream: skipped unless rebasing
+        let head = repo.head().unwrap().target().unwrap();
+        commit_file(&repo, "refs/heads/main", head, "c.txt", "c");
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        assert_eq!(
+            worktree_manager
+                .sync_worktree(&worktree_path, false)
+                .unwrap(),
+            SyncOutcome::SkippedDiverged {
+                ahead: 1,
+                behind: 1
+            }
+        );
+        assert_eq!(
+            worktree_manager
+                .sync_worktree(&worktree_path, true)
+                .unwrap(),
+            SyncOutcome::Rebased { commits: 1 }
+        );
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_id(0).unwrap(), upstream);
+        assert!(worktree_path.join("b.txt").exists());
+        assert!(worktree_path.join("c.txt").exists());
+
+        assert_eq!(
+            worktree_manager.bare_repo_of(&worktree_path).unwrap(),
+            bare_repo_path.canonicalize().unwrap()
+        );
+        fs::remove_dir_all(&worktree_path).unwrap();
+        assert_eq!(
+            worktree_manager
+                .sync_worktree(&worktree_path, false)
+                .unwrap(),
+            SyncOutcome::Missing
+        );
+    }
 }
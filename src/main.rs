@@ -1,10 +1,18 @@
-use clap::Parser;
-use console::style;
-use ptree::{print_config::StyleWhen, print_tree_with, Color, PrintConfig, Style, TreeBuilder};
+use std::{
+    io::{stdout, IsTerminal},
+    sync::Mutex,
+};
+
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 
 use crate::{
-    cli::{Cli, Commands},
-    models::WxError,
+    cli::{Cli, Commands, GroupCommands, WorkspaceCommands},
+    models::{workspace::CleanTarget, WxError},
+    presentation::{
+        print_exec_line, print_welcome, Added, ExecOutputLine, ExecRun, ExecWorktree, GroupCreated,
+        GroupRemoved, OutputFormat, Presenter, Removed, ShellScript, Unregistered, WorkspaceDir,
+    },
 };
 
 // Module declarations
@@ -12,108 +20,261 @@ pub mod cli;
 pub mod commands;
 pub mod infrastructure;
 pub mod models;
+pub mod presentation;
 pub mod services;
 pub mod tui;
 pub mod utils;
 
-fn print_welcome() {
-    // ASCII art logo for wx (block style)
-    let logo_lines = [
-        "██╗    ██╗██╗  ██╗",
-        "██║    ██║╚██╗██╔╝",
-        "██║ █╗ ██║ ╚███╔╝ ",
-        "██║███╗██║ ██╔██╗ ",
-        "╚███╔███╔╝██╔╝ ██╗",
-        " ╚══╝╚══╝ ╚═╝  ╚═╝",
-    ];
-
-    println!();
-    for line in &logo_lines {
-        println!("  {}", style(*line).cyan());
-    }
-    println!();
-    println!(
-        "  {}",
-        style("Git worktree & workspace manager").italic().dim()
-    );
-    println!();
-    println!(
-        "  {}  {}",
-        style("wx list").cyan(),
-        style("List registered repositories").dim()
-    );
-    println!(
-        "  {}  {}",
-        style("wx register <repository url>").cyan(),
-        style("Register a Git repository").dim()
-    );
-    println!(
-        "  {}  {}",
-        style("wx new <workspace name>").cyan(),
-        style("Create a new workspace").dim()
-    );
-    println!();
+fn open_workspace(
+    presenter: &Presenter,
+    workspace: Option<&str>,
+    editor: Option<&str>,
+) -> Result<(), WxError> {
+    let target = commands::open::execute(workspace, editor)?;
+    presenter.render(&target)
 }
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
+    // answers completion requests from the script registered by `wx shell-init`
+    CompleteEnv::with_factory(Cli::command).complete();
 
     let cli = Cli::parse();
+    let presenter = Presenter::new(cli.output);
+
+    if let Err(e) = run(cli.command, &presenter) {
+        presenter.render_error(e)?;
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    match cli.command {
-        None => {
-            print_welcome();
+fn run(command: Option<Commands>, presenter: &Presenter) -> Result<(), WxError> {
+    match command {
+        None if presenter.format() == OutputFormat::Text && stdout().is_terminal() => tui::run()?,
+        None => print_welcome(),
+        Some(Commands::Tui) => tui::run()?,
+        Some(Commands::Register {
+            urls,
+            name,
+            jobs,
+            base,
+        }) => {
+            let bars = presenter.transfer_bars();
+            let reports = commands::register::execute(
+                &urls,
+                name.as_deref(),
+                base,
+                jobs,
+                |index, name, progress| bars.update(index, name, progress),
+            )?;
+            drop(bars);
+            presenter.render(reports.as_slice())?;
+            if reports.iter().any(|report| report.is_failure()) {
+                std::process::exit(1);
+            }
         }
-        Some(Commands::Register { url }) => match commands::register::execute(&url) {
-            Ok(_) => println!("Registered: {}", style(url).cyan()),
-            Err(e) => match e {
-                WxError::AlreadyRegistered(_) => {
-                    println!(
-                        "{} {}",
-                        style("Already registered:").yellow(),
-                        style(url).cyan()
-                    )
-                }
-                _ => return Err(e.into()),
-            },
-        },
-        Some(Commands::List) => match commands::list::execute() {
-            Ok(repos) => {
-                if repos.is_empty() {
-                    println!("{}", style("No registered repositories.").yellow())
-                } else {
-                    let config = PrintConfig {
-                        styled: StyleWhen::Always,
-                        leaf: Style {
-                            foreground: Some(Color::Cyan),
-                            ..Style::default()
-                        },
-                        ..PrintConfig::default()
-                    };
-                    for repo in &repos {
-                        let tree = TreeBuilder::new(repo.name.clone())
-                            .add_empty_child(repo.remote.clone())
-                            .build();
-                        print_tree_with(&tree, &config)?;
-                    }
-                }
+        Some(Commands::List) => presenter.render(commands::list::execute()?.as_slice())?,
+        Some(Commands::Fetch { repos, jobs, .. }) => {
+            let bars = presenter.transfer_bars();
+            let reports = commands::fetch::execute(&repos, jobs, |index, name, progress| {
+                bars.update(index, name, progress)
+            })?;
+            drop(bars);
+            presenter.render(reports.as_slice())?;
+            if reports.iter().any(|report| report.error.is_some()) {
+                std::process::exit(1);
             }
-            Err(e) => return Err(e.into()),
-        },
-        Some(Commands::New { workspace_name }) => match commands::new::execute(workspace_name) {
-            Ok(_) => {
-                println!("{}", style("Workspace created.").green());
+        }
+        Some(Commands::Group { command }) => match command {
+            GroupCommands::Create { name, repos } => {
+                presenter.render(&GroupCreated(commands::group::create(&name, &repos)?))?
+            }
+            GroupCommands::List => presenter.render(commands::group::list()?.as_slice())?,
+            GroupCommands::Rm { name } => {
+                presenter.render(&GroupRemoved(commands::group::remove(&name)?))?
             }
-            Err(e) => match e {
-                WxError::General(e) => {
-                    println!("{}", style(e).red())
-                }
-                _ => return Err(e.into()),
-            },
         },
-        Some(Commands::Unregister { name: _name }) => {
-            todo!()
+        Some(Commands::New {
+            workspace_name,
+            from: Some(manifest),
+            open,
+            ..
+        }) => {
+            let result = commands::apply::execute(&manifest, workspace_name, true)?;
+            presenter.render(&result)?;
+            if open {
+                open_after_create(presenter, &result.workspace_dir.to_string_lossy())?;
+            }
+        }
+        Some(Commands::New {
+            workspace_name,
+            layout,
+            repos,
+            groups,
+            all_repos,
+            branch,
+            base,
+            template,
+            formats,
+            open,
+            ..
+        }) => {
+            let workspace_name = workspace_name.unwrap_or_default();
+            let options = commands::new::NewOptions {
+                layout,
+                repos,
+                groups,
+                all_repos,
+                branch,
+                base,
+                template,
+                formats,
+            };
+            let bars = presenter.transfer_bars();
+            let result = commands::new::execute(
+                workspace_name.clone(),
+                options,
+                |index, name, progress| bars.update(index, name, progress),
+            )?;
+            drop(bars);
+            presenter.render(&result)?;
+            if open {
+                open_after_create(presenter, &workspace_name)?;
+            }
+        }
+        // an error exits with a failing status, so the shell function does not change directory
+        Some(Commands::Cd { target }) => {
+            let path = commands::cd::execute(target.as_deref())?;
+            presenter.render(&WorkspaceDir { path })?
+        }
+        Some(Commands::ShellInit { shell }) => presenter.render(&ShellScript {
+            shell,
+            script: commands::shell_init::execute(shell),
+        })?,
+        Some(Commands::Open { workspace, editor }) => {
+            open_workspace(presenter, workspace.as_deref(), editor.as_deref())?
+        }
+        Some(Commands::Apply { manifest }) => {
+            presenter.render(&commands::apply::execute(&manifest, None, false)?)?
+        }
+        Some(Commands::Unregister { name, force }) => {
+            let orphaned_workspaces = commands::unregister::execute(&name, force)?;
+            presenter.render(&Unregistered {
+                name,
+                orphaned_workspaces,
+            })?
+        }
+        Some(Commands::Workspaces) => {
+            presenter.render(commands::workspaces::execute()?.as_slice())?
+        }
+        Some(Commands::Workspace {
+            command: WorkspaceCommands::Show { name },
+        }) => presenter.render(&commands::workspace::show(&name)?)?,
+        Some(Commands::Status {
+            workspace,
+            groups,
+            json,
+        }) => {
+            let presenter = if json {
+                Presenter::new(OutputFormat::Json)
+            } else {
+                *presenter
+            };
+            presenter.render(&commands::status::execute(workspace.as_deref(), groups)?)?
+        }
+        Some(Commands::Sync {
+            workspace,
+            groups,
+            rebase,
+        }) => {
+            let bars = presenter.transfer_bars();
+            let result = commands::sync::execute(
+                workspace.as_deref(),
+                groups,
+                rebase,
+                |index, name, progress| bars.update(index, name, progress),
+            )?;
+            drop(bars);
+            presenter.render(&result)?
+        }
+        Some(Commands::Add {
+            workspace,
+            repo,
+            base,
+        }) => presenter.render(&Added(commands::add::execute(&workspace, &repo, base)?))?,
+        Some(Commands::Remove {
+            workspace,
+            repo,
+            force,
+        }) => presenter.render(&Removed(commands::remove::execute(
+            &workspace, &repo, force,
+        )?))?,
+        Some(Commands::Exec {
+            workspace,
+            repos,
+            groups,
+            jobs,
+            fail_fast,
+            command,
+        }) => {
+            // as JSON the output is collected per worktree instead of being streamed
+            let output: Mutex<Vec<Vec<ExecOutputLine>>> = Mutex::default();
+            let reports = commands::exec::execute(
+                &workspace,
+                command,
+                repos,
+                groups,
+                jobs,
+                fail_fast,
+                |index, repo_name, stream, line| match presenter.format() {
+                    OutputFormat::Text => print_exec_line(index, repo_name, stream, line),
+                    OutputFormat::Json => {
+                        let mut output = output.lock().unwrap();
+                        if output.len() <= index {
+                            output.resize_with(index + 1, Vec::new);
+                        }
+                        output[index].push(ExecOutputLine {
+                            stream,
+                            line: line.to_string(),
+                        });
+                    }
+                },
+            )?;
+            let mut output = output.into_inner().unwrap().into_iter();
+            let run = ExecRun {
+                worktrees: reports
+                    .into_iter()
+                    .map(|report| ExecWorktree {
+                        report,
+                        output: output.next().unwrap_or_default(),
+                    })
+                    .collect(),
+            };
+            presenter.render(&run)?;
+            if !run.is_success() {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Clean {
+            workspace_name,
+            all,
+            force,
+        }) => {
+            let target = match workspace_name {
+                Some(workspace_name) if !all => CleanTarget::Workspace(workspace_name),
+                _ => CleanTarget::All,
+            };
+            presenter.render(&commands::clean::execute(target, force)?)?
         }
     }
     Ok(())
 }
+
+/// Opens a workspace created with `--open`; as JSON only the created workspace is printed
+fn open_after_create(presenter: &Presenter, workspace: &str) -> Result<(), WxError> {
+    match presenter.format() {
+        OutputFormat::Text => open_workspace(presenter, Some(workspace), None),
+        OutputFormat::Json => commands::open::execute(Some(workspace), None).map(|_| ()),
+    }
+}
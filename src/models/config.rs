@@ -1,13 +1,35 @@
+use std::path::{Path, PathBuf};
+
 use serde::{Deserialize, Serialize};
 
 use super::error::WxError;
+use super::group::RepositoryGroup;
 use super::repository::Repository;
+use super::workspace::{WorkspaceEntry, WorkspaceFormat, WorktreeLayout};
 
 /// Configuration file structure for wx
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct Config {
     /// List of registered repositories
     pub repositories: Vec<Repository>,
+    /// List of workspaces created by wx
+    #[serde(default)]
+    pub workspaces: Vec<WorkspaceEntry>,
+    /// Default layout for worktree directories created by `wx new`
+    #[serde(default)]
+    pub layout: WorktreeLayout,
+    /// Editor formats written by `wx new` (VS Code when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<WorkspaceFormat>,
+    /// Editor command template used by `wx open` (e.g. "code {workspace_file}" or "cursor {dir}")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// Private keys tried after ssh-agent for SSH remotes (`~/.ssh/id_ed25519`, `id_ecdsa` and `id_rsa` when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssh_keys: Vec<PathBuf>,
+    /// Named sets of repositories created by `wx group create`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<RepositoryGroup>,
 }
 
 impl Config {
@@ -15,6 +37,12 @@ impl Config {
     pub fn new() -> Self {
         Self {
             repositories: Vec::new(),
+            workspaces: Vec::new(),
+            layout: WorktreeLayout::default(),
+            formats: Vec::new(),
+            editor: None,
+            ssh_keys: Vec::new(),
+            groups: Vec::new(),
         }
     }
 
@@ -29,7 +57,7 @@ impl Config {
         Ok(())
     }
 
-    /// Remove a repository from the configuration by name
+    /// Remove a repository from the configuration by name, and from the groups containing it
     ///
     /// Returns the removed repository, or an error if not found
     pub fn remove_repository(&mut self, name: &str) -> Result<Repository, WxError> {
@@ -38,6 +66,9 @@ impl Config {
             .iter()
             .position(|r| r.name == name)
             .ok_or_else(|| WxError::RepositoryNotFound(name.to_string()))?;
+        for group in &mut self.groups {
+            group.repositories.retain(|repo_name| repo_name != name);
+        }
         Ok(self.repositories.remove(index))
     }
 
@@ -46,6 +77,20 @@ impl Config {
         self.repositories.iter().find(|r| r.name == name)
     }
 
+    /// Find a repository by name for modification
+    pub fn find_repository_mut(&mut self, name: &str) -> Option<&mut Repository> {
+        self.repositories.iter_mut().find(|r| r.name == name)
+    }
+
+    /// Path of the bare repository of a registered repository, taken from its `local_path`
+    ///
+    /// Returns an error if the repository is not registered
+    pub fn bare_repo_path(&self, name: &str) -> Result<PathBuf, WxError> {
+        self.find_repository(name)
+            .map(Repository::bare_repo_path)
+            .ok_or_else(|| WxError::RepositoryNotFound(name.to_string()))
+    }
+
     /// Check if a repository with the given name exists
     pub fn has_repository(&self, name: &str) -> bool {
         self.repositories.iter().any(|r| r.name == name)
@@ -55,6 +100,97 @@ impl Config {
     pub fn has_remote(&self, remote: &str) -> bool {
         self.repositories.iter().any(|r| r.remote == remote)
     }
+
+    /// Add a group of registered repositories
+    ///
+    /// Returns an error if a group with the same name exists or a repository is not registered
+    pub fn add_group(&mut self, group: RepositoryGroup) -> Result<(), WxError> {
+        if self.find_group(&group.name).is_some() {
+            return Err(WxError::GroupAlreadyExists(group.name));
+        }
+        if let Some(unknown) = group
+            .repositories
+            .iter()
+            .find(|repo_name| !self.has_repository(repo_name))
+        {
+            return Err(WxError::RepositoryNotFound(unknown.clone()));
+        }
+        self.groups.push(group);
+        Ok(())
+    }
+
+    /// Remove a group by name, leaving its repositories registered
+    ///
+    /// Returns the removed group, or an error if not found
+    pub fn remove_group(&mut self, name: &str) -> Result<RepositoryGroup, WxError> {
+        let index = self
+            .groups
+            .iter()
+            .position(|g| g.name == name)
+            .ok_or_else(|| WxError::GroupNotFound(name.to_string()))?;
+        Ok(self.groups.remove(index))
+    }
+
+    /// Find a group by name
+    pub fn find_group(&self, name: &str) -> Option<&RepositoryGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// Names of the repositories in any of the given groups, without duplicates
+    ///
+    /// Returns an error if a group is not found
+    pub fn group_repositories(&self, names: &[String]) -> Result<Vec<String>, WxError> {
+        let mut repositories: Vec<String> = Vec::new();
+        for name in names {
+            let group = self
+                .find_group(name)
+                .ok_or_else(|| WxError::GroupNotFound(name.clone()))?;
+            for repo_name in &group.repositories {
+                if !repositories.contains(repo_name) {
+                    repositories.push(repo_name.clone());
+                }
+            }
+        }
+        Ok(repositories)
+    }
+
+    /// Add a workspace to the registry
+    ///
+    /// Returns an error if a workspace with the same name is already registered
+    pub fn add_workspace(&mut self, workspace: WorkspaceEntry) -> Result<(), WxError> {
+        if self.has_workspace(&workspace.name) {
+            return Err(WxError::WorkspaceAlreadyRegistered(workspace.name));
+        }
+        self.workspaces.push(workspace);
+        Ok(())
+    }
+
+    /// Remove a workspace from the registry by name
+    ///
+    /// Returns the removed workspace, or an error if not found
+    pub fn remove_workspace(&mut self, name: &str) -> Result<WorkspaceEntry, WxError> {
+        let index = self
+            .workspaces
+            .iter()
+            .position(|w| w.name == name)
+            .ok_or_else(|| WxError::WorkspaceNotFound(name.to_string()))?;
+        Ok(self.workspaces.remove(index))
+    }
+
+    /// Find a workspace by name
+    pub fn find_workspace(&self, name: &str) -> Option<&WorkspaceEntry> {
+        self.workspaces.iter().find(|w| w.name == name)
+    }
+
+    /// Find a workspace by its directory
+    pub fn find_workspace_by_path(&self, path: &Path) -> Option<&WorkspaceEntry> {
+        self.workspaces.iter().find(|w| w.path == path)
+    }
+
+    /// Check if a workspace with the given name exists
+    pub fn has_workspace(&self, name: &str) -> bool {
+        self.workspaces.iter().any(|w| w.name == name)
+    }
 }
 
 #[cfg(test)]
@@ -149,6 +285,107 @@ mod tests {
         assert!(!config.has_remote("git@github.com:org/backend.git"));
     }
 
+    #[test]
+    fn test_config_add_workspace() {
+        let mut config = Config::new();
+        assert!(config
+            .add_workspace(create_test_workspace_entry("feature-auth"))
+            .is_ok());
+
+        let result = config.add_workspace(create_test_workspace_entry("feature-auth"));
+        assert!(matches!(
+            result.unwrap_err(),
+            WxError::WorkspaceAlreadyRegistered(_)
+        ));
+        assert_eq!(config.workspaces.len(), 1);
+    }
+
+    #[test]
+    fn test_config_find_and_remove_workspace() {
+        let mut config = Config::new();
+        config
+            .add_workspace(create_test_workspace_entry("feature-auth"))
+            .unwrap();
+
+        assert!(config.has_workspace("feature-auth"));
+        assert!(config
+            .find_workspace_by_path(Path::new("/home/user/work/feature-auth"))
+            .is_some());
+
+        assert!(config.remove_workspace("feature-auth").is_ok());
+        assert!(config.find_workspace("feature-auth").is_none());
+        assert!(matches!(
+            config.remove_workspace("feature-auth").unwrap_err(),
+            WxError::WorkspaceNotFound(_)
+        ));
+    }
+
+    #[test]
+    fn test_config_groups() {
+        let mut config = Config::new();
+        config
+            .add_repository(create_test_repository("frontend"))
+            .unwrap();
+        config
+            .add_repository(create_test_repository("backend"))
+            .unwrap();
+        let web = |repos: &[&str]| {
+            RepositoryGroup::new(
+                "web".to_string(),
+                repos.iter().map(|repo| repo.to_string()).collect(),
+            )
+        };
+
+        assert!(matches!(
+            config.add_group(web(&["frontend", "mobile"])),
+            Err(WxError::RepositoryNotFound(name)) if name == "mobile"
+        ));
+        config.add_group(web(&["frontend", "backend"])).unwrap();
+        config
+            .add_group(RepositoryGroup::new(
+                "api".to_string(),
+                vec!["backend".to_string()],
+            ))
+            .unwrap();
+        assert!(matches!(
+            config.add_group(web(&["frontend"])),
+            Err(WxError::GroupAlreadyExists(_))
+        ));
+
+        assert_eq!(
+            config
+                .group_repositories(&["api".to_string(), "web".to_string()])
+                .unwrap(),
+            vec!["backend", "frontend"]
+        );
+        assert!(matches!(
+            config.group_repositories(&["mobile".to_string()]),
+            Err(WxError::GroupNotFound(_))
+        ));
+
+        // unregistering a repository takes it out of its groups
+        config.remove_repository("backend").unwrap();
+        assert_eq!(
+            config.find_group("web").unwrap().repositories,
+            vec!["frontend"]
+        );
+
+        assert!(config.remove_group("web").is_ok());
+        assert!(config.find_group("web").is_none());
+        assert!(config.has_repository("frontend"));
+        assert!(matches!(
+            config.remove_group("web"),
+            Err(WxError::GroupNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_deserialize_without_workspaces() {
+        let json = r#"{"repositories":[]}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert!(config.workspaces.is_empty());
+    }
+
     #[test]
     fn test_config_serialization() {
         let mut config = Config::new();
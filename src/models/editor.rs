@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::{error::WxError, workspace::WorkspaceFormat};
+
+/// en: A workspace to open in an editor
+///
+/// ja: エディタで開くworkspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpenTarget {
+    pub name: String,
+    pub dir: PathBuf,
+    /// Project file of the workspace's first format, or its directory for formats without one
+    pub workspace_file: PathBuf,
+    pub format: WorkspaceFormat,
+}
+
+/// en: An editor command built from a template such as `code {workspace_file}`
+///
+/// ja: `code {workspace_file}` のようなテンプレートから組み立てたエディタのコマンド
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl EditorCommand {
+    /// en: Build the command from a template, splitting it on whitespace and replacing
+    /// `{workspace_file}`, `{dir}` and `{name}` in each argument
+    /// A template without placeholders, such as `cursor`, gets the workspace file appended
+    ///
+    /// ja: テンプレートを空白で分割し、各引数の `{workspace_file}`・`{dir}`・`{name}` を置換してコマンドを組み立てる
+    /// `cursor` のようにプレースホルダの無いテンプレートには、workspaceファイルを末尾に追加する
+    pub fn from_template(template: &str, target: &OpenTarget) -> Result<Self, WxError> {
+        if template.trim().is_empty() {
+            return Err(WxError::General("Editor command is empty".to_string()));
+        }
+        let workspace_file = target.workspace_file.to_string_lossy();
+        let dir = target.dir.to_string_lossy();
+
+        let mut words: Vec<String> = template
+            .split_whitespace()
+            .map(|word| {
+                word.replace("{workspace_file}", &workspace_file)
+                    .replace("{dir}", &dir)
+                    .replace("{name}", &target.name)
+            })
+            .collect();
+        if !["{workspace_file}", "{dir}", "{name}"]
+            .iter()
+            .any(|placeholder| template.contains(placeholder))
+        {
+            words.push(workspace_file.to_string());
+        }
+
+        let args = words.split_off(1);
+        Ok(Self {
+            program: words.remove(0),
+            args,
+        })
+    }
+}
+
+impl WorkspaceFormat {
+    /// en: Editor command template used when none is configured
+    ///
+    /// ja: 設定が無い場合に使うエディタのコマンドテンプレート
+    pub fn default_editor(&self) -> &'static str {
+        match self {
+            Self::VsCode => "code {workspace_file}",
+            Self::Zed => "zed {dir}",
+            Self::Sublime => "subl --project {workspace_file}",
+            Self::JetBrains => "idea {dir}",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> OpenTarget {
+        OpenTarget {
+            name: "feat".to_string(),
+            dir: PathBuf::from("/work/my feat"),
+            workspace_file: PathBuf::from("/work/my feat/feat.code-workspace"),
+            format: WorkspaceFormat::VsCode,
+        }
+    }
+
+    #[test]
+    fn test_from_template() {
+        assert_eq!(
+            EditorCommand::from_template("code {workspace_file}", &target()).unwrap(),
+            EditorCommand {
+                program: "code".to_string(),
+                args: vec!["/work/my feat/feat.code-workspace".to_string()],
+            }
+        );
+        assert_eq!(
+            EditorCommand::from_template("cursor --new-window {dir}", &target()).unwrap(),
+            EditorCommand {
+                program: "cursor".to_string(),
+                args: vec!["--new-window".to_string(), "/work/my feat".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_template_without_placeholders() {
+        assert_eq!(
+            EditorCommand::from_template("kiro", &target())
+                .unwrap()
+                .args,
+            vec!["/work/my feat/feat.code-workspace".to_string()]
+        );
+        assert!(EditorCommand::from_template("  ", &target()).is_err());
+    }
+}
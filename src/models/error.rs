@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
 use thiserror::Error;
 
 /// Unified error type for wx operations
@@ -13,13 +16,38 @@ pub enum WxError {
     #[error("Repository '{0}' not found")]
     RepositoryNotFound(String),
 
+    #[error("Repository name '{0}' is already used by {1}. Pass --name <alias> to register it under another name")]
+    RepositoryNameTaken(String, String),
+
+    #[error("Invalid repository name '{0}'. Names cannot be empty or contain '/', '\\', ':' or whitespace")]
+    InvalidRepositoryName(String),
+
+    #[error("Repository '{0}' still has worktrees in use. Use --force to unregister it anyway")]
+    RepositoryInUse(String, Vec<PathBuf>),
+
+    #[error("Authentication failed for '{0}' (tried: {1}). Add your key to ssh-agent, list it in \"ssh_keys\" in ~/.wx/config.json, or configure a git credential helper")]
+    AuthenticationFailed(String, String),
+
+    // Group errors
+    #[error("Group '{0}' not found")]
+    GroupNotFound(String),
+
+    #[error("Group '{0}' already exists")]
+    GroupAlreadyExists(String),
+
     // Worktree errors
     #[error("Invalid path: '{0}'")]
     InvalidPath(String),
 
+    #[error("Invalid directory name '{0}'. It must be a single directory name, without '/', '\\' or '..'")]
+    InvalidDirectoryName(String),
+
     #[error("Worktree already exists at '{0}'")]
     WorktreeAlreadyExists(String),
 
+    #[error("Multiple repositories map to the worktree directory '{0}'. Use a layout that includes {{repo}}")]
+    DuplicateWorktreeDirectory(String),
+
     #[error("Branch '{0}' not found in repository '{1}'")]
     BranchNotFound(String, String),
 
@@ -27,6 +55,30 @@ pub enum WxError {
     #[error("Workspace file already exists: '{0}'")]
     WorkspaceFileAlreadyExists(String),
 
+    #[error("Workspace '{0}' not found")]
+    WorkspaceNotFound(String),
+
+    #[error("Repository '{0}' is already in workspace '{1}'")]
+    AlreadyInWorkspace(String, String),
+
+    #[error("Repository '{0}' is not in workspace '{1}'")]
+    NotInWorkspace(String, String),
+
+    #[error("Worktree '{0}' has uncommitted changes. Use --force to remove it anyway")]
+    UncommittedChanges(String),
+
+    #[error("Template '{0}' not found in ~/.wx/templates")]
+    TemplateNotFound(String),
+
+    #[error("Invalid template name '{0}'. Names cannot contain '/', '\\' or '..'")]
+    InvalidTemplateName(String),
+
+    #[error("Editor '{0}' not found. Set \"editor\" in ~/.wx/config.json or pass --editor")]
+    EditorNotFound(String),
+
+    #[error("Workspace '{0}' is already registered")]
+    WorkspaceAlreadyRegistered(String),
+
     #[error("Git operation failed: {0}")]
     GitError(#[from] git2::Error),
 
@@ -39,6 +91,9 @@ pub enum WxError {
     #[error("JSON parsing failed: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("Manifest parsing failed: {0}")]
+    ManifestError(#[from] toml::de::Error),
+
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
@@ -79,6 +134,66 @@ impl WxError {
     pub fn restore(msg: impl Into<String>) -> Self {
         Self::RestoreError(msg.into())
     }
+
+    /// en: Stable, machine-readable code of the error, used by `--output json`
+    ///
+    /// ja: `--output json` で使用する、変わることのない機械可読なエラーコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidUrl(_) => "invalid_url",
+            Self::AlreadyRegistered(_) => "already_registered",
+            Self::RepositoryNotFound(_) => "repository_not_found",
+            Self::RepositoryNameTaken(..) => "repository_name_taken",
+            Self::InvalidRepositoryName(_) => "invalid_repository_name",
+            Self::RepositoryInUse(..) => "repository_in_use",
+            Self::AuthenticationFailed(..) => "authentication_failed",
+            Self::GroupNotFound(_) => "group_not_found",
+            Self::GroupAlreadyExists(_) => "group_already_exists",
+            Self::InvalidPath(_) => "invalid_path",
+            Self::InvalidDirectoryName(_) => "invalid_directory_name",
+            Self::WorktreeAlreadyExists(_) => "worktree_already_exists",
+            Self::DuplicateWorktreeDirectory(_) => "duplicate_worktree_directory",
+            Self::BranchNotFound(..) => "branch_not_found",
+            Self::WorkspaceFileAlreadyExists(_) => "workspace_file_already_exists",
+            Self::WorkspaceNotFound(_) => "workspace_not_found",
+            Self::AlreadyInWorkspace(..) => "already_in_workspace",
+            Self::NotInWorkspace(..) => "not_in_workspace",
+            Self::UncommittedChanges(_) => "uncommitted_changes",
+            Self::TemplateNotFound(_) => "template_not_found",
+            Self::InvalidTemplateName(_) => "invalid_template_name",
+            Self::EditorNotFound(_) => "editor_not_found",
+            Self::WorkspaceAlreadyRegistered(_) => "workspace_already_registered",
+            Self::GitError(_) => "git_error",
+            Self::IoError(_) => "io_error",
+            Self::DialoguerError(_) => "prompt_error",
+            Self::JsonError(_) => "json_error",
+            Self::ManifestError(_) => "manifest_error",
+            Self::ConfigError(_) => "config_error",
+            Self::HomeDirNotFound => "home_dir_not_found",
+            Self::BackupError(_) => "backup_error",
+            Self::RestoreError(_) => "restore_error",
+            Self::RollbackFailed { .. } => "rollback_failed",
+            Self::General(_) => "general",
+        }
+    }
+}
+
+/// en: Code and message of an error that is reported per repository instead of stopping the command
+///
+/// ja: コマンドを中断せず、リポジトリごとに報告するエラーのコードとメッセージ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl From<&WxError> for ErrorReport {
+    fn from(error: &WxError) -> Self {
+        Self {
+            code: error.code(),
+            message: error.to_string(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -106,9 +221,26 @@ mod tests {
         assert!(error.to_string().contains("missing-repo"));
     }
 
+    #[test]
+    fn test_repository_in_use_error_message() {
+        let error = WxError::RepositoryInUse("my-repo".to_string(), vec![]);
+        assert!(error.to_string().contains("my-repo"));
+        assert!(error.to_string().contains("--force"));
+    }
+
     #[test]
     fn test_config_error_helper() {
         let error = WxError::config("custom config error");
         assert!(error.to_string().contains("custom config error"));
     }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(
+            WxError::RepositoryNotFound("x".to_string()).code(),
+            "repository_not_found"
+        );
+        assert_eq!(WxError::HomeDirNotFound.code(), "home_dir_not_found");
+        assert_eq!(WxError::General("x".to_string()).code(), "general");
+    }
 }
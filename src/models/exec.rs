@@ -0,0 +1,98 @@
+use std::{fmt, path::PathBuf, time::Duration};
+
+use serde::{Serialize, Serializer};
+
+/// en: Options of `wx exec`
+///
+/// ja: `wx exec` のオプション
+#[derive(Debug, Clone)]
+pub struct ExecOptions {
+    /// A single argument runs through the shell, several arguments run as a program with arguments
+    pub command: Vec<String>,
+    /// Only run in worktrees of these repositories (all when empty)
+    pub repos: Vec<String>,
+    /// Only run in worktrees of repositories in these groups, in addition to `repos`
+    pub groups: Vec<String>,
+    /// Maximum number of commands running at the same time
+    pub jobs: usize,
+    /// Stop scheduling and kill running commands after the first failure
+    pub fail_fast: bool,
+}
+
+/// en: Which output stream a line was written to
+///
+/// ja: 行が書き込まれた出力ストリーム
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// en: How the command ended in a worktree
+///
+/// ja: worktreeでのコマンドの終了状態
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "detail", rename_all = "snake_case")]
+pub enum ExecStatus {
+    Succeeded,
+    /// Exited with a non-zero code, or `None` when killed by a signal
+    Failed(Option<i32>),
+    /// Could not be started
+    Error(String),
+    /// Killed after another worktree failed with `--fail-fast`
+    Cancelled,
+    /// Not started because another worktree failed with `--fail-fast`
+    Skipped,
+    Missing,
+}
+
+impl ExecStatus {
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Succeeded)
+    }
+}
+
+impl fmt::Display for ExecStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Succeeded => write!(f, "ok"),
+            Self::Failed(Some(code)) => write!(f, "exit {code}"),
+            Self::Failed(None) => write!(f, "killed"),
+            Self::Error(error) => write!(f, "error: {error}"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// en: Result of running the command in one worktree
+///
+/// ja: 1つのworktreeでコマンドを実行した結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExecReport {
+    pub repo_name: String,
+    pub path: PathBuf,
+    pub status: ExecStatus,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_status_display() {
+        assert_eq!(ExecStatus::Succeeded.to_string(), "ok");
+        assert_eq!(ExecStatus::Failed(Some(2)).to_string(), "exit 2");
+        assert_eq!(ExecStatus::Failed(None).to_string(), "killed");
+        assert!(ExecStatus::Succeeded.is_success());
+        assert!(!ExecStatus::Skipped.is_success());
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Named set of registered repositories, selected at once with `--group`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepositoryGroup {
+    /// Group name (e.g., "web")
+    pub name: String,
+    /// Names of the registered repositories in the group, in the order they were given
+    pub repositories: Vec<String>,
+}
+
+impl RepositoryGroup {
+    /// Create a new RepositoryGroup, dropping repositories given more than once
+    pub fn new(name: String, repositories: Vec<String>) -> Self {
+        let mut unique: Vec<String> = Vec::new();
+        for repository in repositories {
+            if !unique.contains(&repository) {
+                unique.push(repository);
+            }
+        }
+        Self {
+            name,
+            repositories: unique,
+        }
+    }
+
+    /// Check if the group contains the repository
+    pub fn contains(&self, repo_name: &str) -> bool {
+        self.repositories.iter().any(|name| name == repo_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_group_new() {
+        let group = RepositoryGroup::new(
+            "web".to_string(),
+            vec![
+                "frontend".to_string(),
+                "backend".to_string(),
+                "frontend".to_string(),
+            ],
+        );
+
+        assert_eq!(group.repositories, vec!["frontend", "backend"]);
+        assert!(group.contains("backend"));
+        assert!(!group.contains("mobile"));
+    }
+}
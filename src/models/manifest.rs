@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::WxError,
+    workspace::{WorkspaceMember, WorkspaceSettings, WorktreeSelection},
+};
+use crate::utils::is_single_component;
+
+/// en: Declarative description of a workspace, read from a `wx.toml` manifest
+///
+/// ja: `wx.toml` マニフェストから読み込む、workspaceの宣言的な定義
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceManifest {
+    /// Workspace name, also used as the directory name
+    pub name: String,
+    /// Repositories checked out in the workspace
+    #[serde(default)]
+    pub repos: Vec<ManifestRepository>,
+    /// Template in `~/.wx/templates` merged into the workspace file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Settings written to the workspace file, overriding those of templates
+    #[serde(default)]
+    pub settings: WorkspaceSettings,
+}
+
+impl WorkspaceManifest {
+    /// en: Check that the workspace name and every `dir` are single directory names, so a
+    /// shared manifest cannot place directories outside the workspace
+    ///
+    /// ja: workspace名と各 `dir` が1つのディレクトリ名か確認する
+    /// 共有されたマニフェストがworkspaceの外にディレクトリを作らないようにする
+    pub fn validate(&self) -> Result<(), WxError> {
+        let dir_names = std::iter::once(&self.name)
+            .chain(self.repos.iter().filter_map(|repo| repo.dir.as_ref()));
+        for dir_name in dir_names {
+            if !is_single_component(dir_name) {
+                return Err(WxError::InvalidDirectoryName(dir_name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// en: A repository entry of a manifest
+///
+/// ja: マニフェストのリポジトリ定義
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestRepository {
+    /// Registered repository name
+    pub name: String,
+    /// Remote URL used to register the repository when it is missing
+    pub url: Option<String>,
+    pub branch: String,
+    /// Ref a new branch is created from
+    pub base: Option<String>,
+    /// Worktree directory name overriding the layout
+    pub dir: Option<String>,
+}
+
+impl ManifestRepository {
+    pub fn to_selection(&self) -> WorktreeSelection {
+        WorktreeSelection {
+            repo_name: self.name.clone(),
+            branch: self.branch.clone(),
+            base: self.base.clone(),
+            dir_name: self.dir.clone(),
+        }
+    }
+}
+
+/// en: Result of applying a manifest
+///
+/// ja: マニフェスト適用の結果
+#[derive(Debug, Default, Serialize)]
+pub struct ApplyResult {
+    pub workspace_dir: PathBuf,
+    /// Whether the workspace was newly created
+    pub created: bool,
+    /// Repositories registered automatically from their manifest URL
+    pub registered_repositories: Vec<String>,
+    /// Worktrees added to the workspace
+    pub added: Vec<WorkspaceMember>,
+    /// Differences between the manifest and the workspace that were left untouched
+    pub drift: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_from_toml() {
+        let manifest: WorkspaceManifest = toml::from_str(
+            r#"
+name = "feature-auth"
+
+[[repos]]
+name = "frontend"
+url = "git@github.com:org/frontend.git"
+branch = "feature/auth"
+base = "origin/develop"
+dir = "web"
+
+[[repos]]
+name = "backend"
+branch = "feature/auth"
+
+[settings]
+"editor.formatOnSave" = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name, "feature-auth");
+        assert_eq!(manifest.repos.len(), 2);
+        assert_eq!(
+            manifest.repos[0].to_selection(),
+            WorktreeSelection {
+                repo_name: "frontend".to_string(),
+                branch: "feature/auth".to_string(),
+                base: Some("origin/develop".to_string()),
+                dir_name: Some("web".to_string()),
+            }
+        );
+        assert_eq!(manifest.repos[1].url, None);
+        assert_eq!(
+            manifest.settings.values["editor.formatOnSave"],
+            serde_json::Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_manifest_validate() {
+        let manifest = |name: &str, dir: &str| -> WorkspaceManifest {
+            toml::from_str(&format!(
+                "name = \"{name}\"\n[[repos]]\nname = \"frontend\"\nbranch = \"main\"\ndir = \"{dir}\"\n"
+            ))
+            .unwrap()
+        };
+
+        assert!(manifest("feature-auth", "web").validate().is_ok());
+        for (name, dir) in [
+            ("feature-auth", "../escaped"),
+            ("feature-auth", "/tmp/web"),
+            ("../feature-auth", "web"),
+            ("work/feature-auth", "web"),
+        ] {
+            assert!(matches!(
+                manifest(name, dir).validate(),
+                Err(WxError::InvalidDirectoryName(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_manifest_requires_name() {
+        assert!(
+            toml::from_str::<WorkspaceManifest>("[[repos]]\nname = \"a\"\nbranch = \"b\"").is_err()
+        );
+    }
+}
@@ -1,8 +1,18 @@
 pub mod config;
+pub mod editor;
 pub mod error;
+pub mod exec;
+pub mod group;
+pub mod manifest;
 pub mod repository;
+pub mod shell;
+pub mod status;
+pub mod sync;
+pub mod template;
+pub mod transfer;
 pub mod workspace;
 
 pub use config::Config;
 pub use error::WxError;
+pub use group::RepositoryGroup;
 pub use repository::Repository;
@@ -1,14 +1,24 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use super::error::WxError;
+use crate::utils::expand_home;
+
 /// Represents a registered Git repository
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Repository {
-    /// Repository name (derived from URL, e.g., "frontend" from "git@github.com:org/frontend.git")
+    /// Repository name (derived from URL, e.g., "frontend" from "git@github.com:org/frontend.git",
+    /// or the alias given with `wx register --name`)
     pub name: String,
     /// Remote URL (SSH or HTTPS format)
     pub remote: String,
-    /// Local path to the bare repository (e.g., "~/.wx/frontend.git")
+    /// Local path to the bare repository (e.g., "~/.wx/repos/github.com/org/frontend.git")
+    /// This is the only place the location is recorded; it is never rebuilt from the name
     pub local_path: String,
+    /// Ref new branches start from when no base is given (e.g., "origin/develop")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_base: Option<String>,
 }
 
 impl Repository {
@@ -18,7 +28,31 @@ impl Repository {
             name,
             remote,
             local_path,
+            default_base: None,
+        }
+    }
+
+    /// en: Path of the bare repository, with a leading `~` expanded
+    ///
+    /// ja: bareリポジトリのパス（先頭の `~` は展開する）
+    pub fn bare_repo_path(&self) -> PathBuf {
+        expand_home(&PathBuf::from(&self.local_path))
+    }
+
+    /// en: Check that a name can be used in `<repo>:<branch>` specs and as a directory name
+    ///
+    /// ja: 名前が `<repo>:<branch>` の指定やディレクトリ名として使用できるか確認する
+    pub fn validate_name(name: &str) -> Result<(), WxError> {
+        let invalid = name.is_empty()
+            || name == "."
+            || name == ".."
+            || name
+                .chars()
+                .any(|c| matches!(c, '/' | '\\' | ':') || c.is_whitespace());
+        if invalid {
+            return Err(WxError::InvalidRepositoryName(name.to_string()));
         }
+        Ok(())
     }
 }
 
@@ -39,6 +73,18 @@ mod tests {
         assert_eq!(repo.local_path, "/home/user/.wx/frontend.git");
     }
 
+    #[test]
+    fn test_repository_validate_name() {
+        assert!(Repository::validate_name("org-b-api").is_ok());
+        assert!(Repository::validate_name("api_v2.old").is_ok());
+        for name in ["", "..", "org/api", "api:main", "my api"] {
+            assert!(matches!(
+                Repository::validate_name(name),
+                Err(WxError::InvalidRepositoryName(_))
+            ));
+        }
+    }
+
     #[test]
     fn test_repository_serialization() {
         let repo = Repository::new(
@@ -66,4 +112,19 @@ mod tests {
         assert!(json.contains("\"remote\": \"git@github.com:org/test-repo.git\""));
         assert!(json.contains("\"local_path\": \"/home/user/.wx/test-repo.git\""));
     }
+
+    #[test]
+    fn test_repository_default_base_is_optional() {
+        let json = r#"{"name":"api","remote":"git@github.com:org/api.git","local_path":"/home/user/.wx/api.git"}"#;
+        let mut repo: Repository = serde_json::from_str(json).unwrap();
+        assert_eq!(repo.default_base, None);
+        assert!(!serde_json::to_string(&repo)
+            .unwrap()
+            .contains("default_base"));
+
+        repo.default_base = Some("origin/develop".to_string());
+        assert!(serde_json::to_string(&repo)
+            .unwrap()
+            .contains("\"default_base\":\"origin/develop\""));
+    }
 }
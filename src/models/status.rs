@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// en: Git state of a single worktree
+///
+/// ja: 1つのworktreeのGitの状態
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WorktreeStatus {
+    /// Checked-out branch, `None` when HEAD is detached or the worktree is missing
+    pub branch: Option<String>,
+    /// Upstream branch, e.g. `origin/feature/auth`
+    pub upstream: Option<String>,
+    /// Commits on the branch that are not on its upstream
+    pub ahead: usize,
+    /// Commits on the upstream that are not on the branch
+    pub behind: usize,
+    /// Changes added to the index
+    pub staged: usize,
+    /// Tracked files modified in the working tree
+    pub dirty: usize,
+    pub untracked: usize,
+    /// Stashes made on the checked-out branch
+    pub stashes: usize,
+    /// Whether the worktree is locked in the bare repository
+    pub locked: bool,
+    /// Whether the worktree directory no longer exists
+    pub missing: bool,
+}
+
+impl WorktreeStatus {
+    /// en: Whether the worktree has no local changes and is in sync with its upstream
+    ///
+    /// ja: worktreeにローカルの変更が無く、上流と同期しているか
+    pub fn is_clean(&self) -> bool {
+        self.ahead == 0
+            && self.behind == 0
+            && self.staged == 0
+            && self.dirty == 0
+            && self.untracked == 0
+            && !self.missing
+    }
+}
+
+/// en: Status of a worktree of a workspace
+///
+/// ja: workspaceのworktreeの状態
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberStatus {
+    pub repo_name: String,
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub status: WorktreeStatus,
+}
+
+/// en: Status of every worktree of a workspace, as shown by `wx status`
+///
+/// ja: `wx status` で表示する、workspaceの全worktreeの状態
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkspaceStatus {
+    pub name: String,
+    pub path: PathBuf,
+    pub worktrees: Vec<MemberStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worktree_status_is_clean() {
+        assert!(WorktreeStatus::default().is_clean());
+        assert!(!WorktreeStatus {
+            behind: 1,
+            ..WorktreeStatus::default()
+        }
+        .is_clean());
+        assert!(!WorktreeStatus {
+            missing: true,
+            ..WorktreeStatus::default()
+        }
+        .is_clean());
+    }
+
+    #[test]
+    fn test_member_status_json() {
+        let member = MemberStatus {
+            repo_name: "frontend".to_string(),
+            path: PathBuf::from("/work/auth/frontend"),
+            status: WorktreeStatus {
+                branch: Some("feature/auth".to_string()),
+                ahead: 2,
+                ..WorktreeStatus::default()
+            },
+        };
+
+        let json = serde_json::to_value(&member).unwrap();
+
+        assert_eq!(json["repo_name"], "frontend");
+        assert_eq!(json["branch"], "feature/auth");
+        assert_eq!(json["ahead"], 2);
+        assert_eq!(json["upstream"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,114 @@
+use std::{fmt, path::PathBuf};
+
+use serde::Serialize;
+
+/// en: What `wx sync` did with a single worktree
+///
+/// ja: `wx sync` が1つのworktreeに対して行った処理
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum SyncOutcome {
+    /// Already contains its upstream; `ahead` local commits are not pushed yet
+    UpToDate {
+        ahead: usize,
+    },
+    FastForwarded {
+        commits: usize,
+    },
+    /// Local commits were replayed on top of the upstream
+    Rebased {
+        commits: usize,
+    },
+    /// Skipped because of uncommitted changes
+    SkippedDirty,
+    /// Skipped because local and upstream commits diverged and `--rebase` was not given
+    SkippedDiverged {
+        ahead: usize,
+        behind: usize,
+    },
+    /// Rebase stopped on a conflict and was aborted
+    SkippedConflict,
+    NoUpstream,
+    Detached,
+    Missing,
+    Failed {
+        error: String,
+    },
+}
+
+impl SyncOutcome {
+    /// en: Whether the worktree was left untouched although it is behind its upstream
+    ///
+    /// ja: 上流より遅れているにもかかわらず、worktreeを更新しなかったか
+    pub fn is_skipped(&self) -> bool {
+        matches!(
+            self,
+            Self::SkippedDirty
+                | Self::SkippedDiverged { .. }
+                | Self::SkippedConflict
+                | Self::Missing
+                | Self::Failed { .. }
+        )
+    }
+}
+
+impl fmt::Display for SyncOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UpToDate { ahead: 0 } => write!(f, "up to date"),
+            Self::UpToDate { ahead } => write!(f, "up to date ({ahead} local commits not pushed)"),
+            Self::FastForwarded { commits } => write!(f, "fast-forwarded {commits} commits"),
+            Self::Rebased { commits } => write!(f, "rebased {commits} local commits"),
+            Self::SkippedDirty => write!(f, "skipped: uncommitted changes"),
+            Self::SkippedDiverged { ahead, behind } => write!(
+                f,
+                "skipped: diverged ({ahead} ahead, {behind} behind), use --rebase"
+            ),
+            Self::SkippedConflict => write!(f, "skipped: rebase conflict, rebase aborted"),
+            Self::NoUpstream => write!(f, "no upstream"),
+            Self::Detached => write!(f, "detached HEAD"),
+            Self::Missing => write!(f, "skipped: worktree is missing"),
+            Self::Failed { error } => write!(f, "failed: {error}"),
+        }
+    }
+}
+
+/// en: Result of syncing one worktree
+///
+/// ja: 1つのworktreeの同期結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncReport {
+    pub repo_name: String,
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub outcome: SyncOutcome,
+}
+
+/// en: Result of `wx sync` for a workspace
+///
+/// ja: workspaceに対する `wx sync` の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncResult {
+    pub workspace_dir: PathBuf,
+    pub reports: Vec<SyncReport>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_outcome_display() {
+        assert_eq!(SyncOutcome::UpToDate { ahead: 0 }.to_string(), "up to date");
+        assert_eq!(
+            SyncOutcome::SkippedDiverged {
+                ahead: 1,
+                behind: 2
+            }
+            .to_string(),
+            "skipped: diverged (1 ahead, 2 behind), use --rebase"
+        );
+        assert!(SyncOutcome::SkippedDirty.is_skipped());
+        assert!(!SyncOutcome::FastForwarded { commits: 1 }.is_skipped());
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::workspace::{WorkspaceFile, WorkspaceFolder, WorkspaceSettings};
+
+/// en: Blocks of a workspace file kept in `~/.wx/templates` and merged into generated workspace files
+///
+/// ja: `~/.wx/templates` に置かれ、生成するworkspaceファイルにマージされるブロック
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceTemplate {
+    #[serde(default)]
+    pub settings: WorkspaceSettings,
+    /// `{"recommendations": [...]}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<Value>,
+}
+
+impl WorkspaceTemplate {
+    /// en: Layer `other` over this template
+    /// Settings override (nested objects are merged), while extension recommendations,
+    /// launch configurations and tasks are appended
+    ///
+    /// ja: このテンプレートの上に `other` を重ねる
+    /// settingsは上書き（ネストしたオブジェクトはマージ）し、推奨拡張機能・launch構成・tasksは追加する
+    pub fn merge(&mut self, other: WorkspaceTemplate) {
+        for (key, value) in other.settings.values {
+            match self.settings.values.get_mut(&key) {
+                Some(current) => merge_value(current, value, false),
+                None => {
+                    self.settings.values.insert(key, value);
+                }
+            }
+        }
+
+        for (current, value) in [
+            (&mut self.extensions, other.extensions),
+            (&mut self.launch, other.launch),
+            (&mut self.tasks, other.tasks),
+        ] {
+            match (current.as_mut(), value) {
+                (Some(current), Some(value)) => merge_value(current, value, true),
+                (None, value) => *current = value,
+                (Some(_), None) => {}
+            }
+        }
+    }
+
+    /// en: Build a workspace file with the folders and this template's blocks
+    ///
+    /// ja: フォルダとこのテンプレートのブロックからworkspaceファイルを組み立てる
+    pub fn into_workspace_file(self, folders: Vec<WorkspaceFolder>) -> WorkspaceFile {
+        let mut extra = Map::new();
+        for (key, value) in [
+            ("extensions", self.extensions),
+            ("launch", self.launch),
+            ("tasks", self.tasks),
+        ] {
+            if let Some(value) = value {
+                extra.insert(key.to_string(), value);
+            }
+        }
+
+        WorkspaceFile {
+            folders,
+            settings: self.settings,
+            extra,
+        }
+    }
+}
+
+/// Merge `value` into `current`: objects key by key, arrays appended without duplicates when
+/// `append_arrays` is set, anything else replaced
+fn merge_value(current: &mut Value, value: Value, append_arrays: bool) {
+    match (current, value) {
+        (Value::Object(current), Value::Object(value)) => {
+            for (key, value) in value {
+                match current.get_mut(&key) {
+                    Some(current) => merge_value(current, value, append_arrays),
+                    None => {
+                        current.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(current), Value::Array(value)) if append_arrays => {
+            for item in value {
+                if !current.contains(&item) {
+                    current.push(item);
+                }
+            }
+        }
+        (current, value) => *current = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn template(value: Value) -> WorkspaceTemplate {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut merged = template(json!({
+            "settings": {"editor.tabSize": 2, "files.exclude": {"**/target": true}, "editor.rulers": [80]},
+            "extensions": {"recommendations": ["editorconfig.editorconfig"]},
+        }));
+        merged.merge(template(json!({
+            "settings": {"editor.tabSize": 4, "files.exclude": {"**/dist": true}, "editor.rulers": [100]},
+            "extensions": {"recommendations": ["editorconfig.editorconfig", "rust-lang.rust-analyzer"]},
+            "launch": {"version": "0.2.0", "configurations": [{"name": "api"}]},
+        })));
+        merged.merge(template(json!({
+            "launch": {"configurations": [{"name": "web"}]},
+        })));
+
+        assert_eq!(
+            Value::Object(merged.settings.values),
+            json!({
+                "editor.tabSize": 4,
+                "files.exclude": {"**/target": true, "**/dist": true},
+                "editor.rulers": [100],
+            })
+        );
+        assert_eq!(
+            merged.extensions.unwrap(),
+            json!({"recommendations": ["editorconfig.editorconfig", "rust-lang.rust-analyzer"]})
+        );
+        assert_eq!(
+            merged.launch.unwrap(),
+            json!({"version": "0.2.0", "configurations": [{"name": "api"}, {"name": "web"}]})
+        );
+        assert_eq!(merged.tasks, None);
+    }
+
+    #[test]
+    fn test_into_workspace_file() {
+        let workspace_file = template(json!({
+            "settings": {"editor.tabSize": 2},
+            "tasks": {"version": "2.0.0", "tasks": []},
+        }))
+        .into_workspace_file(vec![WorkspaceFolder::new("frontend".to_string())]);
+
+        assert_eq!(
+            serde_json::to_value(&workspace_file).unwrap(),
+            json!({
+                "folders": [{"path": "frontend"}],
+                "settings": {"editor.tabSize": 2},
+                "tasks": {"version": "2.0.0", "tasks": []},
+            })
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use super::{error::ErrorReport, Repository};
+
+/// en: Progress of a clone or fetch, as reported by git while objects are transferred
+///
+/// ja: clone・フェッチの進捗（オブジェクト転送中にgitが報告する値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferProgress {
+    pub received_objects: usize,
+    pub total_objects: usize,
+    pub indexed_objects: usize,
+    pub received_bytes: usize,
+    pub indexed_deltas: usize,
+    pub total_deltas: usize,
+}
+
+impl TransferProgress {
+    /// en: Whether every object has been received and the deltas are being resolved
+    ///
+    /// ja: 全オブジェクトを受信し、差分を解決している最中か
+    pub fn is_resolving_deltas(&self) -> bool {
+        self.total_deltas > 0 && self.received_objects == self.total_objects
+    }
+}
+
+/// en: Receives the progress of a clone or fetch; implemented by every `Fn(&TransferProgress)`
+///
+/// ja: clone・フェッチの進捗を受け取る（全ての `Fn(&TransferProgress)` が実装する）
+pub trait TransferObserver {
+    fn on_progress(&self, progress: &TransferProgress);
+}
+
+impl<F: Fn(&TransferProgress)> TransferObserver for F {
+    fn on_progress(&self, progress: &TransferProgress) {
+        self(progress)
+    }
+}
+
+/// en: Result of fetching one repository
+///
+/// ja: 1つのリポジトリをフェッチした結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FetchReport {
+    pub repo_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorReport>,
+}
+
+/// en: Result of registering one repository URL
+///
+/// ja: 1つのリポジトリURLを登録した結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RegisterReport {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<Repository>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorReport>,
+}
+
+impl RegisterReport {
+    /// en: Whether the URL could not be registered; an already registered repository is not a failure
+    ///
+    /// ja: URLを登録できなかったか（登録済みのリポジトリは失敗として扱わない）
+    pub fn is_failure(&self) -> bool {
+        self.error
+            .as_ref()
+            .is_some_and(|error| error.code != "already_registered")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_resolving_deltas() {
+        let mut progress = TransferProgress {
+            received_objects: 10,
+            total_objects: 20,
+            total_deltas: 5,
+            ..Default::default()
+        };
+        assert!(!progress.is_resolving_deltas());
+
+        progress.received_objects = 20;
+        assert!(progress.is_resolving_deltas());
+    }
+}
@@ -1,11 +1,230 @@
-use std::path::PathBuf;
+use std::{fmt, path::PathBuf, str::FromStr};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use super::error::WxError;
+use crate::utils::sanitize_branch_name;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct WorktreeSelection {
     pub repo_name: String,
     pub branch: String,
+    /// Ref a new branch is created from (e.g., "origin/develop"); HEAD when omitted
+    pub base: Option<String>,
+    /// Worktree directory name overriding the layout
+    pub dir_name: Option<String>,
+}
+
+impl WorktreeSelection {
+    /// en: Parse a `<repo>[:<branch>]` spec, using `default_branch` when the branch is omitted
+    ///
+    /// ja: `<repo>[:<branch>]` 形式を解析する（ブランチ省略時は `default_branch` を使用）
+    pub fn parse(spec: &str, default_branch: Option<&str>) -> Result<Self, WxError> {
+        let (repo_name, branch) = match spec.split_once(':') {
+            Some((repo_name, branch)) => (repo_name.trim(), Some(branch.trim())),
+            None => (spec.trim(), default_branch),
+        };
+
+        match branch {
+            Some(branch) if !repo_name.is_empty() && !branch.is_empty() => Ok(Self {
+                repo_name: repo_name.to_string(),
+                branch: branch.to_string(),
+                ..Self::default()
+            }),
+            _ => Err(WxError::General(format!(
+                "Invalid repository '{spec}'. Expected <repo>:<branch>, or pass --branch"
+            ))),
+        }
+    }
+}
+
+/// en: Strategy for naming worktree directories inside a workspace
+/// `repo` and `repo-branch` are presets; any other value is a template using `{repo}` and `{branch}`
+///
+/// ja: workspace内のworktreeディレクトリ名の決め方
+/// `repo` と `repo-branch` はプリセットで、それ以外は `{repo}` と `{branch}` を使うテンプレート
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum WorktreeLayout {
+    #[default]
+    Repo,
+    RepoBranch,
+    Template(String),
+}
+
+impl WorktreeLayout {
+    /// en: Returns the worktree directory name for the repository and branch
+    ///
+    /// ja: リポジトリとブランチに対応するworktreeディレクトリ名を返却する
+    pub fn dir_name(&self, repo_name: &str, branch: &str) -> String {
+        let template = match self {
+            Self::Repo => "{repo}",
+            Self::RepoBranch => "{repo}-{branch}",
+            Self::Template(template) => template,
+        };
+
+        sanitize_branch_name(
+            &template
+                .replace("{repo}", repo_name)
+                .replace("{branch}", branch),
+        )
+    }
+}
+
+impl TryFrom<String> for WorktreeLayout {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<WorktreeLayout> for String {
+    fn from(value: WorktreeLayout) -> Self {
+        value.to_string()
+    }
+}
+
+impl FromStr for WorktreeLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "repo" => Ok(Self::Repo),
+            "repo-branch" => Ok(Self::RepoBranch),
+            template if !template.contains("{repo}") && !template.contains("{branch}") => Err(
+                format!("Layout template '{template}' must contain {{repo}} or {{branch}}"),
+            ),
+            template => Ok(Self::Template(template.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for WorktreeLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Repo => write!(f, "repo"),
+            Self::RepoBranch => write!(f, "repo-branch"),
+            Self::Template(template) => write!(f, "{template}"),
+        }
+    }
+}
+
+/// en: Editor project format written for a workspace
+/// `vscode` workspace files are also opened by Cursor and Kiro
+///
+/// ja: workspaceに書き出すエディタのプロジェクト形式
+/// `vscode` のworkspaceファイルはCursorとKiroでも開ける
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum WorkspaceFormat {
+    /// `<name>.code-workspace`
+    #[default]
+    VsCode,
+    /// `.zed/settings.json`
+    Zed,
+    /// `<name>.sublime-project`
+    Sublime,
+    /// `.idea/modules.xml`, `.idea/vcs.xml` and a module per worktree
+    JetBrains,
+}
+
+impl WorkspaceFormat {
+    pub const ALL: [Self; 4] = [Self::VsCode, Self::Zed, Self::Sublime, Self::JetBrains];
+
+    /// en: The configured formats without duplicates, or VS Code when none are configured
+    ///
+    /// ja: 重複を除いた設定済みの形式（未設定の場合はVS Code）
+    pub fn or_default(formats: &[Self]) -> Vec<Self> {
+        let mut unique: Vec<Self> = Vec::new();
+        for format in formats {
+            if !unique.contains(format) {
+                unique.push(*format);
+            }
+        }
+        if unique.is_empty() {
+            unique.push(Self::default());
+        }
+        unique
+    }
+}
+
+impl FromStr for WorkspaceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "vscode" | "code" | "cursor" | "kiro" => Ok(Self::VsCode),
+            "zed" => Ok(Self::Zed),
+            "sublime" => Ok(Self::Sublime),
+            "jetbrains" | "idea" => Ok(Self::JetBrains),
+            _ => Err(format!(
+                "Unknown workspace format '{s}'. Expected vscode, cursor, kiro, zed, sublime or jetbrains"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for WorkspaceFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<WorkspaceFormat> for String {
+    fn from(value: WorkspaceFormat) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for WorkspaceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VsCode => write!(f, "vscode"),
+            Self::Zed => write!(f, "zed"),
+            Self::Sublime => write!(f, "sublime"),
+            Self::JetBrains => write!(f, "jetbrains"),
+        }
+    }
+}
+
+/// en: Outcome of creating a worktree, used to undo it on rollback
+///
+/// ja: worktree作成の結果（ロールバック時の取り消しに使用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedWorktree {
+    /// Worktree name registered in the bare repository
+    pub name: String,
+    /// Whether the branch was newly created for this worktree
+    pub branch_created: bool,
+    /// Where a newly created branch starts from
+    pub base: Option<BranchBase>,
+}
+
+/// en: The ref and commit a new branch was created from
+///
+/// ja: 新しいブランチの作成元となった参照とコミット
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BranchBase {
+    /// Ref as resolved, e.g. `origin/develop` or `HEAD`
+    pub reference: String,
+    /// Abbreviated commit id
+    pub commit: String,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+/// en: A branch created while generating a workspace
+///
+/// ja: workspace生成時に作成されたブランチ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewBranch {
+    pub repo_name: String,
+    pub branch: String,
+    pub base: BranchBase,
 }
 
 #[derive(Debug, Clone)]
@@ -15,53 +234,294 @@ pub struct WorktreeInfo {
     pub repo_name: String,
 }
 
-#[derive(Debug, Clone)]
+/// en: A branch offered when picking the branch of a worktree
+///
+/// ja: worktreeのブランチ選択時に表示するブランチ
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BranchInfo {
+    /// Branch name without the `origin/` prefix
     pub name: String,
     pub is_default: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct WorkspaceSettings {}
+/// en: The `settings` block of a workspace file
+///
+/// ja: workspaceファイルの `settings` ブロック
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct WorkspaceSettings {
+    #[serde(flatten)]
+    pub values: serde_json::Map<String, serde_json::Value>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceFile {
     pub folders: Vec<WorkspaceFolder>,
+    #[serde(default)]
     pub settings: WorkspaceSettings,
+    /// Keys wx does not manage (e.g., "extensions", "launch"), kept as they are
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceFolder {
     pub path: String,
+    /// Display name shown by the editor instead of the directory name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Keys wx does not manage, kept as they are
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl WorkspaceFolder {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            name: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// en: Folder of a worktree, named after its repository when the directory name differs
+    ///
+    /// ja: worktreeのフォルダ（ディレクトリ名と異なる場合はリポジトリ名を名前にする）
+    pub fn for_worktree(dir_name: &str, repo_name: &str) -> Self {
+        Self {
+            name: (dir_name != repo_name).then(|| repo_name.to_string()),
+            ..Self::new(dir_name.to_string())
+        }
+    }
 }
 
 impl WorkspaceFile {
     pub fn new(folders: Vec<String>) -> Self {
+        Self::with_settings(folders, WorkspaceSettings::default())
+    }
+
+    pub fn with_settings(folders: Vec<String>, settings: WorkspaceSettings) -> Self {
         Self {
-            folders: folders
-                .into_iter()
-                .map(|f| WorkspaceFolder { path: f })
-                .collect(),
-            settings: WorkspaceSettings::default(),
+            folders: folders.into_iter().map(WorkspaceFolder::new).collect(),
+            settings,
+            extra: serde_json::Map::new(),
         }
     }
 }
 
+/// en: A workspace created by `wx new` and recorded in config.json
+///
+/// ja: `wx new` で作成され、config.jsonに記録されたworkspace
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceEntry {
+    /// Workspace name (e.g., "feature-auth")
+    pub name: String,
+    /// Absolute path to the workspace directory
+    pub path: PathBuf,
+    /// Repositories checked out in the workspace
+    pub members: Vec<WorkspaceMember>,
+    /// Time the workspace was created
+    pub created_at: DateTime<Utc>,
+    /// Absolute path the editor opens: the `.code-workspace` file unless other formats were chosen
+    pub workspace_file: PathBuf,
+    /// Editor formats written for the workspace (VS Code when empty)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formats: Vec<WorkspaceFormat>,
+}
+
+impl WorkspaceEntry {
+    /// en: Find the worktree of a repository, narrowed down by branch when given
+    /// A repository checked out more than once needs the branch
+    ///
+    /// ja: リポジトリのworktreeを探す（ブランチ指定時はブランチでも絞り込む）
+    /// 複数回チェックアウトされているリポジトリはブランチの指定が必要
+    pub fn find_member(
+        &self,
+        repo_name: &str,
+        branch: Option<&str>,
+    ) -> Result<&WorkspaceMember, WxError> {
+        let mut members = self.members.iter().filter(|member| {
+            member.repo_name == repo_name && branch.is_none_or(|branch| member.branch == branch)
+        });
+
+        match (members.next(), members.next()) {
+            (Some(member), None) => Ok(member),
+            (Some(_), Some(_)) => Err(WxError::General(format!(
+                "Repository '{repo_name}' is checked out more than once in workspace '{}'. Use <repo>:<branch>",
+                self.name
+            ))),
+            (None, _) => Err(WxError::NotInWorkspace(
+                repo_name.to_string(),
+                self.name.clone(),
+            )),
+        }
+    }
+
+    /// en: Returns the branch every worktree is on, if they all share one
+    ///
+    /// ja: 全worktreeが同じブランチの場合、そのブランチを返却する
+    pub fn common_branch(&self) -> Option<&str> {
+        let branch = self.members.first()?.branch.as_str();
+        self.members
+            .iter()
+            .all(|member| member.branch == branch)
+            .then_some(branch)
+    }
+}
+
+/// en: A repository/branch pair checked out as a worktree in a workspace
+///
+/// ja: workspace内にworktreeとしてチェックアウトされたリポジトリとブランチの組
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceMember {
+    pub repo_name: String,
+    pub branch: String,
+    /// Absolute path to the worktree
+    pub path: PathBuf,
+}
+
 #[derive(Debug, Clone)]
 pub enum CleanTarget {
-    All,              // wx clean --all
-    Worktree(String), // wx clean <worktree>
+    All,               // wx clean --all
+    Workspace(String), // wx clean <workspace>
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct GenerationResult {
     pub worktrees: Vec<PathBuf>,
     pub workspace_file: PathBuf,
+    /// Branches created for the worktrees, with the commit they start from
+    pub new_branches: Vec<NewBranch>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize)]
 pub struct CleanResult {
     pub removed_worktrees: Vec<PathBuf>,
     pub removed_workspace_files: Vec<PathBuf>,
     pub warnings: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(WorktreeLayout::Repo, "frontend")]
+    #[case(WorktreeLayout::RepoBranch, "frontend-feature-auth")]
+    #[case(WorktreeLayout::Template("{branch}@{repo}".to_string()), "feature-auth@frontend")]
+    fn test_worktree_layout_dir_name(#[case] layout: WorktreeLayout, #[case] expected: &str) {
+        assert_eq!(layout.dir_name("frontend", "feature/auth"), expected);
+    }
+
+    #[test]
+    fn test_worktree_selection_parse() {
+        assert_eq!(
+            WorktreeSelection::parse("frontend:feature/x", None).unwrap(),
+            WorktreeSelection {
+                repo_name: "frontend".to_string(),
+                branch: "feature/x".to_string(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            WorktreeSelection::parse("backend", Some("main"))
+                .unwrap()
+                .branch,
+            "main"
+        );
+        assert!(WorktreeSelection::parse("backend", None).is_err());
+        assert!(WorktreeSelection::parse("backend:", Some("main")).is_err());
+        assert!(WorktreeSelection::parse(":main", None).is_err());
+    }
+
+    #[test]
+    fn test_worktree_layout_from_str() {
+        assert_eq!(
+            "repo".parse::<WorktreeLayout>().unwrap(),
+            WorktreeLayout::Repo
+        );
+        assert_eq!(
+            "repo-branch".parse::<WorktreeLayout>().unwrap(),
+            WorktreeLayout::RepoBranch
+        );
+        assert_eq!(
+            "{repo}_{branch}".parse::<WorktreeLayout>().unwrap(),
+            WorktreeLayout::Template("{repo}_{branch}".to_string())
+        );
+        assert!("static".parse::<WorktreeLayout>().is_err());
+    }
+
+    #[test]
+    fn test_worktree_layout_serialization() {
+        let json = serde_json::to_string(&WorktreeLayout::RepoBranch).unwrap();
+        assert_eq!(json, "\"repo-branch\"");
+
+        let layout: WorktreeLayout = serde_json::from_str("\"{repo}\"").unwrap();
+        assert_eq!(layout, WorktreeLayout::Template("{repo}".to_string()));
+        assert!(serde_json::from_str::<WorktreeLayout>("\"static\"").is_err());
+    }
+
+    #[test]
+    fn test_workspace_entry_find_member() {
+        let member = |repo_name: &str, branch: &str| WorkspaceMember {
+            repo_name: repo_name.to_string(),
+            branch: branch.to_string(),
+            path: PathBuf::from(format!("/work/ws/{repo_name}-{branch}")),
+        };
+        let mut entry = WorkspaceEntry {
+            name: "ws".to_string(),
+            path: PathBuf::from("/work/ws"),
+            members: vec![member("frontend", "main"), member("backend", "main")],
+            created_at: Utc::now(),
+            workspace_file: PathBuf::from("/work/ws/ws.code-workspace"),
+            formats: Vec::new(),
+        };
+
+        assert_eq!(entry.common_branch(), Some("main"));
+        assert_eq!(
+            entry.find_member("backend", None).unwrap().path,
+            PathBuf::from("/work/ws/backend-main")
+        );
+        assert!(matches!(
+            entry.find_member("mobile", None),
+            Err(WxError::NotInWorkspace(_, _))
+        ));
+
+        entry.members.push(member("backend", "hotfix"));
+        assert_eq!(entry.common_branch(), None);
+        assert!(matches!(
+            entry.find_member("backend", None),
+            Err(WxError::General(_))
+        ));
+        assert_eq!(
+            entry.find_member("backend", Some("hotfix")).unwrap().branch,
+            "hotfix"
+        );
+    }
+
+    #[test]
+    fn test_workspace_format_from_str() {
+        assert_eq!(
+            "cursor".parse::<WorkspaceFormat>().unwrap(),
+            WorkspaceFormat::VsCode
+        );
+        assert_eq!(
+            "JetBrains".parse::<WorkspaceFormat>().unwrap(),
+            WorkspaceFormat::JetBrains
+        );
+        assert!("emacs".parse::<WorkspaceFormat>().is_err());
+        assert_eq!(
+            serde_json::to_string(&[WorkspaceFormat::VsCode, WorkspaceFormat::Zed]).unwrap(),
+            r#"["vscode","zed"]"#
+        );
+        assert_eq!(
+            WorkspaceFormat::or_default(&[]),
+            vec![WorkspaceFormat::VsCode]
+        );
+        assert_eq!(
+            WorkspaceFormat::or_default(&[WorkspaceFormat::Zed, WorkspaceFormat::Zed]),
+            vec![WorkspaceFormat::Zed]
+        );
+    }
+}
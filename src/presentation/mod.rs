@@ -0,0 +1,192 @@
+use std::{fmt, str::FromStr};
+
+use console::style;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::models::{error::ErrorReport, WxError};
+
+mod progress;
+mod text;
+mod view;
+
+pub use progress::TransferBars;
+pub use text::{print_exec_line, print_welcome};
+pub use view::{
+    Added, ExecOutputLine, ExecRun, ExecWorktree, GroupCreated, GroupRemoved, Removed, ShellScript,
+    Unregistered, WorkspaceDir,
+};
+
+/// en: Output format selected with the global `--output` option
+///
+/// ja: グローバルオプション `--output` で選択する出力形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored output for people
+    #[default]
+    Text,
+    /// A single JSON document on stdout for scripts
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Unknown output format '{s}'. Expected text or json"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// en: A command result that can be printed as text
+/// The JSON form comes from its `Serialize` implementation
+///
+/// ja: テキストとして出力できるコマンドの結果
+/// JSON形式は `Serialize` の実装から生成する
+pub trait Render {
+    fn render_text(&self) -> Result<(), WxError>;
+}
+
+/// en: Prints command results and errors in the selected output format
+///
+/// ja: 選択された出力形式でコマンドの結果とエラーを出力する
+#[derive(Debug, Clone, Copy)]
+pub struct Presenter {
+    format: OutputFormat,
+}
+
+impl Presenter {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// en: Progress bars for the clones or fetches of a command
+    ///
+    /// ja: コマンドのclone・フェッチ用の進捗バー
+    pub fn transfer_bars(&self) -> TransferBars {
+        TransferBars::new(self.format)
+    }
+
+    pub fn render<T: Render + Serialize + ?Sized>(&self, value: &T) -> Result<(), WxError> {
+        match self.format {
+            OutputFormat::Text => value.render_text(),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(value)?);
+                Ok(())
+            }
+        }
+    }
+
+    /// en: Prints an error of a command
+    /// With `--output json` every error is printed as a JSON object to stdout.
+    /// As text, errors the user can act on are printed in color to stderr, and the rest are
+    /// handed back to be reported with their cause
+    ///
+    /// ja: コマンドのエラーを出力する
+    /// `--output json` では全てのエラーをJSONオブジェクトとして標準出力に出力する
+    /// テキストではユーザーが対処できるエラーを色付きで標準エラー出力に出力し、それ以外は原因と共に報告するため呼び出し元へ返す
+    pub fn render_error(&self, error: WxError) -> Result<(), WxError> {
+        if self.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&error_json(&error))?);
+            return Ok(());
+        }
+
+        match error {
+            WxError::AlreadyRegistered(_) => eprintln!("{}", style(error).for_stderr().yellow()),
+            WxError::RepositoryInUse(_, ref workspaces) => {
+                eprintln!("{}", style(&error).for_stderr().red());
+                eprintln!(
+                    "{}",
+                    style("Workspaces that would be orphaned:")
+                        .for_stderr()
+                        .yellow()
+                );
+                for workspace in workspaces {
+                    eprintln!("  {}", style(workspace.display()).for_stderr().cyan());
+                }
+            }
+            WxError::RepositoryNotFound(_)
+            | WxError::RepositoryNameTaken(_, _)
+            | WxError::InvalidRepositoryName(_)
+            | WxError::AuthenticationFailed(_, _)
+            | WxError::GroupNotFound(_)
+            | WxError::InvalidDirectoryName(_)
+            | WxError::GroupAlreadyExists(_)
+            | WxError::WorkspaceNotFound(_)
+            | WxError::WorkspaceAlreadyRegistered(_)
+            | WxError::AlreadyInWorkspace(_, _)
+            | WxError::NotInWorkspace(_, _)
+            | WxError::UncommittedChanges(_)
+            | WxError::TemplateNotFound(_)
+            | WxError::InvalidTemplateName(_)
+            | WxError::EditorNotFound(_)
+            | WxError::General(_) => eprintln!("{}", style(error).for_stderr().red()),
+            _ => return Err(error),
+        }
+        Ok(())
+    }
+}
+
+/// en: JSON form of an error: `{"error": {"code": ..., "message": ...}}`
+///
+/// ja: エラーのJSON形式: `{"error": {"code": ..., "message": ...}}`
+fn error_json(error: &WxError) -> Value {
+    let mut body = json!(ErrorReport::from(error));
+    if let WxError::RepositoryInUse(_, workspaces) = error {
+        body["workspaces"] = json!(workspaces);
+    }
+    json!({ "error": body })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("text".parse(), Ok(OutputFormat::Text));
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_error_json() {
+        let error = WxError::WorkspaceNotFound("feature".to_string());
+        assert_eq!(
+            error_json(&error),
+            json!({
+                "error": {
+                    "code": "workspace_not_found",
+                    "message": "Workspace 'feature' not found",
+                }
+            })
+        );
+
+        let error =
+            WxError::RepositoryInUse("frontend".to_string(), vec![PathBuf::from("/work/feature")]);
+        assert_eq!(
+            error_json(&error)["error"]["workspaces"],
+            json!(["/work/feature"])
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use super::OutputFormat;
+use crate::models::transfer::TransferProgress;
+
+/// en: Progress bars of concurrent clones and fetches, one per repository, drawn on stderr
+/// Hidden with `--output json`, and cleared when dropped
+///
+/// ja: 並行して実行するclone・フェッチの進捗バー（リポジトリごとに1本、標準エラー出力に描画する）
+/// `--output json` では表示せず、破棄時に消去する
+pub struct TransferBars {
+    multi: MultiProgress,
+    bars: Mutex<HashMap<usize, ProgressBar>>,
+}
+
+impl TransferBars {
+    pub fn new(format: OutputFormat) -> Self {
+        let target = match format {
+            OutputFormat::Text => ProgressDrawTarget::stderr(),
+            OutputFormat::Json => ProgressDrawTarget::hidden(),
+        };
+        Self {
+            multi: MultiProgress::with_draw_target(target),
+            bars: Mutex::default(),
+        }
+    }
+
+    /// en: Updates the bar of the `index`-th transfer, adding it on its first progress
+    ///
+    /// ja: `index` 番目の転送のバーを更新する（最初の進捗でバーを追加する）
+    pub fn update(&self, index: usize, repo_name: &str, progress: &TransferProgress) {
+        let mut bars = self.bars.lock().unwrap();
+        let bar = bars.entry(index).or_insert_with(|| {
+            self.multi.add(
+                ProgressBar::new(0)
+                    .with_style(
+                        ProgressStyle::with_template(
+                            "{prefix:<16.cyan} [{bar:30}] {pos}/{len} {msg}",
+                        )
+                        .unwrap()
+                        .progress_chars("=> "),
+                    )
+                    .with_prefix(repo_name.to_string()),
+            )
+        });
+
+        if progress.is_resolving_deltas() {
+            bar.set_length(progress.total_deltas as u64);
+            bar.set_position(progress.indexed_deltas as u64);
+            bar.set_message("resolving deltas");
+        } else {
+            bar.set_length(progress.total_objects as u64);
+            bar.set_position(progress.received_objects as u64);
+            bar.set_message(HumanBytes(progress.received_bytes as u64).to_string());
+        }
+    }
+}
+
+impl Drop for TransferBars {
+    fn drop(&mut self) {
+        for bar in self.bars.lock().unwrap().values() {
+            bar.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,594 @@
+use console::style;
+use ptree::{print_config::StyleWhen, print_tree_with, Color, PrintConfig, Style, TreeBuilder};
+
+use super::{
+    view::{
+        Added, ExecRun, GroupCreated, GroupRemoved, Removed, ShellScript, Unregistered,
+        WorkspaceDir,
+    },
+    Render,
+};
+use crate::models::{
+    editor::OpenTarget,
+    exec::{ExecStatus, OutputStream},
+    manifest::ApplyResult,
+    status::WorkspaceStatus,
+    sync::{SyncOutcome, SyncResult},
+    transfer::{FetchReport, RegisterReport},
+    workspace::{CleanResult, GenerationResult, WorkspaceEntry},
+    Repository, RepositoryGroup, WxError,
+};
+
+pub fn print_welcome() {
+    // ASCII art logo for wx (block style)
+    let logo_lines = [
+        "██╗    ██╗██╗  ██╗",
+        "██║    ██║╚██╗██╔╝",
+        "██║ █╗ ██║ ╚███╔╝ ",
+        "██║███╗██║ ██╔██╗ ",
+        "╚███╔███╔╝██╔╝ ██╗",
+        " ╚══╝╚══╝ ╚═╝  ╚═╝",
+    ];
+
+    println!();
+    for line in &logo_lines {
+        println!("  {}", style(*line).cyan());
+    }
+    println!();
+    println!(
+        "  {}",
+        style("Git worktree & workspace manager").italic().dim()
+    );
+    println!();
+    println!(
+        "  {}  {}",
+        style("wx tui").cyan(),
+        style("Open the dashboard").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx list").cyan(),
+        style("List registered repositories").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx register <repository url>...").cyan(),
+        style("Register Git repositories").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx fetch --all").cyan(),
+        style("Fetch every registered repository").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx unregister <repository name>").cyan(),
+        style("Unregister a Git repository").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx group create <group name> <repository name>...").cyan(),
+        style("Group repositories to select them with --group").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx new <workspace name>").cyan(),
+        style("Create a new workspace").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx open [workspace name]").cyan(),
+        style("Open a workspace in your editor").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx cd <workspace name>[/repo]").cyan(),
+        style("Change to a workspace (after wx shell-init)").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx add <workspace name> <repo>[:branch]").cyan(),
+        style("Add a repository to a workspace").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx remove <workspace name> <repo>").cyan(),
+        style("Remove a repository from a workspace").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx apply <manifest>").cyan(),
+        style("Create or update a workspace from wx.toml").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx workspaces").cyan(),
+        style("List registered workspaces").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx status [workspace name]").cyan(),
+        style("Show the git status of a workspace").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx sync [workspace name]").cyan(),
+        style("Fetch and fast-forward every worktree").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx exec <workspace name> -- <command>").cyan(),
+        style("Run a command in every worktree").dim()
+    );
+    println!(
+        "  {}  {}",
+        style("wx clean <workspace name>").cyan(),
+        style("Remove a workspace and its worktrees").dim()
+    );
+    println!();
+}
+
+/// Colors that tell the output of concurrently running worktrees apart
+const EXEC_COLORS: [console::Color; 5] = [
+    console::Color::Cyan,
+    console::Color::Magenta,
+    console::Color::Yellow,
+    console::Color::Green,
+    console::Color::Blue,
+];
+
+pub fn print_exec_line(index: usize, repo_name: &str, stream: OutputStream, line: &str) {
+    let prefix = style(format!("[{repo_name}]")).fg(EXEC_COLORS[index % EXEC_COLORS.len()]);
+    match stream {
+        OutputStream::Stdout => println!("{prefix} {line}"),
+        OutputStream::Stderr => eprintln!("{prefix} {line}"),
+    }
+}
+
+/// Tree style shared by every tree wx prints, with the leaves in cyan
+fn tree_config() -> PrintConfig {
+    PrintConfig {
+        styled: StyleWhen::Always,
+        leaf: Style {
+            foreground: Some(Color::Cyan),
+            ..Style::default()
+        },
+        ..PrintConfig::default()
+    }
+}
+
+impl Render for [RegisterReport] {
+    fn render_text(&self) -> Result<(), WxError> {
+        for report in self {
+            match &report.error {
+                None => println!("Registered: {}", style(&report.url).cyan()),
+                Some(_) if !report.is_failure() => println!(
+                    "{} {}",
+                    style("Already registered:").yellow(),
+                    style(&report.url).cyan()
+                ),
+                Some(error) => println!(
+                    "{} {}: {}",
+                    style("Failed to register").red(),
+                    style(&report.url).cyan(),
+                    style(&error.message).red()
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Render for [FetchReport] {
+    fn render_text(&self) -> Result<(), WxError> {
+        if self.is_empty() {
+            println!("{}", style("No registered repositories.").yellow());
+        }
+        for report in self {
+            match &report.error {
+                None => println!(
+                    "{} {}",
+                    style("Fetched:").green(),
+                    style(&report.repo_name).cyan()
+                ),
+                Some(error) => println!(
+                    "{} {}: {}",
+                    style("Failed to fetch").red(),
+                    style(&report.repo_name).cyan(),
+                    style(&error.message).red()
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Render for Unregistered {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!("Unregistered: {}", style(&self.name).cyan());
+        if !self.orphaned_workspaces.is_empty() {
+            println!("{}", style("Orphaned workspaces:").yellow());
+            for workspace in &self.orphaned_workspaces {
+                println!("  {}", style(workspace.display()).cyan());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Render for [Repository] {
+    fn render_text(&self) -> Result<(), WxError> {
+        if self.is_empty() {
+            println!("{}", style("No registered repositories.").yellow());
+            return Ok(());
+        }
+        let config = tree_config();
+        for repo in self {
+            let tree = TreeBuilder::new(repo.name.clone())
+                .add_empty_child(repo.remote.clone())
+                .build();
+            print_tree_with(&tree, &config)?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for GroupCreated {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!(
+            "{} {} ({})",
+            style("Created group:").green(),
+            style(&self.0.name).cyan(),
+            self.0.repositories.join(", ")
+        );
+        Ok(())
+    }
+}
+
+impl Render for GroupRemoved {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!("Removed group: {}", style(&self.0.name).cyan());
+        Ok(())
+    }
+}
+
+impl Render for [RepositoryGroup] {
+    fn render_text(&self) -> Result<(), WxError> {
+        if self.is_empty() {
+            println!("{}", style("No groups.").yellow());
+            return Ok(());
+        }
+        let config = tree_config();
+        for group in self {
+            let mut tree = TreeBuilder::new(group.name.clone());
+            for repo_name in &group.repositories {
+                tree.add_empty_child(repo_name.clone());
+            }
+            print_tree_with(&tree.build(), &config)?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for GenerationResult {
+    fn render_text(&self) -> Result<(), WxError> {
+        for new_branch in &self.new_branches {
+            println!(
+                "{} {} {} from {} ({} {})",
+                style("Created branch").green(),
+                style(&new_branch.branch).cyan(),
+                style(format!("[{}]", new_branch.repo_name)).dim(),
+                style(&new_branch.base.reference).cyan(),
+                style(&new_branch.base.commit).yellow(),
+                new_branch.base.summary
+            );
+        }
+        println!("{}", style("Workspace created.").green());
+        println!("  {}", style(self.workspace_file.display()).cyan());
+        Ok(())
+    }
+}
+
+impl Render for ApplyResult {
+    fn render_text(&self) -> Result<(), WxError> {
+        let result = self;
+        for repo_name in &result.registered_repositories {
+            println!(
+                "{} {}",
+                style("Registered:").green(),
+                style(repo_name).cyan()
+            );
+        }
+        if result.created {
+            println!("{}", style("Workspace created.").green());
+            println!("  {}", style(result.workspace_dir.display()).cyan());
+        }
+        if !result.created {
+            for member in &result.added {
+                println!(
+                    "{} {} ({})",
+                    style("Added worktree:").green(),
+                    style(member.path.display()).cyan(),
+                    member.branch
+                );
+            }
+        }
+        if !result.drift.is_empty() {
+            println!("{}", style("Drift (left untouched):").yellow());
+            for drift in &result.drift {
+                println!("  {}", style(drift).yellow());
+            }
+        }
+        if !result.created && result.added.is_empty() && result.drift.is_empty() {
+            println!("{}", style("Workspace is up to date.").green());
+        }
+        Ok(())
+    }
+}
+
+impl Render for OpenTarget {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!(
+            "{} {}",
+            style("Opened").green(),
+            style(self.workspace_file.display()).cyan()
+        );
+        Ok(())
+    }
+}
+
+impl Render for WorkspaceDir {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!("{}", self.path.display());
+        Ok(())
+    }
+}
+
+impl Render for ShellScript {
+    fn render_text(&self) -> Result<(), WxError> {
+        print!("{}", self.script);
+        Ok(())
+    }
+}
+
+impl Render for [WorkspaceEntry] {
+    fn render_text(&self) -> Result<(), WxError> {
+        if self.is_empty() {
+            println!("{}", style("No registered workspaces.").yellow());
+            return Ok(());
+        }
+        let config = tree_config();
+        for workspace in self {
+            let mut tree = TreeBuilder::new(workspace.name.clone());
+            for member in &workspace.members {
+                tree.add_empty_child(format!("{} ({})", member.repo_name, member.branch));
+            }
+            print_tree_with(&tree.build(), &config)?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for WorkspaceEntry {
+    fn render_text(&self) -> Result<(), WxError> {
+        let mut tree = TreeBuilder::new(self.name.clone());
+        tree.add_empty_child(format!("path: {}", self.path.display()))
+            .add_empty_child(format!("workspace file: {}", self.workspace_file.display()))
+            .add_empty_child(format!(
+                "created: {}",
+                self.created_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+            ))
+            .begin_child("repositories".to_string());
+        for member in &self.members {
+            tree.add_empty_child(format!(
+                "{} ({}) {}",
+                member.repo_name,
+                member.branch,
+                member.path.display()
+            ));
+        }
+        tree.end_child();
+        print_tree_with(&tree.build(), &tree_config())?;
+        Ok(())
+    }
+}
+
+impl Render for WorkspaceStatus {
+    fn render_text(&self) -> Result<(), WxError> {
+        let status = self;
+        println!(
+            "{} {}",
+            style(&status.name).bold(),
+            style(status.path.display()).dim()
+        );
+
+        let rows: Vec<[String; 5]> = status
+            .worktrees
+            .iter()
+            .map(|member| {
+                let worktree = &member.status;
+                let branch = worktree
+                    .branch
+                    .clone()
+                    .unwrap_or_else(|| "(detached)".to_string());
+                let sync = match &worktree.upstream {
+                    Some(_) => format!("↑{} ↓{}", worktree.ahead, worktree.behind),
+                    None => "-".to_string(),
+                };
+                let changes = format!(
+                    "+{} ~{} ?{}",
+                    worktree.staged, worktree.dirty, worktree.untracked
+                );
+                let mut state: Vec<String> = Vec::new();
+                if worktree.missing {
+                    state.push("missing".to_string());
+                }
+                if worktree.locked {
+                    state.push("locked".to_string());
+                }
+                if worktree.stashes > 0 {
+                    state.push(format!("stash {}", worktree.stashes));
+                }
+                [
+                    member.repo_name.clone(),
+                    if worktree.missing {
+                        "-".to_string()
+                    } else {
+                        branch
+                    },
+                    sync,
+                    changes,
+                    state.join(", "),
+                ]
+            })
+            .collect();
+
+        let width = |column: usize| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        };
+        let widths = [width(0), width(1), width(2), width(3)];
+
+        for (row, member) in rows.iter().zip(&status.worktrees) {
+            let worktree = &member.status;
+            let sync = format!("{:<w$}", row[2], w = widths[2]);
+            let changes = format!("{:<w$}", row[3], w = widths[3]);
+            let line = format!(
+                "  {}  {}  {}  {}",
+                style(format!("{:<w$}", row[0], w = widths[0])).cyan(),
+                format_args!("{:<w$}", row[1], w = widths[1]),
+                if worktree.ahead > 0 || worktree.behind > 0 {
+                    style(sync).yellow()
+                } else {
+                    style(sync).dim()
+                },
+                if worktree.staged + worktree.dirty + worktree.untracked > 0 {
+                    style(changes).yellow()
+                } else {
+                    style(changes).dim()
+                },
+            );
+            if row[4].is_empty() {
+                println!("{line}");
+            } else {
+                println!("{line}  {}", style(&row[4]).red());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Render for SyncResult {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!("{}", style(self.workspace_dir.display()).dim());
+        let width = self
+            .reports
+            .iter()
+            .map(|report| report.repo_name.chars().count())
+            .max()
+            .unwrap_or(0);
+        for report in &self.reports {
+            let outcome = match &report.outcome {
+                SyncOutcome::FastForwarded { .. } | SyncOutcome::Rebased { .. } => {
+                    style(report.outcome.to_string()).green()
+                }
+                outcome if outcome.is_skipped() => style(report.outcome.to_string()).yellow(),
+                _ => style(report.outcome.to_string()).dim(),
+            };
+            println!(
+                "  {}  {}",
+                style(format!("{:<width$}", report.repo_name)).cyan(),
+                outcome
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Render for Added {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!(
+            "{} {} ({})",
+            style("Added worktree:").green(),
+            style(self.0.path.display()).cyan(),
+            self.0.branch
+        );
+        Ok(())
+    }
+}
+
+impl Render for Removed {
+    fn render_text(&self) -> Result<(), WxError> {
+        println!(
+            "{} {} ({})",
+            style("Removed worktree:").green(),
+            style(self.0.path.display()).cyan(),
+            self.0.branch
+        );
+        Ok(())
+    }
+}
+
+impl Render for ExecRun {
+    fn render_text(&self) -> Result<(), WxError> {
+        let reports: Vec<_> = self
+            .worktrees
+            .iter()
+            .map(|worktree| &worktree.report)
+            .collect();
+        let width = reports
+            .iter()
+            .map(|report| report.repo_name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        println!();
+        for report in reports {
+            let status = format!("{:<9}", report.status.to_string());
+            println!(
+                "  {}  {}  {}",
+                style(format!("{:<width$}", report.repo_name)).cyan(),
+                match report.status {
+                    ExecStatus::Succeeded => style(status).green(),
+                    ExecStatus::Skipped | ExecStatus::Cancelled => style(status).yellow(),
+                    _ => style(status).red(),
+                },
+                style(format!("{:.1}s", report.duration.as_secs_f64())).dim()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Render for CleanResult {
+    fn render_text(&self) -> Result<(), WxError> {
+        for worktree in &self.removed_worktrees {
+            println!(
+                "{} {}",
+                style("Removed worktree:").green(),
+                style(worktree.display()).cyan()
+            );
+        }
+        for workspace_file in &self.removed_workspace_files {
+            println!(
+                "{} {}",
+                style("Removed workspace file:").green(),
+                style(workspace_file.display()).cyan()
+            );
+        }
+        for warning in &self.warnings {
+            println!("{}", style(warning).yellow());
+        }
+        if self.removed_worktrees.is_empty()
+            && self.removed_workspace_files.is_empty()
+            && self.warnings.is_empty()
+        {
+            println!("{}", style("Nothing to clean.").yellow());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::models::{
+    exec::{ExecReport, OutputStream},
+    shell::Shell,
+    workspace::WorkspaceMember,
+    RepositoryGroup,
+};
+
+/// en: A repository unregistered by `wx unregister`, with the workspaces left orphaned
+///
+/// ja: `wx unregister` で登録を解除したリポジトリと、孤立したworkspace
+#[derive(Debug, Serialize)]
+pub struct Unregistered {
+    pub name: String,
+    pub orphaned_workspaces: Vec<PathBuf>,
+}
+
+/// en: A worktree added to a workspace by `wx add`
+///
+/// ja: `wx add` でworkspaceに追加したworktree
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct Added(pub WorkspaceMember);
+
+/// en: A worktree removed from a workspace by `wx remove`
+///
+/// ja: `wx remove` でworkspaceから削除したworktree
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct Removed(pub WorkspaceMember);
+
+/// en: A group created by `wx group create`
+///
+/// ja: `wx group create` で作成したグループ
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct GroupCreated(pub RepositoryGroup);
+
+/// en: A group removed by `wx group rm`
+///
+/// ja: `wx group rm` で削除したグループ
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct GroupRemoved(pub RepositoryGroup);
+
+/// en: The directory `wx cd` changes to
+///
+/// ja: `wx cd` の移動先ディレクトリ
+#[derive(Debug, Serialize)]
+pub struct WorkspaceDir {
+    pub path: PathBuf,
+}
+
+/// en: The script printed by `wx shell-init`
+///
+/// ja: `wx shell-init` が出力するスクリプト
+#[derive(Debug, Serialize)]
+pub struct ShellScript {
+    pub shell: Shell,
+    pub script: String,
+}
+
+/// en: Result of `wx exec`
+/// As text the output is streamed while the commands run, so only the summary is rendered
+///
+/// ja: `wx exec` の結果
+/// テキストでは出力をコマンドの実行中に逐次表示するため、集計のみを出力する
+#[derive(Debug, Serialize)]
+pub struct ExecRun {
+    pub worktrees: Vec<ExecWorktree>,
+}
+
+impl ExecRun {
+    pub fn is_success(&self) -> bool {
+        self.worktrees
+            .iter()
+            .all(|worktree| worktree.report.status.is_success())
+    }
+}
+
+/// en: Result and collected output of the command in one worktree
+///
+/// ja: 1つのworktreeでのコマンドの結果と、収集した出力
+#[derive(Debug, Serialize)]
+pub struct ExecWorktree {
+    #[serde(flatten)]
+    pub report: ExecReport,
+    pub output: Vec<ExecOutputLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecOutputLine {
+    pub stream: OutputStream,
+    pub line: String,
+}
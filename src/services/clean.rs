@@ -0,0 +1,406 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    infrastructure::{config::ConfigManager, filesystem::workspace_writer, git::WorktreeManager},
+    models::{
+        workspace::{CleanResult, CleanTarget, WorkspaceFormat},
+        WxError,
+    },
+    services::WorkspaceRegistryService,
+    utils::normalize,
+};
+
+/// en: A workspace to clean and the editor formats whose files wx wrote for it
+///
+/// ja: 削除対象のworkspaceと、wxがファイルを書き出したエディタ形式
+struct CleanWorkspace {
+    dir: PathBuf,
+    name: String,
+    formats: Vec<WorkspaceFormat>,
+}
+
+/// en: Service for removing workspaces together with their worktrees
+///
+/// ja: worktreeを含むworkspaceを削除するサービス
+pub struct WorkspaceCleanService<W: WorktreeManager> {
+    worktree_manager: W,
+    config_manager: ConfigManager,
+    registry: WorkspaceRegistryService,
+}
+
+impl<W: WorktreeManager> WorkspaceCleanService<W> {
+    pub fn new(worktree_manager: W, wx_home: PathBuf) -> Result<Self, WxError> {
+        Ok(Self {
+            worktree_manager,
+            config_manager: ConfigManager::with_base_dir(&wx_home),
+            registry: WorkspaceRegistryService::with_base_dir(&wx_home),
+        })
+    }
+
+    /// en: Clean the target workspaces
+    /// A named workspace is resolved through the registry first, then as a directory in `base_dir`.
+    /// Worktrees with uncommitted changes are skipped unless `force` is set
+    ///
+    /// ja: 対象のworkspaceを削除する
+    /// 名前指定のworkspaceはまずレジストリから、次に `base_dir` 内のディレクトリとして解決する。
+    /// 未コミットの変更があるworktreeは `force` 指定時以外スキップする
+    pub fn clean(
+        &self,
+        base_dir: &Path,
+        target: CleanTarget,
+        force: bool,
+    ) -> Result<CleanResult, WxError> {
+        let workspaces = match target {
+            CleanTarget::Workspace(workspace_name) => {
+                match self.registry.resolve(&workspace_name, base_dir) {
+                    Ok(entry) => vec![CleanWorkspace {
+                        formats: WorkspaceFormat::or_default(&entry.formats),
+                        dir: entry.path,
+                        name: entry.name,
+                    }],
+                    Err(WxError::WorkspaceNotFound(_)) => {
+                        let workspace_dir = base_dir.join(&workspace_name);
+                        if !workspace_dir.is_dir() {
+                            return Err(WxError::WorkspaceNotFound(workspace_name));
+                        }
+                        // without a registry entry only the `.code-workspace` file is known to be ours
+                        vec![CleanWorkspace {
+                            dir: workspace_dir,
+                            name: workspace_name,
+                            formats: vec![WorkspaceFormat::VsCode],
+                        }]
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            CleanTarget::All => self.find_workspaces(base_dir)?,
+        };
+
+        let mut result = CleanResult::default();
+
+        for workspace in workspaces {
+            self.clean_workspace(&workspace, force, &mut result)?;
+        }
+
+        Ok(result)
+    }
+
+    /// en: Returns the workspaces directly under the base directory: registered workspaces, and
+    /// unregistered directories containing `<dir>/<dir>.code-workspace`
+    /// Project files of other editors are never taken as a sign of a workspace, since any
+    /// project can have them
+    ///
+    /// ja: ベースディレクトリ直下のworkspace（登録済みworkspaceと、`<dir>/<dir>.code-workspace`
+    /// を含む未登録のディレクトリ）を返却する
+    /// 他のエディタのプロジェクトファイルはどのプロジェクトにもあり得るため、workspaceの判定には使わない
+    fn find_workspaces(&self, base_dir: &Path) -> Result<Vec<CleanWorkspace>, WxError> {
+        let base_dir = normalize(base_dir);
+        let mut workspaces: Vec<CleanWorkspace> = self
+            .registry
+            .list()?
+            .into_iter()
+            .filter(|entry| {
+                entry.path.is_dir() && normalize(&entry.path).parent() == Some(base_dir.as_path())
+            })
+            .map(|entry| CleanWorkspace {
+                formats: WorkspaceFormat::or_default(&entry.formats),
+                dir: entry.path,
+                name: entry.name,
+            })
+            .collect();
+
+        for entry in fs::read_dir(&base_dir)? {
+            let path = entry?.path();
+            if !path.is_dir()
+                || workspaces
+                    .iter()
+                    .any(|workspace| normalize(&workspace.dir) == normalize(&path))
+            {
+                continue;
+            }
+
+            let Some(workspace_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            if workspace_writer(WorkspaceFormat::VsCode).exists(&path, workspace_name) {
+                workspaces.push(CleanWorkspace {
+                    name: workspace_name.to_string(),
+                    dir: path,
+                    formats: vec![WorkspaceFormat::VsCode],
+                });
+            }
+        }
+
+        workspaces.sort_by(|a, b| a.dir.cmp(&b.dir));
+        Ok(workspaces)
+    }
+
+    /// en: Remove every worktree located in the workspace directory, then the project files of
+    /// the editor formats wx wrote for it
+    ///
+    /// ja: workspaceディレクトリ内の全worktreeを削除し、その後wxが書き出したエディタ形式のプロジェクトファイルを削除する
+    fn clean_workspace(
+        &self,
+        workspace: &CleanWorkspace,
+        force: bool,
+        result: &mut CleanResult,
+    ) -> Result<(), WxError> {
+        let workspace_dir = normalize(&workspace.dir);
+        let mut skipped = false;
+
+        for repo in self.config_manager.load()?.repositories {
+            let bare_repo_path = repo.bare_repo_path();
+            if !bare_repo_path.exists() {
+                continue;
+            }
+
+            for worktree_name in self.worktree_manager.list_worktrees(&bare_repo_path)? {
+                let worktree_path = self
+                    .worktree_manager
+                    .worktree_path(&bare_repo_path, &worktree_name)?;
+
+                if !normalize(&worktree_path).starts_with(&workspace_dir) {
+                    continue;
+                }
+
+                let is_dirty = worktree_path.exists()
+                    && self
+                        .worktree_manager
+                        .has_uncommitted_changes(&worktree_path)?;
+
+                if is_dirty {
+                    if !force {
+                        skipped = true;
+                        result.warnings.push(format!(
+                            "Skipped '{}': worktree has uncommitted changes (use --force to remove it)",
+                            worktree_path.display()
+                        ));
+                        continue;
+                    }
+                    result.warnings.push(format!(
+                        "Removed '{}' with uncommitted changes",
+                        worktree_path.display()
+                    ));
+                }
+
+                self.worktree_manager
+                    .remove_worktree(&bare_repo_path, &worktree_name)?;
+                result.removed_worktrees.push(worktree_path);
+            }
+        }
+
+        if skipped {
+            return Ok(());
+        }
+
+        for format in &workspace.formats {
+            result
+                .removed_workspace_files
+                .extend(workspace_writer(*format).remove(&workspace_dir, &workspace.name)?);
+        }
+
+        if fs::read_dir(&workspace_dir)?.next().is_none() {
+            fs::remove_dir(&workspace_dir)?;
+        }
+
+        if let Some(entry) = self
+            .registry
+            .list()?
+            .into_iter()
+            .find(|entry| normalize(&entry.path) == workspace_dir)
+        {
+            self.registry.unregister(&entry.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Repository;
+
+    use crate::{
+        infrastructure::git::{DefaultWorktreeManager, MockWorktreeManager},
+        models::workspace::WorktreeSelection,
+        services::WorkspaceGenerationService,
+        utils::test_helpers::{
+            add_test_remote_branch, create_test_bare_repo, create_test_config_file,
+            create_test_repository_in, setup_test_dirs,
+        },
+    };
+
+    use super::*;
+
+    fn setup_workspace(dir: &Path, base_dir: &Path) -> PathBuf {
+        let working_dir = dir.join("work");
+        let workspace_dir = working_dir.join("sso");
+        fs::create_dir_all(&workspace_dir).unwrap();
+
+        create_test_config_file(
+            base_dir,
+            vec![
+                create_test_repository_in(base_dir, "wx-frontend"),
+                create_test_repository_in(base_dir, "wx-backend"),
+            ],
+        );
+        let frontend = create_test_bare_repo(base_dir, "wx-frontend");
+        let backend = create_test_bare_repo(base_dir, "wx-backend");
+        add_test_remote_branch(&Repository::open_bare(frontend).unwrap(), "sso-ui");
+        add_test_remote_branch(&Repository::open_bare(backend).unwrap(), "sso-api");
+
+        WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir.to_path_buf())
+            .unwrap()
+            .generate(
+                &workspace_dir,
+                vec![
+                    WorktreeSelection {
+                        repo_name: "wx-frontend".to_string(),
+                        branch: "sso-ui".to_string(),
+                        ..Default::default()
+                    },
+                    WorktreeSelection {
+                        repo_name: "wx-backend".to_string(),
+                        branch: "sso-api".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                "sso",
+            )
+            .unwrap();
+
+        working_dir
+    }
+
+    #[test]
+    fn test_clean_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = setup_workspace(dir.path(), &base_dir);
+
+        let service = WorkspaceCleanService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+
+        let result = service
+            .clean(
+                &working_dir,
+                CleanTarget::Workspace("sso".to_string()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(result.removed_worktrees.len(), 2);
+        assert_eq!(result.removed_workspace_files.len(), 1);
+        assert!(result.warnings.is_empty());
+        assert!(!working_dir.join("sso").exists());
+        assert!(DefaultWorktreeManager
+            .list_worktrees(&base_dir.join("wx-frontend.git"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clean_registered_workspace_from_any_dir() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = setup_workspace(dir.path(), &base_dir);
+
+        let service = WorkspaceCleanService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+
+        let result = service
+            .clean(
+                Path::new("/"),
+                CleanTarget::Workspace("sso".to_string()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(result.removed_worktrees.len(), 2);
+        assert!(!working_dir.join("sso").exists());
+        assert!(WorkspaceRegistryService::with_base_dir(&base_dir)
+            .list()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clean_skips_dirty_worktree() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = setup_workspace(dir.path(), &base_dir);
+        fs::write(working_dir.join("sso/wx-frontend/wip.txt"), "wip").unwrap();
+
+        let service = WorkspaceCleanService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+
+        let result = service
+            .clean(
+                &working_dir,
+                CleanTarget::Workspace("sso".to_string()),
+                false,
+            )
+            .unwrap();
+
+        assert_eq!(result.removed_worktrees.len(), 1);
+        assert!(result.removed_workspace_files.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(working_dir.join("sso/wx-frontend/wip.txt").exists());
+        assert!(working_dir.join("sso/sso.code-workspace").exists());
+    }
+
+    #[test]
+    fn test_clean_force_removes_dirty_worktree() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = setup_workspace(dir.path(), &base_dir);
+        fs::write(working_dir.join("sso/wx-frontend/wip.txt"), "wip").unwrap();
+
+        let service = WorkspaceCleanService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+
+        let result = service.clean(&working_dir, CleanTarget::All, true).unwrap();
+
+        assert_eq!(result.removed_worktrees.len(), 2);
+        assert_eq!(result.removed_workspace_files.len(), 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(!working_dir.join("sso").exists());
+    }
+
+    #[test]
+    fn test_clean_all_keeps_other_projects() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = setup_workspace(dir.path(), &base_dir);
+        let idea_dir = working_dir.join("myapp/.idea");
+        fs::create_dir_all(&idea_dir).unwrap();
+        fs::write(idea_dir.join("vcs.xml"), "<project />").unwrap();
+        fs::write(idea_dir.join("workspace.xml"), "<project />").unwrap();
+        let zed_settings = working_dir.join("notes/.zed/settings.json");
+        fs::create_dir_all(zed_settings.parent().unwrap()).unwrap();
+        fs::write(&zed_settings, "{}").unwrap();
+
+        let service = WorkspaceCleanService::new(DefaultWorktreeManager, base_dir).unwrap();
+        let result = service
+            .clean(&working_dir, CleanTarget::All, false)
+            .unwrap();
+
+        assert_eq!(result.removed_worktrees.len(), 2);
+        assert!(!working_dir.join("sso").exists());
+        assert!(idea_dir.join("vcs.xml").exists());
+        assert!(idea_dir.join("workspace.xml").exists());
+        assert!(zed_settings.exists());
+    }
+
+    #[test]
+    fn test_clean_workspace_not_found() {
+        let (dir, base_dir) = setup_test_dirs();
+        let mock_worktree_manager = MockWorktreeManager::new();
+
+        let service = WorkspaceCleanService::new(mock_worktree_manager, base_dir).unwrap();
+
+        assert!(matches!(
+            service.clean(
+                dir.path(),
+                CleanTarget::Workspace("missing".to_string()),
+                false
+            ),
+            Err(WxError::WorkspaceNotFound(_))
+        ));
+    }
+}
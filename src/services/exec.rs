@@ -0,0 +1,527 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    infrastructure::{config::ConfigManager, filesystem::WorkspaceFileManager},
+    models::{
+        exec::{ExecOptions, ExecReport, ExecStatus, OutputStream},
+        WxError,
+    },
+    services::WorkspaceRegistryService,
+};
+
+/// en: Service that runs a command in every worktree of a workspace
+///
+/// ja: workspaceの全worktreeでコマンドを実行するサービス
+pub struct WorkspaceExecService {
+    registry: WorkspaceRegistryService,
+    workspace_file_manager: WorkspaceFileManager,
+    config_manager: ConfigManager,
+}
+
+impl WorkspaceExecService {
+    pub fn new(wx_home: &Path) -> Self {
+        Self {
+            registry: WorkspaceRegistryService::with_base_dir(wx_home),
+            workspace_file_manager: WorkspaceFileManager,
+            config_manager: ConfigManager::with_base_dir(wx_home),
+        }
+    }
+
+    /// en: Run the command in each selected worktree, at most `options.jobs` at a time
+    /// Every output line is passed to `on_output` with the index of the worktree as it is written
+    ///
+    /// ja: 選択したworktreeでコマンドを実行する（同時実行数は最大 `options.jobs`）
+    /// 出力された各行は、worktreeのインデックスと共に逐次 `on_output` に渡される
+    pub fn exec<F>(
+        &self,
+        name_or_path: &str,
+        current_dir: &Path,
+        options: &ExecOptions,
+        on_output: F,
+    ) -> Result<Vec<ExecReport>, WxError>
+    where
+        F: Fn(usize, &str, OutputStream, &str) + Sync,
+    {
+        if options.command.is_empty() {
+            return Err(WxError::General("No command given".to_string()));
+        }
+
+        let members = self.select_members(name_or_path, current_dir, options)?;
+
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let _interrupt = InterruptGuard::register(&interrupted);
+        let cancelled = || {
+            (options.fail_fast && failed.load(Ordering::SeqCst))
+                || interrupted.load(Ordering::SeqCst)
+        };
+        let reports: Mutex<Vec<Option<ExecReport>>> = Mutex::new(vec![None; members.len()]);
+
+        thread::scope(|scope| {
+            for _ in 0..options.jobs.clamp(1, members.len().max(1)) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some((repo_name, path)) = members.get(index) else {
+                        break;
+                    };
+
+                    let started = Instant::now();
+                    let status = if cancelled() {
+                        ExecStatus::Skipped
+                    } else {
+                        run(options, path, &cancelled, |stream, line| {
+                            on_output(index, repo_name, stream, line)
+                        })
+                    };
+                    if !status.is_success() && status != ExecStatus::Skipped {
+                        failed.store(true, Ordering::SeqCst);
+                    }
+
+                    reports.lock().unwrap()[index] = Some(ExecReport {
+                        repo_name: repo_name.clone(),
+                        path: path.clone(),
+                        status,
+                        duration: started.elapsed(),
+                    });
+                });
+            }
+        });
+
+        Ok(reports
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// en: Worktrees of the workspace from the registry, or from its workspace file when it is
+    /// not registered, limited to `repos` and the repositories of `groups` when given
+    ///
+    /// ja: レジストリ（未登録の場合はworkspaceファイル）から取得したworkspaceのworktree
+    /// `repos` や `groups` 指定時はそのリポジトリのみに絞り込む
+    fn select_members(
+        &self,
+        name_or_path: &str,
+        current_dir: &Path,
+        options: &ExecOptions,
+    ) -> Result<Vec<(String, PathBuf)>, WxError> {
+        let repos = &options.repos;
+        let group_repos = self
+            .config_manager
+            .load()?
+            .group_repositories(&options.groups)?;
+
+        let members: Vec<(String, PathBuf)> = match self.registry.resolve(name_or_path, current_dir)
+        {
+            Ok(workspace) => workspace
+                .members
+                .into_iter()
+                .map(|member| (member.repo_name, member.path))
+                .collect(),
+            Err(WxError::WorkspaceNotFound(name)) => {
+                let workspace_dir = current_dir.join(name_or_path);
+                let workspace_file = self
+                    .workspace_file_manager
+                    .find(&workspace_dir)
+                    .ok_or(WxError::WorkspaceNotFound(name))?;
+
+                self.workspace_file_manager
+                    .read(&workspace_file)?
+                    .folders
+                    .into_iter()
+                    .map(|folder| {
+                        let path = workspace_dir.join(&folder.path);
+                        let repo_name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or(folder.path);
+                        (repo_name, path)
+                    })
+                    .collect()
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(unknown) = repos
+            .iter()
+            .find(|repo| !members.iter().any(|(name, _)| name == *repo))
+        {
+            return Err(WxError::RepositoryNotFound(unknown.clone()));
+        }
+
+        // repositories of a group that the workspace does not check out are skipped
+        Ok(members
+            .into_iter()
+            .filter(|(name, _)| {
+                (repos.is_empty() && options.groups.is_empty())
+                    || repos.contains(name)
+                    || group_repos.contains(name)
+            })
+            .collect())
+    }
+}
+
+/// en: Run the command in `dir`, streaming its output, and kill it once `cancelled` returns true
+///
+/// ja: `dir` でコマンドを実行して出力を逐次渡し、`cancelled` がtrueを返したら終了させる
+fn run<F>(options: &ExecOptions, dir: &Path, cancelled: &dyn Fn() -> bool, on_line: F) -> ExecStatus
+where
+    F: Fn(OutputStream, &str) + Sync,
+{
+    if !dir.exists() {
+        return ExecStatus::Missing;
+    }
+
+    let mut command = command(&options.command);
+    command
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // the command gets a process group of its own, so that cancelling it also stops
+    // the processes it started, which would otherwise keep the output pipes open
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => return ExecStatus::Error(e.to_string()),
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    thread::scope(|scope| {
+        if let Some(stdout) = stdout {
+            scope.spawn(|| stream_lines(stdout, |line| on_line(OutputStream::Stdout, line)));
+        }
+        if let Some(stderr) = stderr {
+            scope.spawn(|| stream_lines(stderr, |line| on_line(OutputStream::Stderr, line)));
+        }
+
+        wait(&mut child, cancelled)
+    })
+}
+
+fn wait(child: &mut Child, cancelled: &dyn Fn() -> bool) -> ExecStatus {
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return ExecStatus::Succeeded,
+            Ok(Some(status)) => return ExecStatus::Failed(status.code()),
+            Ok(None) if cancelled() => {
+                kill_process_group(child);
+                let _ = child.kill();
+                let _ = child.wait();
+                return ExecStatus::Cancelled;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return ExecStatus::Error(e.to_string()),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn kill_process_group(child: &Child) {
+    // SAFETY: kill(2) only sends a signal; the group id is the pid of the child, which has
+    // not been reaped yet and so cannot have been reused
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_child: &Child) {}
+
+/// en: While alive, Ctrl-C and SIGTERM only set the flag, so that the commands, which no longer
+/// share the terminal's process group, are killed with their processes
+/// A second Ctrl-C exits at once
+///
+/// ja: 生存中はCtrl-CとSIGTERMでフラグを立てるのみとし、端末のプロセスグループから外れた
+/// コマンドをその子プロセスごと終了させる。2回目のCtrl-Cでは即座に終了する
+struct InterruptGuard {
+    #[cfg(unix)]
+    ids: Vec<signal_hook::SigId>,
+}
+
+impl InterruptGuard {
+    #[cfg(unix)]
+    fn register(flag: &Arc<AtomicBool>) -> Self {
+        use signal_hook::{consts::TERM_SIGNALS, flag};
+
+        let mut ids = Vec::new();
+        for signal in TERM_SIGNALS {
+            ids.extend(flag::register_conditional_default(*signal, Arc::clone(flag)).ok());
+            ids.extend(flag::register(*signal, Arc::clone(flag)).ok());
+        }
+        Self { ids }
+    }
+
+    #[cfg(not(unix))]
+    fn register(_flag: &Arc<AtomicBool>) -> Self {
+        Self {}
+    }
+}
+
+#[cfg(unix)]
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        for id in self.ids.drain(..) {
+            signal_hook::low_level::unregister(id);
+        }
+    }
+}
+
+fn stream_lines<R: Read>(reader: R, on_line: impl Fn(&str)) {
+    for line in BufReader::new(reader).lines() {
+        match line {
+            Ok(line) => on_line(&line),
+            Err(_) => break,
+        }
+    }
+}
+
+/// en: A single argument is a shell command line, several arguments are a program and its arguments
+///
+/// ja: 引数が1つの場合はシェルのコマンドライン、複数の場合はプログラムとその引数として扱う
+fn command(args: &[String]) -> Command {
+    if let [command_line] = args {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        command.arg(command_line);
+        return command;
+    }
+
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        services::GroupService,
+        utils::test_helpers::{
+            create_test_config_file, create_test_repository, create_test_workspace_entry,
+            setup_test_dirs, test_create_workspace_file,
+        },
+    };
+
+    fn options(command: &[&str]) -> ExecOptions {
+        ExecOptions {
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            repos: Vec::new(),
+            groups: Vec::new(),
+            jobs: 4,
+            fail_fast: false,
+        }
+    }
+
+    /// Register a workspace with worktree directories for `repos`
+    fn setup_workspace(dir: &Path, base_dir: &Path, repos: &[&str]) -> PathBuf {
+        let workspace_dir = dir.join("work/feature");
+        let mut entry = create_test_workspace_entry("feature");
+        entry.members.clear();
+        for repo in repos {
+            fs::create_dir_all(workspace_dir.join(repo)).unwrap();
+            let mut member = create_test_workspace_entry("feature").members.remove(0);
+            member.repo_name = repo.to_string();
+            member.path = workspace_dir.join(repo);
+            entry.members.push(member);
+        }
+        entry.path = workspace_dir.canonicalize().unwrap();
+        WorkspaceRegistryService::with_base_dir(base_dir)
+            .register(entry)
+            .unwrap();
+        workspace_dir
+    }
+
+    #[test]
+    fn test_exec_streams_output_per_worktree() {
+        let (dir, base_dir) = setup_test_dirs();
+        setup_workspace(dir.path(), &base_dir, &["frontend", "backend"]);
+        let lines: Mutex<Vec<(String, OutputStream, String)>> = Mutex::new(Vec::new());
+
+        let reports = WorkspaceExecService::new(&base_dir)
+            .exec(
+                "feature",
+                dir.path(),
+                &options(&["basename \"$PWD\"; echo oops >&2"]),
+                |_, repo_name, stream, line| {
+                    lines
+                        .lock()
+                        .unwrap()
+                        .push((repo_name.to_string(), stream, line.to_string()))
+                },
+            )
+            .unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|report| report.status.is_success()));
+        let lines = lines.into_inner().unwrap();
+        assert!(lines.contains(&(
+            "frontend".to_string(),
+            OutputStream::Stdout,
+            "frontend".to_string()
+        )));
+        assert!(lines.contains(&(
+            "backend".to_string(),
+            OutputStream::Stderr,
+            "oops".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_exec_exit_codes_and_repo_filter() {
+        let (dir, base_dir) = setup_test_dirs();
+        setup_workspace(dir.path(), &base_dir, &["frontend", "backend", "docs"]);
+        let service = WorkspaceExecService::new(&base_dir);
+
+        let mut filtered = options(&["sh", "-c", "test \"$(basename \"$PWD\")\" = frontend"]);
+        filtered.repos = vec!["frontend".to_string(), "backend".to_string()];
+        let reports = service
+            .exec("feature", dir.path(), &filtered, |_, _, _, _| {})
+            .unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].status, ExecStatus::Succeeded);
+        assert_eq!(reports[1].status, ExecStatus::Failed(Some(1)));
+
+        filtered.repos = vec!["mobile".to_string()];
+        assert!(matches!(
+            service.exec("feature", dir.path(), &filtered, |_, _, _, _| {}),
+            Err(WxError::RepositoryNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_exec_group_filter() {
+        let (dir, base_dir) = setup_test_dirs();
+        create_test_config_file(
+            &base_dir,
+            ["frontend", "backend", "docs", "mobile"]
+                .into_iter()
+                .map(create_test_repository)
+                .collect(),
+        );
+        GroupService::with_base_dir(&base_dir)
+            .create("web", &["frontend".to_string(), "mobile".to_string()])
+            .unwrap();
+        setup_workspace(dir.path(), &base_dir, &["frontend", "backend", "docs"]);
+        let service = WorkspaceExecService::new(&base_dir);
+
+        // mobile is in the group but not checked out in the workspace
+        let mut filtered = options(&["true"]);
+        filtered.groups = vec!["web".to_string()];
+        filtered.repos = vec!["docs".to_string()];
+        let reports = service
+            .exec("feature", dir.path(), &filtered, |_, _, _, _| {})
+            .unwrap();
+        let repo_names: Vec<&str> = reports
+            .iter()
+            .map(|report| report.repo_name.as_str())
+            .collect();
+        assert_eq!(repo_names, vec!["frontend", "docs"]);
+
+        filtered.groups = vec!["api".to_string()];
+        assert!(matches!(
+            service.exec("feature", dir.path(), &filtered, |_, _, _, _| {}),
+            Err(WxError::GroupNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_exec_fail_fast() {
+        let (dir, base_dir) = setup_test_dirs();
+        setup_workspace(dir.path(), &base_dir, &["frontend", "backend", "docs"]);
+
+        let mut fail_fast = options(&["exit 3"]);
+        fail_fast.jobs = 1;
+        fail_fast.fail_fast = true;
+        let reports = WorkspaceExecService::new(&base_dir)
+            .exec("feature", dir.path(), &fail_fast, |_, _, _, _| {})
+            .unwrap();
+
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| report.status.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                ExecStatus::Failed(Some(3)),
+                ExecStatus::Skipped,
+                ExecStatus::Skipped
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_fail_fast_kills_running_commands() {
+        let (dir, base_dir) = setup_test_dirs();
+        setup_workspace(dir.path(), &base_dir, &["frontend", "backend"]);
+
+        // the shell waits for `sleep` and keeps its stdout open, as build tools do
+        let mut fail_fast = options(&[
+            r#"if [ "$(basename "$PWD")" = backend ]; then sleep 0.2; exit 3; fi; sleep 10; echo done"#,
+        ]);
+        fail_fast.jobs = 2;
+        fail_fast.fail_fast = true;
+        let started = Instant::now();
+        let reports = WorkspaceExecService::new(&base_dir)
+            .exec("feature", dir.path(), &fail_fast, |_, _, _, _| {})
+            .unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let status = |name: &str| {
+            reports
+                .iter()
+                .find(|report| report.repo_name == name)
+                .map(|report| report.status.clone())
+                .unwrap()
+        };
+        assert_eq!(status("backend"), ExecStatus::Failed(Some(3)));
+        assert_eq!(status("frontend"), ExecStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_exec_unregistered_workspace_file() {
+        let (dir, base_dir) = setup_test_dirs();
+        let workspace_dir = dir.path().join("legacy");
+        fs::create_dir_all(workspace_dir.join("frontend")).unwrap();
+        test_create_workspace_file(
+            &workspace_dir,
+            "legacy",
+            vec!["frontend".to_string(), "gone".to_string()],
+        );
+
+        let reports = WorkspaceExecService::new(&base_dir)
+            .exec("legacy", dir.path(), &options(&["true"]), |_, _, _, _| {})
+            .unwrap();
+
+        assert_eq!(reports[0].status, ExecStatus::Succeeded);
+        assert_eq!(reports[1].repo_name, "gone");
+        assert_eq!(reports[1].status, ExecStatus::Missing);
+    }
+}
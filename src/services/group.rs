@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use crate::{
+    infrastructure::config::ConfigManager,
+    models::{RepositoryGroup, WxError},
+};
+
+/// en: Service for the repository groups persisted in config.json
+///
+/// ja: config.jsonに保存されるリポジトリグループを扱うサービス
+pub struct GroupService {
+    config_manager: ConfigManager,
+}
+
+impl GroupService {
+    pub fn new() -> Result<Self, WxError> {
+        Ok(Self {
+            config_manager: ConfigManager::new()?,
+        })
+    }
+
+    pub fn with_base_dir(base_dir: &Path) -> Self {
+        Self {
+            config_manager: ConfigManager::with_base_dir(base_dir),
+        }
+    }
+
+    /// en: Create a group of registered repositories
+    ///
+    /// ja: 登録済みリポジトリのグループを作成する
+    pub fn create(&self, name: &str, repos: &[String]) -> Result<RepositoryGroup, WxError> {
+        let mut config = self.config_manager.load()?;
+        let group = RepositoryGroup::new(name.to_string(), repos.to_vec());
+        config.add_group(group.clone())?;
+        self.config_manager.save(&config)?;
+        Ok(group)
+    }
+
+    /// en: Returns all groups
+    ///
+    /// ja: 全てのグループを返却する
+    pub fn list(&self) -> Result<Vec<RepositoryGroup>, WxError> {
+        Ok(self.config_manager.load()?.groups)
+    }
+
+    /// en: Names of the repositories in any of the given groups, without duplicates
+    ///
+    /// ja: 指定したいずれかのグループに含まれるリポジトリ名（重複無し）
+    pub fn repositories(&self, names: &[String]) -> Result<Vec<String>, WxError> {
+        self.config_manager.load()?.group_repositories(names)
+    }
+
+    /// en: Remove a group by name; its repositories stay registered
+    ///
+    /// ja: 名前を指定してグループを削除する（リポジトリの登録はそのまま残る）
+    pub fn remove(&self, name: &str) -> Result<RepositoryGroup, WxError> {
+        let mut config = self.config_manager.load()?;
+        let group = config.remove_group(name)?;
+        self.config_manager.save(&config)?;
+        Ok(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_helpers::{
+        create_test_config_file, create_test_repository, setup_test_dirs,
+    };
+
+    #[test]
+    fn test_group_service() {
+        let (_dir, base_dir) = setup_test_dirs();
+        create_test_config_file(
+            &base_dir,
+            vec![
+                create_test_repository("frontend"),
+                create_test_repository("backend"),
+            ],
+        );
+        let group_service = GroupService::with_base_dir(&base_dir);
+
+        let group = group_service
+            .create("web", &["frontend".to_string(), "backend".to_string()])
+            .unwrap();
+        assert_eq!(group.repositories, vec!["frontend", "backend"]);
+        assert!(matches!(
+            group_service.create("api", &["mobile".to_string()]),
+            Err(WxError::RepositoryNotFound(_))
+        ));
+        assert_eq!(group_service.list().unwrap(), vec![group.clone()]);
+        assert_eq!(
+            group_service.repositories(&["web".to_string()]).unwrap(),
+            vec!["frontend", "backend"]
+        );
+
+        assert_eq!(group_service.remove("web").unwrap(), group);
+        assert!(group_service.list().unwrap().is_empty());
+        assert_eq!(
+            ConfigManager::with_base_dir(&base_dir)
+                .load()
+                .unwrap()
+                .repositories
+                .len(),
+            2
+        );
+    }
+}
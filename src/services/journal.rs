@@ -0,0 +1,164 @@
+use std::{fs, path::PathBuf};
+
+use crate::{infrastructure::git::WorktreeManager, models::WxError};
+
+/// en: A change made while building a workspace that can be undone
+///
+/// ja: workspace構築中に行われた、取り消し可能な変更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalEntry {
+    Dir(PathBuf),
+    File(PathBuf),
+    /// A file that was overwritten, with its original contents
+    FileContents {
+        path: PathBuf,
+        original: String,
+    },
+    Branch {
+        bare_repo_path: PathBuf,
+        branch: String,
+    },
+    Worktree {
+        bare_repo_path: PathBuf,
+        name: String,
+    },
+}
+
+/// en: Records changes in order so that a failed operation can be undone in reverse
+///
+/// ja: 変更を順に記録し、失敗した操作を逆順に取り消せるようにする
+#[derive(Debug, Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// en: Record a change that has just been made
+    ///
+    /// ja: 直前に行った変更を記録する
+    pub fn record(&mut self, entry: JournalEntry) {
+        self.entries.push(entry);
+    }
+
+    /// en: Undo every recorded change in reverse order after `error` occurred
+    /// Returns `error` itself, or `RollbackFailed` if any change could not be undone
+    ///
+    /// ja: `error` 発生後、記録した変更を逆順に全て取り消す
+    /// `error` をそのまま返却し、取り消せない変更があった場合は `RollbackFailed` を返却する
+    pub fn rollback<W: WorktreeManager>(self, worktree_manager: &W, error: WxError) -> WxError {
+        let mut rollback_errors: Vec<String> = Vec::new();
+
+        for entry in self.entries.into_iter().rev() {
+            let result = match &entry {
+                JournalEntry::Dir(path) => fs::remove_dir_all(path).map_err(WxError::from),
+                JournalEntry::File(path) => fs::remove_file(path).map_err(WxError::from),
+                JournalEntry::FileContents { path, original } => {
+                    fs::write(path, original).map_err(WxError::from)
+                }
+                JournalEntry::Branch {
+                    bare_repo_path,
+                    branch,
+                } => worktree_manager.delete_branch(bare_repo_path, branch),
+                JournalEntry::Worktree {
+                    bare_repo_path,
+                    name,
+                } => worktree_manager.remove_worktree(bare_repo_path, name),
+            };
+
+            if let Err(e) = result {
+                rollback_errors.push(format!("{entry:?}: {e}"));
+            }
+        }
+
+        if rollback_errors.is_empty() {
+            error
+        } else {
+            WxError::RollbackFailed {
+                original_error: error.to_string(),
+                rollback_error: rollback_errors.join("; "),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::{infrastructure::git::MockWorktreeManager, utils::test_helpers::setup_test_dirs};
+
+    #[test]
+    fn test_rollback_in_reverse_order() {
+        let (dir, _base_dir) = setup_test_dirs();
+        let workspace_dir = dir.path().join("work");
+        fs::create_dir_all(&workspace_dir).unwrap();
+        let file = workspace_dir.join("wx.code-workspace");
+        fs::write(&file, "{}").unwrap();
+
+        let mut sequence = mockall::Sequence::new();
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        mock_worktree_manager
+            .expect_remove_worktree()
+            .with(eq(Path::new("/repo.git")), eq("frontend"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(()));
+        mock_worktree_manager
+            .expect_delete_branch()
+            .with(eq(Path::new("/repo.git")), eq("feature"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(()));
+
+        let mut journal = Journal::new();
+        journal.record(JournalEntry::Dir(workspace_dir.clone()));
+        journal.record(JournalEntry::Branch {
+            bare_repo_path: PathBuf::from("/repo.git"),
+            branch: "feature".to_string(),
+        });
+        journal.record(JournalEntry::Worktree {
+            bare_repo_path: PathBuf::from("/repo.git"),
+            name: "frontend".to_string(),
+        });
+        journal.record(JournalEntry::File(file));
+
+        let error = journal.rollback(&mock_worktree_manager, WxError::General("boom".to_string()));
+
+        assert!(matches!(error, WxError::General(_)));
+        assert!(!workspace_dir.exists());
+    }
+
+    #[test]
+    fn test_rollback_failure() {
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        mock_worktree_manager
+            .expect_remove_worktree()
+            .returning(|_, _| Err(WxError::General("locked".to_string())));
+
+        let mut journal = Journal::new();
+        journal.record(JournalEntry::Worktree {
+            bare_repo_path: PathBuf::from("/repo.git"),
+            name: "frontend".to_string(),
+        });
+
+        let error = journal.rollback(&mock_worktree_manager, WxError::General("boom".to_string()));
+
+        match error {
+            WxError::RollbackFailed {
+                original_error,
+                rollback_error,
+            } => {
+                assert_eq!(original_error, "boom");
+                assert!(rollback_error.contains("locked"));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,601 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    infrastructure::{filesystem::WorkspaceFileManager, git::WorktreeManager},
+    models::{
+        manifest::{ApplyResult, WorkspaceManifest},
+        workspace::{WorkspaceEntry, WorkspaceMember, WorktreeSelection},
+        WxError,
+    },
+    services::{RepositoryService, WorkspaceGenerationService, WorkspaceRegistryService},
+    utils::normalize,
+};
+
+/// en: Service that creates or reconciles workspaces from `wx.toml` manifests
+///
+/// ja: `wx.toml` マニフェストからworkspaceを作成・同期するサービス
+pub struct WorkspaceManifestService<W: WorktreeManager> {
+    workspace_generation_service: WorkspaceGenerationService<W>,
+    repository_service: RepositoryService,
+    registry: WorkspaceRegistryService,
+    workspace_file_manager: WorkspaceFileManager,
+    wx_home: PathBuf,
+}
+
+impl<W: WorktreeManager> WorkspaceManifestService<W> {
+    pub fn new(worktree_manager: W, wx_home: PathBuf) -> Result<Self, WxError> {
+        Ok(Self {
+            repository_service: RepositoryService::with_base_dir(&wx_home),
+            registry: WorkspaceRegistryService::with_base_dir(&wx_home),
+            workspace_file_manager: WorkspaceFileManager,
+            workspace_generation_service: WorkspaceGenerationService::new(
+                worktree_manager,
+                wx_home.clone(),
+            )?,
+            wx_home,
+        })
+    }
+
+    /// en: Apply a manifest idempotently
+    /// Creates the workspace in `base_dir` when it is not registered yet, otherwise adds missing
+    /// worktrees and reports drift without removing or changing anything else
+    /// With `create_only`, an already registered workspace is an error
+    /// Repositories registered from manifest URLs are unregistered again when applying fails
+    ///
+    /// ja: マニフェストを冪等に適用する
+    /// 未登録の場合は `base_dir` にworkspaceを作成し、登録済みの場合は不足しているworktreeを追加して
+    /// 差分を報告する（それ以外の削除や変更は行わない）
+    /// `create_only` 指定時は、登録済みのworkspaceをエラーとする
+    /// マニフェストのURLから登録したリポジトリは、適用に失敗した場合に登録を解除する
+    pub fn apply(
+        mut self,
+        manifest: &WorkspaceManifest,
+        base_dir: &Path,
+        create_only: bool,
+    ) -> Result<ApplyResult, WxError> {
+        // the name may have been overridden after the manifest file was read
+        manifest.validate()?;
+
+        // checked before any repository is cloned, so that nothing is left behind
+        let existing = self
+            .registry
+            .list()?
+            .into_iter()
+            .find(|entry| entry.name == manifest.name && entry.path.exists());
+        match &existing {
+            Some(_) if create_only => {
+                return Err(WxError::WorkspaceAlreadyRegistered(manifest.name.clone()))
+            }
+            None if base_dir.join(&manifest.name).exists() => {
+                return Err(WxError::General(format!(
+                    "Workspace directory '{}' already exists",
+                    base_dir.join(&manifest.name).display()
+                )))
+            }
+            _ => {}
+        }
+
+        let registered_repositories = self.register_missing_repositories(manifest)?;
+        let drift = self.registration_drift(manifest)?;
+
+        let wx_home = self.wx_home.clone();
+        let applied = match existing {
+            Some(workspace) => self.reconcile(manifest, &workspace),
+            None => self.create(manifest, base_dir),
+        };
+        let mut result = match applied {
+            Ok(result) => result,
+            Err(e) => return Err(unregister_all(&wx_home, &registered_repositories, e)),
+        };
+        result.registered_repositories = registered_repositories;
+        result.drift.splice(0..0, drift);
+
+        Ok(result)
+    }
+
+    /// en: Register repositories that are missing from config.json using their manifest URL
+    ///
+    /// ja: config.jsonに存在しないリポジトリをマニフェストのURLで登録する
+    fn register_missing_repositories(
+        &mut self,
+        manifest: &WorkspaceManifest,
+    ) -> Result<Vec<String>, WxError> {
+        let repos = self.repository_service.list()?;
+        let missing = manifest
+            .repos
+            .iter()
+            .filter(|manifest_repo| !repos.iter().any(|repo| repo.name == manifest_repo.name))
+            .map(|manifest_repo| {
+                manifest_repo
+                    .url
+                    .as_deref()
+                    .map(|url| (url, manifest_repo))
+                    .ok_or_else(|| WxError::RepositoryNotFound(manifest_repo.name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut registered: Vec<String> = Vec::new();
+
+        for (url, manifest_repo) in missing {
+            match self
+                .repository_service
+                .register(url, Some(&manifest_repo.name))
+            {
+                Ok(repository) => registered.push(repository.name),
+                Err(e) => return Err(unregister_all(&self.wx_home, &registered, e)),
+            }
+        }
+
+        Ok(registered)
+    }
+
+    /// en: Describe manifest repositories whose registration was made from another URL
+    ///
+    /// ja: 別のURLから登録されているマニフェストのリポジトリを差分として記述する
+    fn registration_drift(&self, manifest: &WorkspaceManifest) -> Result<Vec<String>, WxError> {
+        let repos = self.repository_service.list()?;
+
+        Ok(manifest
+            .repos
+            .iter()
+            .filter_map(|manifest_repo| {
+                let url = manifest_repo.url.as_deref()?;
+                let repo = repos.iter().find(|repo| repo.name == manifest_repo.name)?;
+                (repo.remote != url).then(|| {
+                    format!(
+                        "{}: registered from {}, manifest wants {url}",
+                        repo.name, repo.remote
+                    )
+                })
+            })
+            .collect())
+    }
+
+    fn create(self, manifest: &WorkspaceManifest, base_dir: &Path) -> Result<ApplyResult, WxError> {
+        let workspace_dir = base_dir.join(&manifest.name);
+        let selection: Vec<WorktreeSelection> = manifest
+            .repos
+            .iter()
+            .map(|repo| repo.to_selection())
+            .collect();
+
+        let Self {
+            workspace_generation_service,
+            registry,
+            ..
+        } = self;
+        workspace_generation_service
+            .with_template(manifest.template.clone())
+            .with_settings(manifest.settings.clone())
+            .generate(&workspace_dir, selection, &manifest.name)?;
+
+        let workspace = registry.resolve(&manifest.name, base_dir)?;
+
+        Ok(ApplyResult {
+            workspace_dir: workspace.path,
+            created: true,
+            added: workspace.members,
+            ..ApplyResult::default()
+        })
+    }
+
+    /// en: Add worktrees that the manifest lists but the workspace lacks, and describe
+    /// every other difference as drift
+    ///
+    /// ja: マニフェストにあってworkspaceに無いworktreeを追加し、それ以外の差分をdriftとして報告する
+    fn reconcile(
+        &self,
+        manifest: &WorkspaceManifest,
+        workspace: &WorkspaceEntry,
+    ) -> Result<ApplyResult, WxError> {
+        let mut drift: Vec<String> = Vec::new();
+        let mut missing: Vec<WorktreeSelection> = Vec::new();
+
+        for manifest_repo in &manifest.repos {
+            match find_member(workspace, &manifest_repo.name) {
+                Some(member) => {
+                    if member.branch != manifest_repo.branch {
+                        drift.push(format!(
+                            "{}: on branch '{}', manifest wants '{}'",
+                            member.repo_name, member.branch, manifest_repo.branch
+                        ));
+                    }
+                    if let Some(dir) = &manifest_repo.dir {
+                        let manifest_path = workspace.path.join(dir);
+                        if normalize(&member.path) != normalize(&manifest_path) {
+                            drift.push(format!(
+                                "{}: worktree at {}, manifest wants {}",
+                                member.repo_name,
+                                member.path.display(),
+                                manifest_path.display()
+                            ));
+                        }
+                    }
+                    if !member.path.exists() {
+                        drift.push(format!(
+                            "{}: worktree {} is missing",
+                            member.repo_name,
+                            member.path.display()
+                        ));
+                    }
+                }
+                None => missing.push(manifest_repo.to_selection()),
+            }
+        }
+
+        for member in &workspace.members {
+            if !manifest
+                .repos
+                .iter()
+                .any(|repo| repo.name == member.repo_name)
+            {
+                drift.push(format!(
+                    "{}: not in the manifest ({})",
+                    member.repo_name,
+                    member.path.display()
+                ));
+            }
+        }
+
+        if workspace.workspace_file.exists() {
+            let workspace_file = self
+                .workspace_file_manager
+                .read(&workspace.workspace_file)?;
+            for (key, value) in &manifest.settings.values {
+                match workspace_file.settings.values.get(key) {
+                    Some(current) if current == value => {}
+                    Some(current) => {
+                        drift.push(format!("settings.{key}: {current}, manifest wants {value}"))
+                    }
+                    None => drift.push(format!("settings.{key}: not set, manifest wants {value}")),
+                }
+            }
+        } else {
+            drift.push(format!(
+                "workspace file {} is missing",
+                workspace.workspace_file.display()
+            ));
+        }
+
+        let added = if missing.is_empty() {
+            Vec::new()
+        } else {
+            self.workspace_generation_service
+                .add_worktrees(workspace, missing)?
+        };
+
+        Ok(ApplyResult {
+            workspace_dir: workspace.path.clone(),
+            created: false,
+            added,
+            drift,
+            ..ApplyResult::default()
+        })
+    }
+}
+
+/// en: Unregister the repositories registered for a manifest after `error` occurred
+/// Returns `error` itself, or `RollbackFailed` if any of them could not be unregistered
+///
+/// ja: `error` 発生後、マニフェストのために登録したリポジトリの登録を解除する
+/// `error` をそのまま返却し、解除できないリポジトリがあった場合は `RollbackFailed` を返却する
+fn unregister_all(wx_home: &Path, repo_names: &[String], error: WxError) -> WxError {
+    let mut repository_service = RepositoryService::with_base_dir(wx_home);
+    let rollback_errors: Vec<String> = repo_names
+        .iter()
+        .filter_map(|name| {
+            repository_service
+                .unregister(name, true)
+                .err()
+                .map(|e| format!("{name}: {e}"))
+        })
+        .collect();
+
+    if rollback_errors.is_empty() {
+        error
+    } else {
+        WxError::RollbackFailed {
+            original_error: error.to_string(),
+            rollback_error: rollback_errors.join("; "),
+        }
+    }
+}
+
+fn find_member<'a>(workspace: &'a WorkspaceEntry, repo_name: &str) -> Option<&'a WorkspaceMember> {
+    workspace
+        .members
+        .iter()
+        .find(|member| member.repo_name == repo_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use git2::Repository;
+
+    use crate::{
+        infrastructure::git::DefaultWorktreeManager,
+        models::manifest::ManifestRepository,
+        utils::test_helpers::{
+            add_test_remote_branch, create_test_bare_repo, create_test_config_file,
+            create_test_git_repo, create_test_repository_in, setup_test_dirs,
+        },
+    };
+
+    use super::*;
+
+    fn manifest_repo(name: &str, branch: &str) -> ManifestRepository {
+        ManifestRepository {
+            name: name.to_string(),
+            url: None,
+            branch: branch.to_string(),
+            base: None,
+            dir: None,
+        }
+    }
+
+    fn setup_repos(base_dir: &Path, repos: &[(&str, &str)]) {
+        create_test_config_file(
+            base_dir,
+            repos
+                .iter()
+                .map(|(name, _)| create_test_repository_in(base_dir, name))
+                .collect(),
+        );
+        for (name, branch) in repos {
+            let repo_path = create_test_bare_repo(base_dir, name);
+            add_test_remote_branch(&Repository::open_bare(&repo_path).unwrap(), branch);
+        }
+    }
+
+    fn apply(
+        base_dir: &Path,
+        manifest: &WorkspaceManifest,
+        work_dir: &Path,
+    ) -> Result<ApplyResult, WxError> {
+        WorkspaceManifestService::new(DefaultWorktreeManager, base_dir.to_path_buf())
+            .unwrap()
+            .apply(manifest, work_dir, false)
+    }
+
+    #[test]
+    fn test_apply_creates_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+        let work_dir = dir.path().join("work");
+        fs::create_dir_all(&work_dir).unwrap();
+        setup_repos(
+            &base_dir,
+            &[("frontend", "feature"), ("backend", "feature")],
+        );
+        let mut manifest = WorkspaceManifest {
+            name: "auth".to_string(),
+            repos: vec![manifest_repo("frontend", "feature")],
+            template: None,
+            settings: Default::default(),
+        };
+        manifest.repos[0].dir = Some("web".to_string());
+        manifest
+            .settings
+            .values
+            .insert("editor.formatOnSave".to_string(), true.into());
+
+        let result = apply(&base_dir, &manifest, &work_dir).unwrap();
+
+        assert!(result.created);
+        assert_eq!(result.added.len(), 1);
+        assert!(work_dir.join("auth/web").exists());
+        let workspace_file = WorkspaceFileManager
+            .read(&work_dir.join("auth/auth.code-workspace"))
+            .unwrap();
+        assert_eq!(workspace_file.settings, manifest.settings);
+    }
+
+    #[test]
+    fn test_apply_reconciles_existing_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+        let work_dir = dir.path().join("work");
+        fs::create_dir_all(&work_dir).unwrap();
+        setup_repos(
+            &base_dir,
+            &[("frontend", "feature"), ("backend", "feature")],
+        );
+        let mut manifest = WorkspaceManifest {
+            name: "auth".to_string(),
+            repos: vec![manifest_repo("frontend", "feature")],
+            template: None,
+            settings: Default::default(),
+        };
+        apply(&base_dir, &manifest, &work_dir).unwrap();
+
+        // applying the same manifest again changes nothing
+        let result = apply(&base_dir, &manifest, &work_dir).unwrap();
+        assert!(!result.created);
+        assert!(result.added.is_empty());
+        assert!(result.drift.is_empty());
+
+        manifest.repos[0].branch = "other".to_string();
+        manifest.repos.push(manifest_repo("backend", "feature"));
+        manifest
+            .settings
+            .values
+            .insert("editor.tabSize".to_string(), 2.into());
+
+        let result = apply(&base_dir, &manifest, &work_dir).unwrap();
+
+        assert_eq!(result.added.len(), 1);
+        assert_eq!(result.added[0].repo_name, "backend");
+        assert!(work_dir.join("auth/backend").exists());
+        assert_eq!(result.drift.len(), 2);
+        assert!(result.drift[0].contains("frontend"));
+        assert!(result.drift[1].contains("editor.tabSize"));
+        // the mismatching worktree is left untouched
+        assert!(work_dir.join("auth/frontend").exists());
+
+        let create_only = WorkspaceManifestService::new(DefaultWorktreeManager, base_dir.clone())
+            .unwrap()
+            .apply(&manifest, &work_dir, true);
+        assert!(matches!(
+            create_only,
+            Err(WxError::WorkspaceAlreadyRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_unknown_repository() {
+        let (dir, base_dir) = setup_test_dirs();
+        setup_repos(&base_dir, &[("frontend", "feature")]);
+        let manifest = WorkspaceManifest {
+            name: "auth".to_string(),
+            repos: vec![manifest_repo("mobile", "feature")],
+            template: None,
+            settings: Default::default(),
+        };
+
+        assert!(matches!(
+            apply(&base_dir, &manifest, dir.path()),
+            Err(WxError::RepositoryNotFound(_))
+        ));
+
+        assert!(!dir.path().join("auth").exists());
+    }
+
+    /// Create a repository with one commit to register from its path
+    fn source_repo(dir: &Path, name: &str) -> String {
+        let path = create_test_git_repo(dir, name);
+        let repo = Repository::open(&path).unwrap();
+        let signature = git2::Signature::now("wx", "wx@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Init", &tree, &[])
+            .unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_apply_reports_dir_and_remote_drift() {
+        let (dir, base_dir) = setup_test_dirs();
+        let work_dir = dir.path().join("work");
+        fs::create_dir_all(&work_dir).unwrap();
+        setup_repos(&base_dir, &[("frontend", "feature")]);
+        let mut manifest = WorkspaceManifest {
+            name: "auth".to_string(),
+            repos: vec![manifest_repo("frontend", "feature")],
+            template: None,
+            settings: Default::default(),
+        };
+        manifest.repos[0].dir = Some("web".to_string());
+        apply(&base_dir, &manifest, &work_dir).unwrap();
+
+        manifest.repos[0].dir = Some("app".to_string());
+        let result = apply(&base_dir, &manifest, &work_dir).unwrap();
+        assert_eq!(result.drift.len(), 1);
+        assert!(result.drift[0].contains("frontend: worktree at"));
+        assert!(result.drift[0].contains("app"));
+        assert!(!work_dir.join("auth/app").exists());
+
+        manifest.repos[0].dir = Some("web".to_string());
+        manifest.repos[0].url = Some("git@github.com:other/frontend.git".to_string());
+        let result = apply(&base_dir, &manifest, &work_dir).unwrap();
+        assert_eq!(
+            result.drift,
+            vec![
+                "frontend: registered from git@github.com:org/frontend.git, manifest wants git@github.com:other/frontend.git"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_failure_unregisters_new_repositories() {
+        let (dir, base_dir) = setup_test_dirs();
+        let work_dir = dir.path().join("work");
+        fs::create_dir_all(&work_dir).unwrap();
+        setup_repos(&base_dir, &[("frontend", "feature")]);
+        let manifest = WorkspaceManifest {
+            name: "auth".to_string(),
+            repos: vec![manifest_repo("frontend", "feature")],
+            template: None,
+            settings: Default::default(),
+        };
+        apply(&base_dir, &manifest, &work_dir).unwrap();
+
+        let mut mobile = manifest_repo("mobile", "feature");
+        mobile.url = Some(source_repo(dir.path(), "ios"));
+        let repositories = || RepositoryService::with_base_dir(&base_dir).list().unwrap();
+        let repos_dir = base_dir.join("repos");
+
+        // the workspace exists, so nothing is cloned for `wx new --from`
+        let mut existing = manifest.clone();
+        existing.repos.push(mobile.clone());
+        let create_only = WorkspaceManifestService::new(DefaultWorktreeManager, base_dir.clone())
+            .unwrap()
+            .apply(&existing, &work_dir, true);
+        assert!(matches!(
+            create_only,
+            Err(WxError::WorkspaceAlreadyRegistered(_))
+        ));
+        assert_eq!(repositories().len(), 1);
+        assert!(!repos_dir.exists());
+
+        // creating fails after the repository was registered
+        let mut web = manifest_repo("frontend", "feature");
+        web.dir = Some("web".to_string());
+        mobile.dir = Some("web".to_string());
+        let failing = WorkspaceManifest {
+            name: "mobile".to_string(),
+            repos: vec![web, mobile.clone()],
+            template: None,
+            settings: Default::default(),
+        };
+        assert!(matches!(
+            apply(&base_dir, &failing, &work_dir),
+            Err(WxError::DuplicateWorktreeDirectory(_))
+        ));
+        assert_eq!(repositories().len(), 1);
+        assert!(fs::read_dir(&repos_dir).unwrap().next().is_none());
+
+        // a later repository that cannot be cloned
+        let mut desktop = manifest_repo("desktop", "feature");
+        desktop.url = Some(dir.path().join("missing").to_str().unwrap().to_string());
+        let failing = WorkspaceManifest {
+            name: "mobile".to_string(),
+            repos: vec![mobile.clone(), desktop],
+            template: None,
+            settings: Default::default(),
+        };
+        assert!(apply(&base_dir, &failing, &work_dir).is_err());
+        assert_eq!(repositories().len(), 1);
+        assert!(fs::read_dir(&repos_dir).unwrap().next().is_none());
+
+        // a later repository without a URL is found before anything is cloned
+        let failing = WorkspaceManifest {
+            name: "mobile".to_string(),
+            repos: vec![mobile, manifest_repo("desktop", "feature")],
+            template: None,
+            settings: Default::default(),
+        };
+        assert!(matches!(
+            apply(&base_dir, &failing, &work_dir),
+            Err(WxError::RepositoryNotFound(_))
+        ));
+        assert_eq!(repositories().len(), 1);
+        assert!(fs::read_dir(&repos_dir).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_apply_registers_repository_under_manifest_name() {
+        let (dir, base_dir) = setup_test_dirs();
+        let mut mobile = manifest_repo("mobile", "feature");
+        mobile.url = Some(source_repo(dir.path(), "ios"));
+        let manifest = WorkspaceManifest {
+            name: "auth".to_string(),
+            repos: vec![mobile],
+            template: None,
+            settings: Default::default(),
+        };
+
+        let result = apply(&base_dir, &manifest, &dir.path().join("work")).unwrap();
+        assert_eq!(result.registered_repositories, vec!["mobile".to_string()]);
+        assert_eq!(result.added[0].repo_name, "mobile");
+    }
+}
@@ -1,5 +1,22 @@
+mod clean;
+mod exec;
+mod group;
+mod journal;
+mod manifest;
+mod open;
+mod registry;
 mod repository;
+mod status;
+mod sync;
 mod workspace;
 
+pub use clean::WorkspaceCleanService;
+pub use exec::WorkspaceExecService;
+pub use group::GroupService;
+pub use manifest::WorkspaceManifestService;
+pub use open::WorkspaceOpenService;
+pub use registry::WorkspaceRegistryService;
 pub use repository::RepositoryService;
+pub use status::WorkspaceStatusService;
+pub use sync::WorkspaceSyncService;
 pub use workspace::WorkspaceGenerationService;
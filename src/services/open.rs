@@ -0,0 +1,212 @@
+use std::{io::ErrorKind, path::Path, process::Command};
+
+use crate::{
+    infrastructure::{config::ConfigManager, filesystem::WorkspaceFileManager},
+    models::{
+        editor::{EditorCommand, OpenTarget},
+        workspace::WorkspaceFormat,
+        WxError,
+    },
+    services::WorkspaceRegistryService,
+};
+
+/// en: Service that launches an editor on a workspace
+///
+/// ja: workspaceをエディタで開くサービス
+pub struct WorkspaceOpenService {
+    registry: WorkspaceRegistryService,
+    config_manager: ConfigManager,
+    workspace_file_manager: WorkspaceFileManager,
+}
+
+impl WorkspaceOpenService {
+    pub fn new(wx_home: &Path) -> Self {
+        Self {
+            registry: WorkspaceRegistryService::with_base_dir(wx_home),
+            config_manager: ConfigManager::with_base_dir(wx_home),
+            workspace_file_manager: WorkspaceFileManager,
+        }
+    }
+
+    /// en: Open the named workspace, or the one containing the current directory, and wait for the editor command
+    /// `editor` overrides the `editor` template of config.json, which defaults to the command of the workspace's format
+    ///
+    /// ja: 指定したworkspace（省略時はカレントディレクトリを含むworkspace）を開き、エディタのコマンドの終了を待つ
+    /// `editor` はconfig.jsonの `editor` テンプレートを上書きする（既定値はworkspaceの形式のコマンド）
+    pub fn open(
+        &self,
+        name_or_path: Option<&str>,
+        current_dir: &Path,
+        editor: Option<&str>,
+    ) -> Result<OpenTarget, WxError> {
+        let target = self.resolve(name_or_path, current_dir)?;
+        let command = self.command(&target, editor)?;
+
+        let status = Command::new(&command.program)
+            .args(&command.args)
+            .status()
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => WxError::EditorNotFound(command.program.clone()),
+                _ => WxError::IoError(e),
+            })?;
+        if !status.success() {
+            return Err(WxError::General(format!(
+                "Editor '{}' exited with {status}",
+                command.program
+            )));
+        }
+
+        Ok(target)
+    }
+
+    /// en: Build the editor command for the target
+    ///
+    /// ja: 対象に対するエディタのコマンドを組み立てる
+    pub fn command(
+        &self,
+        target: &OpenTarget,
+        editor: Option<&str>,
+    ) -> Result<EditorCommand, WxError> {
+        let configured = self.config_manager.load()?.editor;
+        let template = editor
+            .or(configured.as_deref())
+            .unwrap_or_else(|| target.format.default_editor());
+
+        EditorCommand::from_template(template, target)
+    }
+
+    /// en: Resolve the workspace through the registry, falling back to the workspace file of an unregistered directory
+    ///
+    /// ja: レジストリからworkspaceを解決する（未登録のディレクトリはworkspaceファイルから解決する）
+    fn resolve(
+        &self,
+        name_or_path: Option<&str>,
+        current_dir: &Path,
+    ) -> Result<OpenTarget, WxError> {
+        let entry = match name_or_path {
+            Some(name_or_path) => self.registry.resolve(name_or_path, current_dir),
+            None => self.registry.resolve_containing(current_dir),
+        };
+
+        match entry {
+            Ok(entry) => Ok(OpenTarget {
+                format: WorkspaceFormat::or_default(&entry.formats)[0],
+                name: entry.name,
+                dir: entry.path,
+                workspace_file: entry.workspace_file,
+            }),
+            Err(WxError::WorkspaceNotFound(name)) => {
+                let dir = match name_or_path {
+                    Some(name_or_path) => current_dir.join(name_or_path),
+                    None => current_dir.to_path_buf(),
+                };
+                let workspace_file = self
+                    .workspace_file_manager
+                    .find(&dir)
+                    .ok_or(WxError::WorkspaceNotFound(name))?;
+
+                Ok(OpenTarget {
+                    name: workspace_file
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    dir,
+                    workspace_file,
+                    format: WorkspaceFormat::VsCode,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        models::Config,
+        utils::test_helpers::{
+            create_test_workspace_entry, setup_test_dirs, test_create_workspace_file,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_command_prefers_override_then_config() {
+        let (dir, base_dir) = setup_test_dirs();
+        let service = WorkspaceOpenService::new(&base_dir);
+        let target = OpenTarget {
+            name: "feat".to_string(),
+            dir: dir.path().join("feat"),
+            workspace_file: dir.path().join("feat"),
+            format: WorkspaceFormat::Zed,
+        };
+
+        assert_eq!(service.command(&target, None).unwrap().program, "zed");
+
+        let mut config = Config::new();
+        config.editor = Some("cursor {dir}".to_string());
+        ConfigManager::with_base_dir(&base_dir)
+            .save(&config)
+            .unwrap();
+        assert_eq!(service.command(&target, None).unwrap().program, "cursor");
+        assert_eq!(
+            service.command(&target, Some("vim")).unwrap().program,
+            "vim"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_runs_editor() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (dir, base_dir) = setup_test_dirs();
+        let editor = dir.path().join("stub-editor");
+        let output = dir.path().join("opened.txt");
+        fs::write(
+            &editor,
+            format!("#!/bin/sh\necho \"$@\" > '{}'\n", output.display()),
+        )
+        .unwrap();
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let workspace = create_test_workspace_entry("feat");
+        let mut config = Config::new();
+        config.workspaces.push(workspace.clone());
+        ConfigManager::with_base_dir(&base_dir)
+            .save(&config)
+            .unwrap();
+        let service = WorkspaceOpenService::new(&base_dir);
+
+        let template = format!("{} {{workspace_file}}", editor.display());
+        let target = service
+            .open(Some("feat"), dir.path(), Some(&template))
+            .unwrap();
+        assert_eq!(target.workspace_file, workspace.workspace_file);
+        assert_eq!(
+            fs::read_to_string(&output).unwrap().trim(),
+            workspace.workspace_file.to_string_lossy()
+        );
+
+        // an unregistered directory is opened through its workspace file
+        let unregistered = dir.path().join("other");
+        fs::create_dir_all(&unregistered).unwrap();
+        test_create_workspace_file(&unregistered, "other", vec![]);
+        let target = service
+            .open(Some("other"), dir.path(), Some(&template))
+            .unwrap();
+        assert_eq!(target.name, "other");
+
+        assert!(matches!(
+            service.open(Some("missing"), dir.path(), Some(&template)),
+            Err(WxError::WorkspaceNotFound(_))
+        ));
+        assert!(matches!(
+            service.open(Some("feat"), dir.path(), Some("wx-no-such-editor")),
+            Err(WxError::EditorNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    infrastructure::config::ConfigManager,
+    models::{workspace::WorkspaceEntry, WxError},
+};
+
+/// en: Service for the workspace registry persisted in config.json
+///
+/// ja: config.jsonに保存されるworkspaceレジストリを扱うサービス
+pub struct WorkspaceRegistryService {
+    config_manager: ConfigManager,
+}
+
+impl WorkspaceRegistryService {
+    pub fn new() -> Result<Self, WxError> {
+        Ok(Self {
+            config_manager: ConfigManager::new()?,
+        })
+    }
+
+    pub fn with_base_dir(base_dir: &Path) -> Self {
+        Self {
+            config_manager: ConfigManager::with_base_dir(base_dir),
+        }
+    }
+
+    /// en: Returns all registered workspaces
+    ///
+    /// ja: 登録済みの全workspaceを返却する
+    pub fn list(&self) -> Result<Vec<WorkspaceEntry>, WxError> {
+        Ok(self.config_manager.load()?.workspaces)
+    }
+
+    /// en: Check that the workspace name can be registered
+    /// Entries whose directory no longer exists are treated as stale and do not block the name
+    ///
+    /// ja: workspace名が登録可能か確認する
+    /// ディレクトリが存在しないエントリは古いものとして扱い、名前の再利用を妨げない
+    pub fn ensure_available(&self, name: &str) -> Result<(), WxError> {
+        match self.config_manager.load()?.find_workspace(name) {
+            Some(entry) if entry.path.exists() => {
+                Err(WxError::WorkspaceAlreadyRegistered(name.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// en: Record a workspace in the registry, replacing a stale entry with the same name
+    ///
+    /// ja: workspaceをレジストリに記録する（同名の古いエントリは置き換える）
+    pub fn register(&self, entry: WorkspaceEntry) -> Result<(), WxError> {
+        self.ensure_available(&entry.name)?;
+
+        let mut config = self.config_manager.load()?;
+        if config.has_workspace(&entry.name) {
+            config.remove_workspace(&entry.name)?;
+        }
+        config.add_workspace(entry)?;
+
+        self.config_manager.save(&config)
+    }
+
+    /// en: Replace a registered workspace with the same name
+    ///
+    /// ja: 同名の登録済みworkspaceを置き換える
+    pub fn update(&self, entry: WorkspaceEntry) -> Result<(), WxError> {
+        let mut config = self.config_manager.load()?;
+        config.remove_workspace(&entry.name)?;
+        config.add_workspace(entry)?;
+
+        self.config_manager.save(&config)
+    }
+
+    /// en: Remove a workspace from the registry by name
+    ///
+    /// ja: 名前を指定してworkspaceをレジストリから削除する
+    pub fn unregister(&self, name: &str) -> Result<WorkspaceEntry, WxError> {
+        let mut config = self.config_manager.load()?;
+        let entry = config.remove_workspace(name)?;
+        self.config_manager.save(&config)?;
+        Ok(entry)
+    }
+
+    /// en: Resolve a workspace by registered name, or by directory path relative to `base_dir`
+    ///
+    /// ja: 登録名、または `base_dir` からの相対ディレクトリパスでworkspaceを解決する
+    pub fn resolve(&self, name_or_path: &str, base_dir: &Path) -> Result<WorkspaceEntry, WxError> {
+        let config = self.config_manager.load()?;
+
+        if let Some(entry) = config.find_workspace(name_or_path) {
+            return Ok(entry.clone());
+        }
+
+        base_dir
+            .join(name_or_path)
+            .canonicalize()
+            .ok()
+            .and_then(|path| config.find_workspace_by_path(&path).cloned())
+            .ok_or_else(|| WxError::WorkspaceNotFound(name_or_path.to_string()))
+    }
+
+    /// en: Resolve the registered workspace that contains `dir`, e.g. the current directory
+    ///
+    /// ja: `dir`（カレントディレクトリなど）を含む登録済みworkspaceを解決する
+    pub fn resolve_containing(&self, dir: &Path) -> Result<WorkspaceEntry, WxError> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+
+        self.config_manager
+            .load()?
+            .workspaces
+            .into_iter()
+            .filter(|entry| dir.starts_with(&entry.path))
+            .max_by_key(|entry| entry.path.components().count())
+            .ok_or_else(|| WxError::WorkspaceNotFound(dir.display().to_string()))
+    }
+
+    /// en: Resolve `<workspace>[/<repo>]` to the workspace directory, or to the worktree
+    /// whose directory or repository is `<repo>`
+    ///
+    /// ja: `<workspace>[/<repo>]` をworkspaceディレクトリ、またはディレクトリ名かリポジトリ名が
+    /// `<repo>` のworktreeに解決する
+    pub fn resolve_dir(&self, target: &str, base_dir: &Path) -> Result<PathBuf, WxError> {
+        let target = target.trim_end_matches('/');
+        let error = match self.resolve(target, base_dir) {
+            Ok(entry) => return Ok(entry.path),
+            Err(e) => e,
+        };
+        let Some((name_or_path, repo)) = target.rsplit_once('/') else {
+            return Err(error);
+        };
+
+        let entry = self.resolve(name_or_path, base_dir)?;
+        if let Some(member) = entry
+            .members
+            .iter()
+            .find(|member| member.path.file_name().is_some_and(|name| name == repo))
+        {
+            return Ok(member.path.clone());
+        }
+        Ok(entry.find_member(repo, None)?.path.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::utils::test_helpers::*;
+
+    #[test]
+    fn test_register_and_list() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+
+        registry
+            .register(create_test_workspace_entry("feature-auth"))
+            .unwrap();
+
+        let workspaces = registry.list().unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0].name, "feature-auth");
+    }
+
+    #[test]
+    fn test_register_duplicate_live_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+
+        let mut entry = create_test_workspace_entry("feature-auth");
+        entry.path = dir.path().to_path_buf();
+        registry.register(entry.clone()).unwrap();
+
+        assert!(matches!(
+            registry.register(entry),
+            Err(WxError::WorkspaceAlreadyRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_replaces_stale_workspace() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+
+        registry
+            .register(create_test_workspace_entry("feature-auth"))
+            .unwrap();
+
+        assert!(registry
+            .register(create_test_workspace_entry("feature-auth"))
+            .is_ok());
+        assert_eq!(registry.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_by_name_and_path() {
+        let (dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+        let workspace_dir = dir.path().join("work/feature-auth");
+        fs::create_dir_all(&workspace_dir).unwrap();
+
+        let mut entry = create_test_workspace_entry("feature-auth");
+        entry.path = workspace_dir.canonicalize().unwrap();
+        registry.register(entry).unwrap();
+
+        assert_eq!(
+            registry
+                .resolve("feature-auth", Path::new("/"))
+                .unwrap()
+                .name,
+            "feature-auth"
+        );
+        assert_eq!(
+            registry
+                .resolve("./feature-auth", &dir.path().join("work"))
+                .unwrap()
+                .name,
+            "feature-auth"
+        );
+        assert!(matches!(
+            registry.resolve("missing", dir.path()),
+            Err(WxError::WorkspaceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_dir() {
+        let (dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+        let workspace_dir = dir.path().join("work/feature-auth");
+        fs::create_dir_all(&workspace_dir).unwrap();
+
+        let mut entry = create_test_workspace_entry("feature-auth");
+        entry.path = workspace_dir.canonicalize().unwrap();
+        entry.members[0].path = entry.path.join("frontend-main");
+        registry.register(entry.clone()).unwrap();
+
+        assert_eq!(
+            registry.resolve_dir("feature-auth", dir.path()).unwrap(),
+            entry.path
+        );
+        assert_eq!(
+            registry
+                .resolve_dir("feature-auth/frontend-main", dir.path())
+                .unwrap(),
+            entry.members[0].path
+        );
+        assert_eq!(
+            registry
+                .resolve_dir("work/feature-auth/frontend/", dir.path())
+                .unwrap(),
+            entry.members[0].path
+        );
+        assert!(matches!(
+            registry.resolve_dir("feature-auth/backend", dir.path()),
+            Err(WxError::NotInWorkspace(_, _))
+        ));
+        assert!(matches!(
+            registry.resolve_dir("missing", dir.path()),
+            Err(WxError::WorkspaceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_containing() {
+        let (dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+        let workspace_dir = dir.path().join("work/feature-auth");
+        fs::create_dir_all(workspace_dir.join("frontend/src")).unwrap();
+
+        let mut entry = create_test_workspace_entry("feature-auth");
+        entry.path = workspace_dir.canonicalize().unwrap();
+        registry.register(entry).unwrap();
+
+        assert_eq!(
+            registry
+                .resolve_containing(&workspace_dir.join("frontend/src"))
+                .unwrap()
+                .name,
+            "feature-auth"
+        );
+        assert!(matches!(
+            registry.resolve_containing(&dir.path().join("work")),
+            Err(WxError::WorkspaceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_update() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+
+        let mut entry = create_test_workspace_entry("feature-auth");
+        registry.register(entry.clone()).unwrap();
+
+        entry.members.clear();
+        registry.update(entry).unwrap();
+
+        assert!(registry.list().unwrap()[0].members.is_empty());
+        assert!(registry
+            .update(create_test_workspace_entry("missing"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_unregister() {
+        let (_dir, base_dir) = setup_test_dirs();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+
+        registry
+            .register(create_test_workspace_entry("feature-auth"))
+            .unwrap();
+
+        assert!(registry.unregister("feature-auth").is_ok());
+        assert!(registry.list().unwrap().is_empty());
+        assert!(registry.unregister("feature-auth").is_err());
+    }
+}
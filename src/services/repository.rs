@@ -1,13 +1,25 @@
-use std::{fs::remove_dir_all, path::Path};
+use std::{
+    fs::{remove_dir, remove_dir_all},
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    infrastructure::{config::ConfigManager, git::GitOperations},
-    models::{Repository, WxError},
+    infrastructure::{
+        config::ConfigManager,
+        git::{DefaultWorktreeManager, GitOperations, WorktreeManager},
+    },
+    models::{
+        error::ErrorReport,
+        transfer::{FetchReport, TransferProgress},
+        Repository, WxError,
+    },
+    utils::parallel_map,
 };
 
 pub struct RepositoryService {
     config_manager: ConfigManager,
     git_ops: GitOperations,
+    worktree_manager: DefaultWorktreeManager,
 }
 
 impl RepositoryService {
@@ -15,6 +27,7 @@ impl RepositoryService {
         Ok(Self {
             config_manager: ConfigManager::new()?,
             git_ops: GitOperations,
+            worktree_manager: DefaultWorktreeManager,
         })
     }
 
@@ -22,48 +35,260 @@ impl RepositoryService {
         Self {
             config_manager: ConfigManager::with_base_dir(base_dir),
             git_ops: GitOperations,
+            worktree_manager: DefaultWorktreeManager,
         }
     }
 
-    pub fn register(&mut self, url: &str) -> Result<(), WxError> {
-        self.git_ops.validate_url(url)?;
-
-        let repo_name = self.git_ops.extract_repo_name(url)?;
+    /// en: Register a repository by bare-cloning it, returning the registered repository
+    /// `name` overrides the name taken from the URL
+    ///
+    /// ja: リポジトリをbare cloneして登録し、登録したリポジトリを返却する
+    /// `name` を指定するとURLから得る名前の代わりに使用する
+    pub fn register(&mut self, url: &str, name: Option<&str>) -> Result<Repository, WxError> {
+        self.register_all(&[url.to_string()], name, 1, |_, _, _| {})?
+            .remove(0)
+    }
 
-        let target_path = self
-            .config_manager
-            .base_dir()
-            .join(format!("{}.git", &repo_name));
+    /// en: Register several repositories, bare-cloning at most `jobs` of them at a time
+    /// into `repos/<host>/<owner>/<repo>.git`. `name` is only accepted with a single URL.
+    /// A URL that fails does not stop the others; the result of each URL is returned in order.
+    /// The transfer of each clone is passed to `on_progress` with the index of its URL
+    ///
+    /// ja: 複数のリポジトリを `repos/<host>/<owner>/<repo>.git` にbare cloneして登録する（同時実行数は最大 `jobs`）
+    /// `name` はURLが1つの場合のみ指定できる
+    /// 失敗したURLがあっても他のURLの登録は続け、各URLの結果を順に返却する
+    /// 各cloneの転送の進捗は、URLのインデックスと共に `on_progress` に渡される
+    pub fn register_all<F>(
+        &mut self,
+        urls: &[String],
+        name: Option<&str>,
+        jobs: usize,
+        on_progress: F,
+    ) -> Result<Vec<Result<Repository, WxError>>, WxError>
+    where
+        F: Fn(usize, &str, &TransferProgress) + Sync,
+    {
+        if name.is_some() && urls.len() > 1 {
+            return Err(WxError::General(
+                "--name can only be used when registering a single URL".to_string(),
+            ));
+        }
 
         let mut config = self.config_manager.load()?;
 
-        config.add_repository(Repository {
-            name: repo_name,
-            remote: url.to_string(),
-            local_path: target_path.to_str().unwrap().to_string(),
-        })?;
+        // a copy of the config also catches the same repository given twice
+        let mut planned_config = config.clone();
+        let mut results: Vec<Result<Repository, WxError>> =
+            urls.iter()
+                .map(|url| {
+                    self.git_ops.validate_url(url)?;
+                    let repo_name = match name {
+                        Some(name) => {
+                            Repository::validate_name(name)?;
+                            name.to_string()
+                        }
+                        None => self.git_ops.extract_repo_name(url)?,
+                    };
+                    let target_path = self
+                        .config_manager
+                        .base_dir()
+                        .join("repos")
+                        .join(self.git_ops.storage_path(url)?);
+                    // another form of the same URL (e.g. SSH and HTTPS) shares the clone directory
+                    if let Some(existing) = planned_config.repositories.iter().find(|repo| {
+                        repo.remote == *url || Path::new(&repo.local_path) == target_path
+                    }) {
+                        return Err(WxError::AlreadyRegistered(existing.name.clone()));
+                    }
+                    if let Some(existing) = planned_config.find_repository(&repo_name) {
+                        return Err(WxError::RepositoryNameTaken(
+                            repo_name,
+                            existing.remote.clone(),
+                        ));
+                    }
+
+                    let repository = Repository {
+                        name: repo_name,
+                        remote: url.to_string(),
+                        local_path: target_path.to_str().unwrap().to_string(),
+                        default_base: None,
+                    };
+                    planned_config.add_repository(repository.clone())?;
+                    Ok(repository)
+                })
+                .collect();
+
+        let planned: Vec<(usize, &Repository)> = results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.as_ref().ok().map(|repo| (index, repo)))
+            .collect();
+        let cloned = parallel_map(&planned, jobs, |_, (index, repository)| {
+            let target_path = Path::new(&repository.local_path);
+            let existed = target_path.exists();
+            let result = self.git_ops.bare_clone(
+                &repository.remote,
+                target_path,
+                &|progress: &TransferProgress| on_progress(*index, &repository.name, progress),
+            );
+            // a failed clone must not block registering the URL again
+            if result.is_err() && !existed {
+                let _ = remove_dir_all(target_path);
+                self.remove_empty_parents(target_path);
+            }
+            (*index, result)
+        });
+
+        for (index, result) in cloned {
+            if let Err(e) = result {
+                results[index] = Err(e);
+            }
+        }
 
-        self.git_ops.bare_clone(url, &target_path)?;
+        let mut registered = false;
+        for repository in results.iter().flatten() {
+            config.add_repository(repository.clone())?;
+            registered = true;
+        }
+        if registered {
+            self.config_manager.save(&config)?;
+        }
 
-        self.config_manager.save(&config)?;
+        Ok(results)
+    }
 
-        Ok(())
+    /// en: Fetch the named repositories, or every registered repository when `names` is empty,
+    /// at most `jobs` at a time. A failed fetch is reported for its repository without stopping
+    /// the others. The transfer of each fetch is passed to `on_progress` with its index
+    ///
+    /// ja: 指定したリポジトリ（`names` が空の場合は全ての登録済みリポジトリ）をフェッチする（同時実行数は最大 `jobs`）
+    /// 失敗したフェッチは他を止めずにそのリポジトリの結果として報告する
+    /// 各フェッチの転送の進捗は、インデックスと共に `on_progress` に渡される
+    pub fn fetch<F>(
+        &self,
+        names: &[String],
+        jobs: usize,
+        on_progress: F,
+    ) -> Result<Vec<FetchReport>, WxError>
+    where
+        F: Fn(usize, &str, &TransferProgress) + Sync,
+    {
+        let repositories = self.list()?;
+        let targets: Vec<&Repository> = if names.is_empty() {
+            repositories.iter().collect()
+        } else {
+            names
+                .iter()
+                .map(|name| {
+                    repositories
+                        .iter()
+                        .find(|repo| &repo.name == name)
+                        .ok_or_else(|| WxError::RepositoryNotFound(name.clone()))
+                })
+                .collect::<Result<_, _>>()?
+        };
+
+        Ok(parallel_map(&targets, jobs, |index, repository| {
+            let bare_repo_path = repository.bare_repo_path();
+            let result = self
+                .worktree_manager
+                .fetch(&bare_repo_path, &|progress: &TransferProgress| {
+                    on_progress(index, &repository.name, progress)
+                });
+            FetchReport {
+                repo_name: repository.name.clone(),
+                error: result.err().as_ref().map(ErrorReport::from),
+            }
+        }))
     }
 
-    pub fn unregister(&mut self, repo_name: &str) -> Result<(), WxError> {
+    /// en: Unregister a repository and remove its bare clone
+    /// Refuses while worktrees still point at the repository unless `force` is set,
+    /// and returns the workspace directories that are left orphaned
+    ///
+    /// ja: リポジトリの登録を解除し、bare cloneを削除する
+    /// worktreeが残っている場合は `force` 指定時以外拒否し、孤立するworkspaceディレクトリを返却する
+    pub fn unregister(&mut self, repo_name: &str, force: bool) -> Result<Vec<PathBuf>, WxError> {
         let mut config = self.config_manager.load()?;
 
-        config.remove_repository(repo_name)?;
+        let bare_repo_path = config.remove_repository(repo_name)?.bare_repo_path();
+
+        let orphaned_workspaces = self.find_workspaces_using(&bare_repo_path)?;
+        if !orphaned_workspaces.is_empty() && !force {
+            return Err(WxError::RepositoryInUse(
+                repo_name.to_string(),
+                orphaned_workspaces,
+            ));
+        }
 
         self.config_manager.save(&config)?;
 
-        remove_dir_all(
-            self.config_manager
-                .base_dir()
-                .join(format!("{repo_name}.git")),
-        )?;
+        if bare_repo_path.exists() {
+            remove_dir_all(&bare_repo_path)?;
+            self.remove_empty_parents(&bare_repo_path);
+        }
 
-        Ok(())
+        Ok(orphaned_workspaces)
+    }
+
+    /// en: Remove the `<host>/<owner>` directories left empty under `repos` by removing a bare clone
+    ///
+    /// ja: bare cloneの削除で空になった `repos` 配下の `<host>/<owner>` ディレクトリを削除する
+    fn remove_empty_parents(&self, bare_repo_path: &Path) {
+        let repos_dir = self.config_manager.base_dir().join("repos");
+        let mut dir = bare_repo_path.parent();
+        while let Some(parent) = dir.filter(|dir| dir.starts_with(&repos_dir) && *dir != repos_dir)
+        {
+            // fails once a directory still holds other repositories
+            if remove_dir(parent).is_err() {
+                break;
+            }
+            dir = parent.parent();
+        }
+    }
+
+    /// en: Returns the workspace directories containing worktrees of the bare repository
+    ///
+    /// ja: bareリポジトリのworktreeを含むworkspaceディレクトリを返却する
+    fn find_workspaces_using(&self, bare_repo_path: &Path) -> Result<Vec<PathBuf>, WxError> {
+        if !bare_repo_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut workspaces: Vec<PathBuf> = Vec::new();
+
+        for worktree_name in self.worktree_manager.list_worktrees(bare_repo_path)? {
+            let worktree_path = self
+                .worktree_manager
+                .worktree_path(bare_repo_path, &worktree_name)?;
+            let workspace_dir = worktree_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or(worktree_path);
+
+            if !workspaces.contains(&workspace_dir) {
+                workspaces.push(workspace_dir);
+            }
+        }
+
+        Ok(workspaces)
+    }
+
+    /// en: Set the ref new branches of the repository start from when no base is given
+    ///
+    /// ja: base未指定時に新しいブランチの作成元となる参照をリポジトリに設定する
+    pub fn set_default_base(
+        &self,
+        repo_name: &str,
+        default_base: Option<String>,
+    ) -> Result<(), WxError> {
+        let mut config = self.config_manager.load()?;
+        config
+            .find_repository_mut(repo_name)
+            .ok_or_else(|| WxError::RepositoryNotFound(repo_name.to_string()))?
+            .default_base = default_base;
+
+        self.config_manager.save(&config)
     }
 
     pub fn list(&self) -> Result<Vec<Repository>, WxError> {
@@ -93,7 +318,7 @@ mod tests {
         let mut repository_service = RepositoryService::with_base_dir(&base_dir);
 
         assert!(repository_service
-            .register(soruce_repo.to_str().unwrap())
+            .register(soruce_repo.to_str().unwrap(), None)
             .is_ok());
 
         assert_eq!(
@@ -106,10 +331,17 @@ mod tests {
             1
         );
 
-        assert!(base_dir.join("source_repo.git").exists());
+        assert!(base_dir
+            .join("repos")
+            .join(
+                GitOperations
+                    .storage_path(soruce_repo.to_str().unwrap())
+                    .unwrap()
+            )
+            .exists());
 
         assert!(repository_service
-            .register(source_repo2.to_str().unwrap())
+            .register(source_repo2.to_str().unwrap(), None)
             .is_ok());
 
         assert_eq!(
@@ -122,7 +354,14 @@ mod tests {
             2
         );
 
-        assert!(base_dir.join("source_repo2.git").exists());
+        assert!(base_dir
+            .join("repos")
+            .join(
+                GitOperations
+                    .storage_path(source_repo2.to_str().unwrap())
+                    .unwrap()
+            )
+            .exists());
     }
 
     #[test]
@@ -133,7 +372,7 @@ mod tests {
         let mut repository_service = RepositoryService::with_base_dir(&base_dir);
 
         assert!(repository_service
-            .register(source_repo.to_str().unwrap())
+            .register(source_repo.to_str().unwrap(), None)
             .is_ok());
 
         assert_eq!(
@@ -147,7 +386,7 @@ mod tests {
         );
 
         assert!(repository_service
-            .register(source_repo.to_str().unwrap())
+            .register(source_repo.to_str().unwrap(), None)
             .is_err());
 
         assert_eq!(
@@ -161,12 +400,125 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_repository_service_register_with_name() {
+        let (dir, base_dir) = setup_test_dirs();
+        let org_a_api = create_test_git_repo(&dir.path().join("org-a"), "api");
+        let org_b_api = create_test_git_repo(&dir.path().join("org-b"), "api");
+
+        let mut repository_service = RepositoryService::with_base_dir(&base_dir);
+        let api = repository_service
+            .register(org_a_api.to_str().unwrap(), None)
+            .unwrap();
+        assert!(matches!(
+            repository_service.register(org_b_api.to_str().unwrap(), None),
+            Err(WxError::RepositoryNameTaken(_, _))
+        ));
+        let org_b = repository_service
+            .register(org_b_api.to_str().unwrap(), Some("org-b-api"))
+            .unwrap();
+
+        assert_eq!(org_b.name, "org-b-api");
+        assert_ne!(org_b.local_path, api.local_path);
+        assert!(org_b.bare_repo_path().starts_with(base_dir.join("repos")));
+        assert!(org_b.bare_repo_path().exists());
+        assert!(matches!(
+            repository_service.register(org_b_api.to_str().unwrap(), Some("again")),
+            Err(WxError::AlreadyRegistered(name)) if name == "org-b-api"
+        ));
+        assert!(matches!(
+            repository_service.register(&format!("{}/", org_b_api.to_str().unwrap()), Some("again")),
+            Err(WxError::AlreadyRegistered(name)) if name == "org-b-api"
+        ));
+        assert!(matches!(
+            repository_service.register(org_b_api.to_str().unwrap(), Some("org/b")),
+            Err(WxError::InvalidRepositoryName(_))
+        ));
+        assert!(repository_service
+            .register_all(
+                &[
+                    org_a_api.to_str().unwrap().to_string(),
+                    org_b_api.to_str().unwrap().to_string()
+                ],
+                Some("api"),
+                1,
+                |_, _, _| {}
+            )
+            .is_err());
+
+        repository_service.unregister("api", false).unwrap();
+        repository_service.unregister("org-b-api", false).unwrap();
+        assert!(!org_b.bare_repo_path().exists());
+        assert!(!base_dir.join("repos").join("local").exists());
+    }
+
     #[test]
     fn test_repository_service_register_invalid_url() {
         let (_dir, base_dir) = setup_test_dirs();
         let mut repository_service = RepositoryService::with_base_dir(&base_dir);
 
-        assert!(repository_service.register("https://github.com").is_err())
+        assert!(repository_service
+            .register("https://github.com", None)
+            .is_err())
+    }
+
+    #[test]
+    fn test_repository_service_register_all() {
+        let (dir, base_dir) = setup_test_dirs();
+        let source_repo = create_test_git_repo(dir.path(), "source_repo.git");
+        let source_repo2 = create_test_git_repo(dir.path(), "source_repo2.git");
+        let urls = vec![
+            source_repo.to_str().unwrap().to_string(),
+            "https://github.com".to_string(),
+            source_repo2.to_str().unwrap().to_string(),
+            source_repo.to_str().unwrap().to_string(),
+        ];
+
+        let mut repository_service = RepositoryService::with_base_dir(&base_dir);
+        let results = repository_service
+            .register_all(&urls, None, 2, |_, _, _| {})
+            .unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().name, "source_repo");
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().name, "source_repo2");
+        assert!(matches!(results[3], Err(WxError::AlreadyRegistered(_))));
+        assert_eq!(repository_service.list().unwrap().len(), 2);
+        assert!(base_dir
+            .join("repos")
+            .join(
+                GitOperations
+                    .storage_path(source_repo2.to_str().unwrap())
+                    .unwrap()
+            )
+            .exists());
+    }
+
+    #[test]
+    fn test_repository_service_fetch() {
+        let (dir, base_dir) = setup_test_dirs();
+        let source_repo = create_test_git_repo(dir.path(), "source_repo.git");
+        let mut repository_service = RepositoryService::with_base_dir(&base_dir);
+        repository_service
+            .register(source_repo.to_str().unwrap(), None)
+            .unwrap();
+
+        let reports = repository_service.fetch(&[], 4, |_, _, _| {}).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].repo_name, "source_repo");
+        assert!(reports[0].error.is_none());
+
+        remove_dir_all(&source_repo).unwrap();
+        let reports = repository_service
+            .fetch(&["source_repo".to_string()], 4, |_, _, _| {})
+            .unwrap();
+        assert!(reports[0].error.is_some());
+
+        assert!(matches!(
+            repository_service.fetch(&["unknown".to_string()], 4, |_, _, _| {}),
+            Err(WxError::RepositoryNotFound(_))
+        ));
     }
 
     #[test]
@@ -185,13 +537,16 @@ mod tests {
     fn test_repository_service_unregister() {
         let (_dir, base_dir) = setup_test_dirs();
 
-        create_test_config_file(&base_dir, vec![create_test_repository("test")]);
+        create_test_config_file(
+            &base_dir,
+            vec![create_test_repository_in(&base_dir, "test")],
+        );
 
         create_test_bare_repo(&base_dir, "test");
 
         let mut repository_service = RepositoryService::with_base_dir(&base_dir);
 
-        assert!(repository_service.unregister("test").is_ok());
+        assert!(repository_service.unregister("test", false).is_ok());
 
         assert_eq!(
             repository_service
@@ -214,7 +569,7 @@ mod tests {
 
         let mut repository_service = RepositoryService::with_base_dir(&base_dir);
 
-        assert!(repository_service.unregister("test2").is_err());
+        assert!(repository_service.unregister("test2", false).is_err());
 
         assert_eq!(
             repository_service
@@ -226,4 +581,62 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_repository_service_unregister_with_worktrees() {
+        let (dir, base_dir) = setup_test_dirs();
+
+        create_test_config_file(
+            &base_dir,
+            vec![create_test_repository_in(&base_dir, "test")],
+        );
+
+        let bare_repo_path = create_test_bare_repo(&base_dir, "test");
+        add_test_remote_branch(
+            &git2::Repository::open_bare(&bare_repo_path).unwrap(),
+            "main",
+        );
+        let workspace_dir = dir.path().join("work/feature");
+        std::fs::create_dir_all(&workspace_dir).unwrap();
+        DefaultWorktreeManager
+            .create_worktree(&bare_repo_path, &workspace_dir.join("main"), "main", None)
+            .unwrap();
+
+        let mut repository_service = RepositoryService::with_base_dir(&base_dir);
+
+        let result = repository_service.unregister("test", false);
+        assert!(matches!(
+            result,
+            Err(WxError::RepositoryInUse(_, ref workspaces)) if workspaces.len() == 1
+        ));
+        assert!(bare_repo_path.exists());
+        assert_eq!(repository_service.list().unwrap().len(), 1);
+
+        let orphaned = repository_service.unregister("test", true).unwrap();
+        assert_eq!(orphaned.len(), 1);
+        assert!(!bare_repo_path.exists());
+        assert_eq!(repository_service.list().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_repository_service_set_default_base() {
+        let (_dir, base_dir) = setup_test_dirs();
+        create_test_config_file(
+            &base_dir,
+            vec![create_test_repository("a"), create_test_repository("b")],
+        );
+
+        let repository_service = RepositoryService::with_base_dir(&base_dir);
+        repository_service
+            .set_default_base("a", Some("origin/develop".to_string()))
+            .unwrap();
+
+        let repos = repository_service.list().unwrap();
+        assert_eq!(repos[0].name, "a");
+        assert_eq!(repos[0].default_base.as_deref(), Some("origin/develop"));
+        assert!(matches!(
+            repository_service.set_default_base("missing", None),
+            Err(WxError::RepositoryNotFound(_))
+        ));
+    }
 }
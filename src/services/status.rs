@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    infrastructure::{config::ConfigManager, git::WorktreeManager},
+    models::{
+        status::{MemberStatus, WorkspaceStatus},
+        workspace::WorkspaceEntry,
+        WxError,
+    },
+    services::WorkspaceRegistryService,
+};
+
+/// en: Service that collects the git state of every worktree of a workspace
+///
+/// ja: workspaceの全worktreeのGitの状態を収集するサービス
+pub struct WorkspaceStatusService<W: WorktreeManager> {
+    worktree_manager: W,
+    registry: WorkspaceRegistryService,
+    wx_home: PathBuf,
+    groups: Vec<String>,
+}
+
+impl<W: WorktreeManager> WorkspaceStatusService<W> {
+    pub fn new(worktree_manager: W, wx_home: PathBuf) -> Self {
+        Self {
+            worktree_manager,
+            registry: WorkspaceRegistryService::with_base_dir(&wx_home),
+            wx_home,
+            groups: Vec::new(),
+        }
+    }
+
+    /// en: Only include worktrees of repositories in these groups
+    ///
+    /// ja: 指定したグループのリポジトリのworktreeのみを対象にする
+    pub fn with_groups(mut self, groups: Vec<String>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// en: Status of the workspace given by name or path, or of the workspace containing
+    /// `current_dir` when `name_or_path` is omitted
+    ///
+    /// ja: 名前またはパスで指定したworkspace（省略時は `current_dir` を含むworkspace）の状態を返却する
+    pub fn status(
+        &self,
+        name_or_path: Option<&str>,
+        current_dir: &Path,
+    ) -> Result<WorkspaceStatus, WxError> {
+        let workspace = match name_or_path {
+            Some(name_or_path) => self.registry.resolve(name_or_path, current_dir)?,
+            None => self.registry.resolve_containing(current_dir)?,
+        };
+
+        self.workspace_status(&workspace)
+    }
+
+    fn workspace_status(&self, workspace: &WorkspaceEntry) -> Result<WorkspaceStatus, WxError> {
+        let config = ConfigManager::with_base_dir(&self.wx_home).load()?;
+        let group_repos = config.group_repositories(&self.groups)?;
+        let mut worktrees = Vec::new();
+
+        for member in workspace
+            .members
+            .iter()
+            .filter(|member| self.groups.is_empty() || group_repos.contains(&member.repo_name))
+        {
+            // a repository unregistered with --force is still found through the worktree itself
+            let bare_repo_path = match config.find_repository(&member.repo_name) {
+                Some(repo) => repo.bare_repo_path(),
+                None => self.worktree_manager.bare_repo_of(&member.path)?,
+            };
+            let status = self
+                .worktree_manager
+                .worktree_status(&bare_repo_path, &member.path)?;
+
+            worktrees.push(MemberStatus {
+                repo_name: member.repo_name.clone(),
+                path: member.path.clone(),
+                status,
+            });
+        }
+
+        Ok(WorkspaceStatus {
+            name: workspace.name.clone(),
+            path: workspace.path.clone(),
+            worktrees,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::{
+        infrastructure::git::MockWorktreeManager,
+        models::status::WorktreeStatus,
+        services::GroupService,
+        utils::test_helpers::{
+            create_test_config_file, create_test_repository_in, create_test_workspace_entry,
+            setup_test_dirs,
+        },
+    };
+
+    #[test]
+    fn test_status() {
+        let (dir, base_dir) = setup_test_dirs();
+        let workspace_dir = dir.path().join("work/feature-auth");
+        fs::create_dir_all(&workspace_dir).unwrap();
+        let workspace_dir = workspace_dir.canonicalize().unwrap();
+        let mut entry = create_test_workspace_entry("feature-auth");
+        entry.path = workspace_dir.clone();
+        entry.members[0].path = workspace_dir.join("frontend");
+        create_test_config_file(
+            &base_dir,
+            vec![create_test_repository_in(&base_dir, "frontend")],
+        );
+        WorkspaceRegistryService::with_base_dir(&base_dir)
+            .register(entry.clone())
+            .unwrap();
+
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        mock_worktree_manager
+            .expect_worktree_status()
+            .with(
+                eq(base_dir.join("frontend.git")),
+                eq(workspace_dir.join("frontend")),
+            )
+            .times(2)
+            .returning(|_, _| {
+                Ok(WorktreeStatus {
+                    branch: Some("main".to_string()),
+                    behind: 3,
+                    ..WorktreeStatus::default()
+                })
+            });
+
+        let service = WorkspaceStatusService::new(mock_worktree_manager, base_dir.clone());
+
+        let status = service.status(Some("feature-auth"), dir.path()).unwrap();
+        assert_eq!(status.worktrees.len(), 1);
+        assert_eq!(status.worktrees[0].repo_name, "frontend");
+        assert_eq!(status.worktrees[0].status.behind, 3);
+
+        // resolved from a directory inside the workspace
+        let status = service.status(None, &workspace_dir).unwrap();
+        assert_eq!(status.name, "feature-auth");
+
+        assert!(matches!(
+            service.status(Some("missing"), dir.path()),
+            Err(WxError::WorkspaceNotFound(_))
+        ));
+
+        // a group without the workspace's repositories leaves nothing to inspect
+        create_test_config_file(
+            &base_dir,
+            vec![
+                create_test_repository_in(&base_dir, "frontend"),
+                create_test_repository_in(&base_dir, "backend"),
+            ],
+        );
+        WorkspaceRegistryService::with_base_dir(&base_dir)
+            .register(entry)
+            .unwrap();
+        GroupService::with_base_dir(&base_dir)
+            .create("api", &["backend".to_string()])
+            .unwrap();
+        let service = WorkspaceStatusService::new(MockWorktreeManager::new(), base_dir)
+            .with_groups(vec!["api".to_string()]);
+        let status = service.status(Some("feature-auth"), dir.path()).unwrap();
+        assert!(status.worktrees.is_empty());
+
+        let service = service.with_groups(vec!["web".to_string()]);
+        assert!(matches!(
+            service.status(Some("feature-auth"), dir.path()),
+            Err(WxError::GroupNotFound(_))
+        ));
+    }
+}
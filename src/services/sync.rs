@@ -0,0 +1,368 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    infrastructure::{
+        config::ConfigManager, filesystem::WorkspaceFileManager, git::WorktreeManager,
+    },
+    models::{
+        sync::{SyncOutcome, SyncReport, SyncResult},
+        transfer::TransferProgress,
+        Config, WxError,
+    },
+    services::WorkspaceRegistryService,
+    utils::{default_jobs, normalize, parallel_map},
+};
+
+/// en: A worktree to sync and the bare repository it belongs to
+///
+/// ja: 同期対象のworktreeと、その所属するbareリポジトリ
+struct SyncTarget {
+    repo_name: String,
+    path: PathBuf,
+    bare_repo_path: Result<PathBuf, String>,
+}
+
+/// en: Service that fetches and fast-forwards (or rebases) every worktree of a workspace
+///
+/// ja: workspaceの全worktreeをフェッチし、fast-forward（またはrebase）するサービス
+pub struct WorkspaceSyncService<W: WorktreeManager> {
+    worktree_manager: W,
+    workspace_file_manager: WorkspaceFileManager,
+    registry: WorkspaceRegistryService,
+    wx_home: PathBuf,
+    groups: Vec<String>,
+}
+
+impl<W: WorktreeManager> WorkspaceSyncService<W> {
+    pub fn new(worktree_manager: W, wx_home: PathBuf) -> Self {
+        Self {
+            worktree_manager,
+            workspace_file_manager: WorkspaceFileManager,
+            registry: WorkspaceRegistryService::with_base_dir(&wx_home),
+            wx_home,
+            groups: Vec::new(),
+        }
+    }
+
+    /// en: Only sync worktrees of repositories in these groups
+    ///
+    /// ja: 指定したグループのリポジトリのworktreeのみを同期する
+    pub fn with_groups(mut self, groups: Vec<String>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// en: Fetch each bare repository once, concurrently, then sync every worktree of the workspace
+    /// The transfer of each fetch is passed to `on_progress` with its index and repository name.
+    /// A workspace missing from the registry is read from its `.code-workspace` file
+    ///
+    /// ja: bareリポジトリごとに1回ずつ並行してフェッチし、workspaceの全worktreeを同期する
+    /// 各フェッチの転送の進捗は、インデックスとリポジトリ名と共に `on_progress` に渡される
+    /// レジストリに無いworkspaceは `.code-workspace` ファイルから読み込む
+    pub fn sync<F>(
+        &self,
+        name_or_path: Option<&str>,
+        current_dir: &Path,
+        rebase: bool,
+        on_progress: F,
+    ) -> Result<SyncResult, WxError>
+    where
+        W: Sync,
+        F: Fn(usize, &str, &TransferProgress) + Sync,
+    {
+        let (workspace_dir, targets) = self.resolve_targets(name_or_path, current_dir)?;
+
+        let mut fetches: Vec<(&str, &PathBuf)> = Vec::new();
+        for target in &targets {
+            if let Ok(bare_repo_path) = &target.bare_repo_path {
+                if !fetches
+                    .iter()
+                    .any(|(_, fetched)| *fetched == bare_repo_path)
+                {
+                    fetches.push((&target.repo_name, bare_repo_path));
+                }
+            }
+        }
+        let fetch_errors: HashMap<PathBuf, String> = parallel_map(
+            &fetches,
+            default_jobs(),
+            |index, (repo_name, bare_repo_path)| {
+                self.worktree_manager
+                    .fetch(bare_repo_path, &|progress: &TransferProgress| {
+                        on_progress(index, repo_name, progress)
+                    })
+                    .err()
+                    .map(|e| ((*bare_repo_path).clone(), e.to_string()))
+            },
+        )
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let reports = targets
+            .iter()
+            .map(|target| {
+                let outcome = match &target.bare_repo_path {
+                    Err(_) if !target.path.exists() => SyncOutcome::Missing,
+                    Err(error) => SyncOutcome::Failed {
+                        error: error.clone(),
+                    },
+                    Ok(bare_repo_path) => match fetch_errors.get(bare_repo_path) {
+                        Some(error) => SyncOutcome::Failed {
+                            error: format!("fetch failed: {error}"),
+                        },
+                        None => self
+                            .worktree_manager
+                            .sync_worktree(&target.path, rebase)
+                            .unwrap_or_else(|e| SyncOutcome::Failed {
+                                error: e.to_string(),
+                            }),
+                    },
+                };
+
+                SyncReport {
+                    repo_name: target.repo_name.clone(),
+                    path: target.path.clone(),
+                    outcome,
+                }
+            })
+            .collect();
+
+        Ok(SyncResult {
+            workspace_dir,
+            reports,
+        })
+    }
+
+    /// en: Worktrees of a registered workspace, or of the `.code-workspace` file in the
+    /// workspace directory when it is not registered, limited to the groups when given
+    ///
+    /// ja: 登録済みworkspaceのworktree、未登録の場合はworkspaceディレクトリ内の `.code-workspace` ファイルのworktree
+    /// グループ指定時はそのグループのリポジトリのworktreeのみ
+    fn resolve_targets(
+        &self,
+        name_or_path: Option<&str>,
+        current_dir: &Path,
+    ) -> Result<(PathBuf, Vec<SyncTarget>), WxError> {
+        let config = ConfigManager::with_base_dir(&self.wx_home).load()?;
+        let group_repos = config.group_repositories(&self.groups)?;
+        let registered = match name_or_path {
+            Some(name_or_path) => self.registry.resolve(name_or_path, current_dir),
+            None => self.registry.resolve_containing(current_dir),
+        };
+
+        let (workspace_dir, mut targets): (PathBuf, Vec<SyncTarget>) = match registered {
+            Ok(workspace) => {
+                let targets = workspace
+                    .members
+                    .into_iter()
+                    .map(|member| match config.find_repository(&member.repo_name) {
+                        Some(repo) => SyncTarget {
+                            bare_repo_path: Ok(repo.bare_repo_path()),
+                            repo_name: member.repo_name,
+                            path: member.path,
+                        },
+                        None => SyncTarget {
+                            repo_name: member.repo_name,
+                            ..self.unregistered_target(&config, member.path)
+                        },
+                    })
+                    .collect();
+                (workspace.path, targets)
+            }
+            Err(WxError::WorkspaceNotFound(name)) => {
+                let workspace_dir = match name_or_path {
+                    Some(name_or_path) => current_dir.join(name_or_path),
+                    None => current_dir.to_path_buf(),
+                };
+                let workspace_file = self
+                    .workspace_file_manager
+                    .find(&workspace_dir)
+                    .ok_or(WxError::WorkspaceNotFound(name))?;
+
+                let targets = self
+                    .workspace_file_manager
+                    .read(&workspace_file)?
+                    .folders
+                    .into_iter()
+                    .map(|folder| {
+                        self.unregistered_target(&config, workspace_dir.join(folder.path))
+                    })
+                    .collect();
+                (workspace_dir, targets)
+            }
+            Err(e) => return Err(e),
+        };
+
+        if !self.groups.is_empty() {
+            targets.retain(|target| group_repos.contains(&target.repo_name));
+        }
+        Ok((workspace_dir, targets))
+    }
+
+    /// en: Target whose bare repository is found through the worktree itself, named after the
+    /// registered repository with that bare repository when there is one
+    ///
+    /// ja: worktree自体からbareリポジトリを特定する同期対象
+    /// そのbareリポジトリの登録済みリポジトリがあればその名前を使用する
+    fn unregistered_target(&self, config: &Config, path: PathBuf) -> SyncTarget {
+        let bare_repo_path = if path.exists() {
+            self.worktree_manager
+                .bare_repo_of(&path)
+                .map_err(|e| e.to_string())
+        } else {
+            Err("worktree is missing".to_string())
+        };
+        let registered_name = bare_repo_path.as_ref().ok().and_then(|bare| {
+            config
+                .repositories
+                .iter()
+                .find(|repo| normalize(&repo.bare_repo_path()) == normalize(bare))
+                .map(|repo| repo.name.clone())
+        });
+        let repo_name = registered_name.unwrap_or_else(|| {
+            bare_repo_path
+                .as_ref()
+                .ok()
+                .and_then(|bare| bare.file_stem())
+                .or_else(|| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+        SyncTarget {
+            repo_name,
+            path,
+            bare_repo_path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use mockall::predicate::eq;
+
+    use super::*;
+    use crate::{
+        infrastructure::git::MockWorktreeManager,
+        utils::test_helpers::{
+            create_test_config_file, create_test_repository_in, create_test_workspace_entry,
+            setup_test_dirs, test_create_workspace_file,
+        },
+    };
+
+    #[test]
+    fn test_sync_registered_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+        let workspace_dir = dir.path().join("work/feature-auth");
+        fs::create_dir_all(&workspace_dir).unwrap();
+        let workspace_dir = workspace_dir.canonicalize().unwrap();
+        let mut entry = create_test_workspace_entry("feature-auth");
+        entry.path = workspace_dir.clone();
+        entry.members[0].path = workspace_dir.join("frontend");
+        let mut second = entry.members[0].clone();
+        second.path = workspace_dir.join("frontend-2");
+        entry.members.push(second);
+        create_test_config_file(
+            &base_dir,
+            vec![create_test_repository_in(&base_dir, "frontend")],
+        );
+        WorkspaceRegistryService::with_base_dir(&base_dir)
+            .register(entry)
+            .unwrap();
+
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        // both worktrees share one bare repository, which is fetched once
+        mock_worktree_manager
+            .expect_fetch()
+            .withf({
+                let bare_repo_path = base_dir.join("frontend.git");
+                move |path, _| path == bare_repo_path
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_worktree_manager
+            .expect_sync_worktree()
+            .with(eq(workspace_dir.join("frontend")), eq(true))
+            .returning(|_, _| Ok(SyncOutcome::FastForwarded { commits: 2 }));
+        mock_worktree_manager
+            .expect_sync_worktree()
+            .with(eq(workspace_dir.join("frontend-2")), eq(true))
+            .returning(|_, _| Ok(SyncOutcome::SkippedDirty));
+
+        let result = WorkspaceSyncService::new(mock_worktree_manager, base_dir)
+            .sync(Some("feature-auth"), dir.path(), true, |_, _, _| {})
+            .unwrap();
+
+        assert_eq!(result.workspace_dir, workspace_dir);
+        assert_eq!(
+            result
+                .reports
+                .iter()
+                .map(|report| report.outcome.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                SyncOutcome::FastForwarded { commits: 2 },
+                SyncOutcome::SkippedDirty
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sync_unregistered_workspace_reads_workspace_file() {
+        let (dir, base_dir) = setup_test_dirs();
+        let workspace_dir = dir.path().join("work/legacy");
+        fs::create_dir_all(workspace_dir.join("frontend")).unwrap();
+        test_create_workspace_file(
+            &workspace_dir,
+            "legacy",
+            vec!["frontend".to_string(), "gone".to_string()],
+        );
+
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        mock_worktree_manager
+            .expect_bare_repo_of()
+            .returning(|_| Ok(PathBuf::from("/home/user/.wx/frontend.git")));
+        mock_worktree_manager
+            .expect_fetch()
+            .times(1)
+            .returning(|_, _| Err(WxError::General("offline".to_string())));
+
+        let result = WorkspaceSyncService::new(mock_worktree_manager, base_dir)
+            .sync(
+                Some("legacy"),
+                &dir.path().join("work"),
+                false,
+                |_, _, _| {},
+            )
+            .unwrap();
+
+        assert_eq!(result.reports.len(), 2);
+        assert_eq!(result.reports[0].repo_name, "frontend");
+        assert!(matches!(
+            &result.reports[0].outcome,
+            SyncOutcome::Failed { error } if error.contains("offline")
+        ));
+        assert_eq!(result.reports[1].repo_name, "gone");
+        assert_eq!(result.reports[1].outcome, SyncOutcome::Missing);
+    }
+
+    #[test]
+    fn test_sync_unknown_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+
+        let result = WorkspaceSyncService::new(MockWorktreeManager::new(), base_dir).sync(
+            Some("missing"),
+            dir.path(),
+            false,
+            |_, _, _| {},
+        );
+
+        assert!(matches!(result, Err(WxError::WorkspaceNotFound(_))));
+    }
+}
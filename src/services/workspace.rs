@@ -1,9 +1,29 @@
-use std::path::{Path, PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+
+use super::journal::{Journal, JournalEntry};
 
 use crate::{
-    infrastructure::{filesystem::WorkspaceFileManager, git::WorktreeManager},
-    models::{workspace::WorktreeSelection, WxError},
-    utils::sanitize_branch_name,
+    infrastructure::{
+        config::ConfigManager,
+        filesystem::{workspace_writer, TemplateFileManager, WorkspaceFileManager},
+        git::WorktreeManager,
+    },
+    models::{
+        template::WorkspaceTemplate,
+        workspace::{
+            BranchInfo, GenerationResult, NewBranch, WorkspaceEntry, WorkspaceFile,
+            WorkspaceFolder, WorkspaceFormat, WorkspaceMember, WorkspaceSettings, WorktreeLayout,
+            WorktreeSelection,
+        },
+        Repository, WxError,
+    },
+    services::WorkspaceRegistryService,
+    utils::{is_single_component, normalize},
 };
 
 /// en: Service for generating workspaces with worktrees
@@ -12,67 +32,542 @@ use crate::{
 pub struct WorkspaceGenerationService<W: WorktreeManager> {
     worktree_manager: W,
     workspace_file_manager: WorkspaceFileManager,
+    registry: WorkspaceRegistryService,
+    layout: WorktreeLayout,
+    formats: Vec<WorkspaceFormat>,
+    settings: WorkspaceSettings,
+    template: Option<String>,
     wx_home: PathBuf,
 }
 
 impl<W: WorktreeManager> WorkspaceGenerationService<W> {
     pub fn new(worktree_manager: W, wx_home: PathBuf) -> Result<Self, WxError> {
+        let config = ConfigManager::with_base_dir(&wx_home).load()?;
+
         Ok(Self {
             workspace_file_manager: WorkspaceFileManager,
+            registry: WorkspaceRegistryService::with_base_dir(&wx_home),
             worktree_manager,
+            layout: config.layout,
+            formats: config.formats,
+            settings: WorkspaceSettings::default(),
+            template: None,
             wx_home,
         })
     }
 
-    /// en: Returns the list of branches for the specified repository
+    /// en: Override the worktree directory layout configured in config.json
     ///
-    /// ja: 指定したリポジトリのブランチを返却する
-    pub fn get_branches(&self, repo_name: &str) -> Result<Vec<String>, WxError> {
-        let bare_repo_path = self.wx_home.join(format!("{repo_name}.git"));
-        self.worktree_manager.fetch(&bare_repo_path)?;
-        self.worktree_manager.get_remote_branches(&bare_repo_path)
+    /// ja: config.jsonで設定されたworktreeディレクトリのレイアウトを上書きする
+    pub fn with_layout(mut self, layout: WorktreeLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// en: Override the editor formats configured in config.json
+    ///
+    /// ja: config.jsonで設定されたエディタ形式を上書きする
+    pub fn with_formats(mut self, formats: Vec<WorkspaceFormat>) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// en: Settings written to generated workspace files, overriding those of templates
+    ///
+    /// ja: 生成するworkspaceファイルに書き込むsettings（テンプレートの値を上書きする）
+    pub fn with_settings(mut self, settings: WorkspaceSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// en: Named template in `~/.wx/templates` merged over the default and repository templates
+    ///
+    /// ja: デフォルトとリポジトリのテンプレートの上にマージする、`~/.wx/templates` の名前付きテンプレート
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// en: Returns the local and remote branches of the specified repository,
+    /// deduplicated by name with the default branch first
+    /// Fetch the repository beforehand (see `RepositoryService::fetch`) to include new remote branches
+    ///
+    /// ja: 指定したリポジトリのローカル・リモートブランチを返却する
+    /// 名前で重複を除き、デフォルトブランチを先頭にする
+    /// 新しいリモートブランチを含めるには、事前にリポジトリをフェッチする（`RepositoryService::fetch` を参照）
+    pub fn get_branches(&self, repo_name: &str) -> Result<Vec<BranchInfo>, WxError> {
+        let bare_repo_path = ConfigManager::with_base_dir(&self.wx_home)
+            .load()?
+            .bare_repo_path(repo_name)?;
+
+        let default_branch = self.worktree_manager.default_branch(&bare_repo_path)?;
+        let remote_branches = self
+            .worktree_manager
+            .get_remote_branches(&bare_repo_path)?
+            .into_iter()
+            .filter_map(|name| name.strip_prefix("origin/").map(str::to_string))
+            .filter(|name| name != "HEAD");
+
+        let mut names: Vec<String> = self.worktree_manager.get_local_branches(&bare_repo_path)?;
+        names.extend(remote_branches);
+        names.sort();
+        names.dedup();
+
+        let mut branches: Vec<BranchInfo> = names
+            .into_iter()
+            .map(|name| BranchInfo {
+                is_default: default_branch.as_deref() == Some(name.as_str()),
+                name,
+            })
+            .collect();
+        branches.sort_by_key(|branch| !branch.is_default);
+
+        Ok(branches)
     }
 
     /// en: Generate workspace with worktrees from the specified selections
+    /// and record it in the workspace registry
+    /// Every change is journaled and undone in reverse order if a later step fails
     ///
-    /// ja: 指定された選択からworktreeを含むworkspaceを生成する
+    /// ja: 指定された選択からworktreeを含むworkspaceを生成し、workspaceレジストリに記録する
+    /// 全ての変更はジャーナルに記録され、後続の処理が失敗した場合は逆順に取り消される
     pub fn generate(
         &self,
         workspace_dir: &Path,
         worktree_selection: Vec<WorktreeSelection>,
         workspace_name: &str,
-    ) -> Result<(), WxError> {
-        // create workspace file
-        self.workspace_file_manager.generate(
+    ) -> Result<GenerationResult, WxError> {
+        let mut journal = Journal::new();
+
+        self.try_generate(
+            &mut journal,
             workspace_dir,
+            worktree_selection,
             workspace_name,
-            worktree_selection
-                .iter()
-                .map(|ws| sanitize_branch_name(&ws.branch))
-                .collect(),
+        )
+        .map_err(|e| journal.rollback(&self.worktree_manager, e))
+    }
+
+    fn try_generate(
+        &self,
+        journal: &mut Journal,
+        workspace_dir: &Path,
+        worktree_selection: Vec<WorktreeSelection>,
+        workspace_name: &str,
+    ) -> Result<GenerationResult, WxError> {
+        self.registry.ensure_available(workspace_name)?;
+
+        let dir_names = self.worktree_dir_names(workspace_dir, &worktree_selection, &[])?;
+        let template = self.workspace_template(&worktree_selection)?;
+        let folders = worktree_selection
+            .iter()
+            .zip(&dir_names)
+            .map(|(ws, dir_name)| WorkspaceFolder::for_worktree(dir_name, &ws.repo_name))
+            .collect();
+        let workspace_file = template.into_workspace_file(folders);
+
+        let formats = WorkspaceFormat::or_default(&self.formats);
+        for format in &formats {
+            let writer = workspace_writer(*format);
+            if let Some(path) = writer
+                .paths(workspace_dir, workspace_name, &workspace_file)
+                .into_iter()
+                .find(|path| path.exists())
+            {
+                return Err(WxError::WorkspaceFileAlreadyExists(
+                    path.to_string_lossy().to_string(),
+                ));
+            }
+        }
+
+        if !workspace_dir.exists() {
+            fs::create_dir_all(workspace_dir)?;
+            journal.record(JournalEntry::Dir(workspace_dir.to_path_buf()));
+        }
+        let workspace_dir = workspace_dir.canonicalize()?;
+
+        let (members, new_branches) =
+            self.create_worktrees(journal, &workspace_dir, worktree_selection, &dir_names)?;
+
+        // create workspace files
+        self.write_workspace_files(
+            journal,
+            &workspace_dir,
+            workspace_name,
+            &formats,
+            &workspace_file,
         )?;
+        let project_path =
+            workspace_writer(formats[0]).project_path(&workspace_dir, workspace_name);
+
+        let result = GenerationResult {
+            worktrees: members.iter().map(|m| m.path.clone()).collect(),
+            workspace_file: project_path.clone(),
+            new_branches,
+        };
+
+        self.registry.register(WorkspaceEntry {
+            name: workspace_name.to_string(),
+            path: workspace_dir,
+            members,
+            created_at: Utc::now(),
+            workspace_file: project_path,
+            formats: formats.clone(),
+        })?;
+
+        Ok(result)
+    }
+
+    /// en: Merge the blocks of a new workspace file: the `default` template, then the template of
+    /// each selected repository, then the named template, then the settings given to the service
+    ///
+    /// ja: 新しいworkspaceファイルのブロックをマージする
+    /// `default` テンプレート、選択した各リポジトリのテンプレート、名前付きテンプレート、
+    /// サービスに渡されたsettingsの順に重ねる
+    fn workspace_template(
+        &self,
+        worktree_selection: &[WorktreeSelection],
+    ) -> Result<WorkspaceTemplate, WxError> {
+        let template_file_manager = TemplateFileManager::with_base_dir(&self.wx_home);
+        let mut template = template_file_manager.load("default")?.unwrap_or_default();
 
-        // create worktrees
+        let mut repo_names: Vec<&str> = Vec::new();
         for ws in worktree_selection {
-            let bare_repo_path = self.wx_home.join(format!("{}.git", ws.repo_name));
-            let target_path = workspace_dir.join(sanitize_branch_name(&ws.branch));
-            self.worktree_manager
-                .create_worktree(&bare_repo_path, &target_path, &ws.branch)?;
+            if !repo_names.contains(&ws.repo_name.as_str()) {
+                repo_names.push(&ws.repo_name);
+            }
+        }
+        for repo_name in repo_names {
+            if let Some(repo_template) = template_file_manager.load_repo(repo_name)? {
+                template.merge(repo_template);
+            }
+        }
+
+        if let Some(name) = &self.template {
+            template.merge(
+                template_file_manager
+                    .load(name)?
+                    .ok_or_else(|| WxError::TemplateNotFound(name.clone()))?,
+            );
+        }
+
+        template.merge(WorkspaceTemplate {
+            settings: self.settings.clone(),
+            ..WorkspaceTemplate::default()
+        });
+
+        Ok(template)
+    }
+
+    /// en: Add worktrees to an existing workspace, appending them to its workspace file and registry entry
+    /// Every change is journaled and undone in reverse order if a later step fails
+    ///
+    /// ja: 既存のworkspaceにworktreeを追加し、workspaceファイルとレジストリのエントリに追記する
+    /// 全ての変更はジャーナルに記録され、後続の処理が失敗した場合は逆順に取り消される
+    pub fn add_worktrees(
+        &self,
+        workspace: &WorkspaceEntry,
+        worktree_selection: Vec<WorktreeSelection>,
+    ) -> Result<Vec<WorkspaceMember>, WxError> {
+        let mut journal = Journal::new();
+
+        self.try_add_worktrees(&mut journal, workspace, worktree_selection)
+            .map_err(|e| journal.rollback(&self.worktree_manager, e))
+    }
+
+    fn try_add_worktrees(
+        &self,
+        journal: &mut Journal,
+        workspace: &WorkspaceEntry,
+        worktree_selection: Vec<WorktreeSelection>,
+    ) -> Result<Vec<WorkspaceMember>, WxError> {
+        let existing_dir_names: Vec<String> = workspace
+            .members
+            .iter()
+            .filter_map(|m| m.path.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .collect();
+        let dir_names =
+            self.worktree_dir_names(&workspace.path, &worktree_selection, &existing_dir_names)?;
+
+        let (members, _new_branches) =
+            self.create_worktrees(journal, &workspace.path, worktree_selection, &dir_names)?;
+
+        let mut workspace_file = self.current_workspace_file(workspace)?;
+        workspace_file.folders.extend(
+            members.iter().zip(&dir_names).map(|(member, dir_name)| {
+                WorkspaceFolder::for_worktree(dir_name, &member.repo_name)
+            }),
+        );
+        self.update_workspace_files(journal, workspace, &workspace_file)?;
+
+        let mut entry = workspace.clone();
+        entry.members.extend(members.iter().cloned());
+        self.registry.update(entry)?;
+
+        Ok(members)
+    }
+
+    /// en: Remove a worktree from an existing workspace, dropping its folder from the workspace file
+    /// and its member from the registry entry
+    /// A worktree with uncommitted changes is kept unless `force` is set
+    ///
+    /// ja: 既存のworkspaceからworktreeを削除し、workspaceファイルのフォルダとレジストリのメンバーから外す
+    /// 未コミットの変更があるworktreeは `force` 指定時以外削除しない
+    pub fn remove_worktree(
+        &self,
+        workspace: &WorkspaceEntry,
+        member: &WorkspaceMember,
+        force: bool,
+    ) -> Result<(), WxError> {
+        if !force
+            && member.path.exists()
+            && self
+                .worktree_manager
+                .has_uncommitted_changes(&member.path)?
+        {
+            return Err(WxError::UncommittedChanges(
+                member.path.to_string_lossy().to_string(),
+            ));
+        }
+
+        let bare_repo_path = ConfigManager::with_base_dir(&self.wx_home)
+            .load()?
+            .find_repository(&member.repo_name)
+            .map(Repository::bare_repo_path);
+        if let Some(bare_repo_path) = bare_repo_path.filter(|path| path.exists()) {
+            let member_path = normalize(&member.path);
+            for worktree_name in self.worktree_manager.list_worktrees(&bare_repo_path)? {
+                let worktree_path = self
+                    .worktree_manager
+                    .worktree_path(&bare_repo_path, &worktree_name)?;
+                if normalize(&worktree_path) == member_path {
+                    self.worktree_manager
+                        .remove_worktree(&bare_repo_path, &worktree_name)?;
+                }
+            }
+        }
+
+        let mut workspace_file = self.current_workspace_file(workspace)?;
+        workspace_file
+            .folders
+            .retain(|folder| workspace.path.join(&folder.path) != member.path);
+        // the worktree is already pruned, so the updated files are kept even if one fails
+        self.update_workspace_files(&mut Journal::new(), workspace, &workspace_file)?;
+
+        let mut entry = workspace.clone();
+        entry.members.retain(|m| m != member);
+        self.registry.update(entry)
+    }
+
+    /// en: The workspace file model of an existing workspace, read from its `.code-workspace` file
+    /// or built from its members when the workspace has none
+    ///
+    /// ja: 既存のworkspaceのworkspaceファイルのモデル
+    /// `.code-workspace` ファイルから読み込み、存在しない場合はメンバーから組み立てる
+    fn current_workspace_file(&self, workspace: &WorkspaceEntry) -> Result<WorkspaceFile, WxError> {
+        let vscode_path = workspace
+            .path
+            .join(format!("{}.code-workspace", workspace.name));
+        if vscode_path.is_file() {
+            return self.workspace_file_manager.read(&vscode_path);
+        }
+
+        let mut workspace_file = WorkspaceFile::new(Vec::new());
+        workspace_file.folders = workspace
+            .members
+            .iter()
+            .filter_map(|member| {
+                let dir_name = member.path.file_name()?.to_string_lossy().to_string();
+                Some(WorkspaceFolder::for_worktree(&dir_name, &member.repo_name))
+            })
+            .collect();
+        Ok(workspace_file)
+    }
+
+    /// en: Rewrite the project files of each format of an existing workspace
+    /// Formats whose files were deleted by the user are not written again
+    ///
+    /// ja: 既存のworkspaceの各形式のプロジェクトファイルを書き直す
+    /// ユーザーが削除した形式のファイルは再作成しない
+    fn update_workspace_files(
+        &self,
+        journal: &mut Journal,
+        workspace: &WorkspaceEntry,
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
+        let formats: Vec<WorkspaceFormat> = WorkspaceFormat::or_default(&workspace.formats)
+            .into_iter()
+            .filter(|format| workspace_writer(*format).exists(&workspace.path, &workspace.name))
+            .collect();
+
+        self.write_workspace_files(
+            journal,
+            &workspace.path,
+            &workspace.name,
+            &formats,
+            workspace_file,
+        )
+    }
+
+    /// en: Write the project files of each format, journaling the files created or overwritten
+    /// A directory the writer creates (e.g., `.idea`) is journaled as a whole
+    ///
+    /// ja: 各形式のプロジェクトファイルを書き出し、作成・上書きしたファイルをジャーナルに記録する
+    /// 書き出しで作成されたディレクトリ（`.idea` など）はまとめて記録する
+    fn write_workspace_files(
+        &self,
+        journal: &mut Journal,
+        workspace_dir: &Path,
+        workspace_name: &str,
+        formats: &[WorkspaceFormat],
+        workspace_file: &WorkspaceFile,
+    ) -> Result<(), WxError> {
+        for format in formats {
+            let writer = workspace_writer(*format);
+            let paths = writer.paths(workspace_dir, workspace_name, workspace_file);
+
+            let mut new_dirs: Vec<PathBuf> = Vec::new();
+            for dir in paths.iter().filter_map(|path| path.parent()) {
+                if !dir.exists() && !new_dirs.iter().any(|new_dir| new_dir == dir) {
+                    new_dirs.push(dir.to_path_buf());
+                }
+            }
+            let originals: Vec<(PathBuf, Option<String>)> = paths
+                .into_iter()
+                .filter(|path| !new_dirs.iter().any(|dir| path.starts_with(dir)))
+                .map(|path| {
+                    let original = fs::read_to_string(&path).ok();
+                    (path, original)
+                })
+                .collect();
+
+            writer.write(workspace_dir, workspace_name, workspace_file)?;
+
+            for dir in new_dirs {
+                journal.record(JournalEntry::Dir(dir));
+            }
+            for (path, original) in originals {
+                match original {
+                    Some(original) => journal.record(JournalEntry::FileContents { path, original }),
+                    None if path.exists() => journal.record(JournalEntry::File(path)),
+                    None => {}
+                }
+            }
         }
 
         Ok(())
     }
+
+    /// en: Create a worktree for each selection, journaling created branches and worktrees
+    /// New branches start from the selection's base, or the repository's `default_base`
+    ///
+    /// ja: 各選択のworktreeを作成し、作成したブランチとworktreeをジャーナルに記録する
+    /// 新しいブランチは選択のbase、またはリポジトリの `default_base` から作成する
+    fn create_worktrees(
+        &self,
+        journal: &mut Journal,
+        workspace_dir: &Path,
+        worktree_selection: Vec<WorktreeSelection>,
+        dir_names: &[String],
+    ) -> Result<(Vec<WorkspaceMember>, Vec<NewBranch>), WxError> {
+        let config = ConfigManager::with_base_dir(&self.wx_home).load()?;
+        let mut members = Vec::new();
+        let mut new_branches = Vec::new();
+
+        for (ws, dir_name) in worktree_selection.into_iter().zip(dir_names) {
+            let bare_repo_path = config.bare_repo_path(&ws.repo_name)?;
+            let target_path = workspace_dir.join(dir_name);
+            let base = ws.base.as_deref().or_else(|| {
+                config
+                    .find_repository(&ws.repo_name)
+                    .and_then(|repo| repo.default_base.as_deref())
+            });
+            let created = self.worktree_manager.create_worktree(
+                &bare_repo_path,
+                &target_path,
+                &ws.branch,
+                base,
+            )?;
+
+            if created.branch_created {
+                journal.record(JournalEntry::Branch {
+                    bare_repo_path: bare_repo_path.clone(),
+                    branch: ws.branch.clone(),
+                });
+            }
+            if let Some(base) = created.base {
+                new_branches.push(NewBranch {
+                    repo_name: ws.repo_name.clone(),
+                    branch: ws.branch.clone(),
+                    base,
+                });
+            }
+            journal.record(JournalEntry::Worktree {
+                bare_repo_path,
+                name: created.name,
+            });
+
+            members.push(WorkspaceMember {
+                repo_name: ws.repo_name,
+                branch: ws.branch,
+                path: target_path,
+            });
+        }
+
+        Ok((members, new_branches))
+    }
+
+    /// en: Returns the worktree directory name for each selection, rejecting collisions and
+    /// names that would leave the workspace directory up front
+    ///
+    /// ja: 各選択のworktreeディレクトリ名を返却する（衝突やworkspaceディレクトリ外を指す名前は事前にエラーとする）
+    fn worktree_dir_names(
+        &self,
+        workspace_dir: &Path,
+        worktree_selection: &[WorktreeSelection],
+        existing_dir_names: &[String],
+    ) -> Result<Vec<String>, WxError> {
+        let mut dir_names: Vec<String> = Vec::new();
+
+        for ws in worktree_selection {
+            let dir_name = ws
+                .dir_name
+                .clone()
+                .unwrap_or_else(|| self.layout.dir_name(&ws.repo_name, &ws.branch));
+
+            if !is_single_component(&dir_name) {
+                return Err(WxError::InvalidDirectoryName(dir_name));
+            }
+            if dir_names.contains(&dir_name) || existing_dir_names.contains(&dir_name) {
+                return Err(WxError::DuplicateWorktreeDirectory(dir_name));
+            }
+
+            let target_path = workspace_dir.join(&dir_name);
+            if target_path.exists() {
+                return Err(WxError::WorktreeAlreadyExists(
+                    target_path.to_string_lossy().to_string(),
+                ));
+            }
+
+            dir_names.push(dir_name);
+        }
+
+        Ok(dir_names)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
     use git2::Repository;
 
     use crate::{
         infrastructure::git::{DefaultWorktreeManager, MockWorktreeManager},
-        utils::test_helpers::{add_test_remote_branch, create_test_bare_repo, setup_test_dirs},
+        models::workspace::{BranchBase, CreatedWorktree},
+        utils::test_helpers::{
+            add_test_remote_branch, create_test_config_file, create_test_repository,
+            create_test_workspace_entry, register_test_bare_repo, setup_test_dirs,
+        },
     };
 
     use super::*;
@@ -90,21 +585,42 @@ mod tests {
     #[test]
     fn test_get_branches() {
         let (_dir, base_dir) = setup_test_dirs();
+        create_test_config_file(&base_dir, vec![create_test_repository("wx")]);
         let mut mock_worktree_manager = MockWorktreeManager::new();
 
-        mock_worktree_manager.expect_fetch().returning(|_| Ok(()));
+        mock_worktree_manager
+            .expect_default_branch()
+            .withf(|path| path == Path::new("/home/user/.wx/wx.git"))
+            .returning(|_| Ok(Some("main".to_string())));
         mock_worktree_manager
             .expect_get_remote_branches()
-            .returning(|_| Ok(vec!["origin/main".to_string(), "origin/dev".to_string()]));
+            .returning(|_| {
+                Ok(vec![
+                    "origin/HEAD".to_string(),
+                    "origin/main".to_string(),
+                    "origin/dev".to_string(),
+                ])
+            });
+        mock_worktree_manager
+            .expect_get_local_branches()
+            .returning(|_| Ok(vec!["main".to_string(), "feature/local".to_string()]));
 
         let workspace_generation_service =
             WorkspaceGenerationService::new(mock_worktree_manager, base_dir).unwrap();
 
         let branches = workspace_generation_service.get_branches("wx").unwrap();
 
+        let branch = |name: &str, is_default: bool| BranchInfo {
+            name: name.to_string(),
+            is_default,
+        };
         assert_eq!(
             branches,
-            vec!["origin/main".to_string(), "origin/dev".to_string()]
+            vec![
+                branch("main", true),
+                branch("dev", false),
+                branch("feature/local", false),
+            ]
         );
     }
 
@@ -115,8 +631,8 @@ mod tests {
         let wx_frontend_repo_name = String::from("wx-frontend");
         let wx_backend_repo_name = String::from("wx-backend");
         fs::create_dir_all(&working_dir).unwrap();
-        let wx_frontend_repo_path = create_test_bare_repo(&base_dir, &wx_frontend_repo_name);
-        let wx_backend_repo_path = create_test_bare_repo(&base_dir, &wx_backend_repo_name);
+        let wx_frontend_repo_path = register_test_bare_repo(&base_dir, &wx_frontend_repo_name);
+        let wx_backend_repo_path = register_test_bare_repo(&base_dir, &wx_backend_repo_name);
         let wx_frontend_repo = Repository::open_bare(&wx_frontend_repo_path).unwrap();
         let wx_backend_repo = Repository::open_bare(&wx_backend_repo_path).unwrap();
         add_test_remote_branch(&wx_frontend_repo, "sso-ui");
@@ -125,24 +641,568 @@ mod tests {
             WorktreeSelection {
                 repo_name: wx_frontend_repo_name,
                 branch: "sso-ui".to_string(),
+                ..Default::default()
             },
             WorktreeSelection {
                 repo_name: wx_backend_repo_name,
                 branch: "sso-api".to_string(),
+                ..Default::default()
             },
         ];
 
         let worktree_manager = DefaultWorktreeManager;
 
         let workspace_generation_service =
-            WorkspaceGenerationService::new(worktree_manager, base_dir).unwrap();
+            WorkspaceGenerationService::new(worktree_manager, base_dir.clone()).unwrap();
 
         assert!(workspace_generation_service
             .generate(&working_dir, selection, "wx")
             .is_ok());
 
         assert!(working_dir.join("wx.code-workspace").exists());
-        assert!(working_dir.join("sso-ui").exists());
-        assert!(working_dir.join("sso-api").exists());
+        assert!(working_dir.join("wx-frontend").exists());
+        assert!(working_dir.join("wx-backend").exists());
+
+        let workspaces = WorkspaceRegistryService::with_base_dir(&base_dir)
+            .list()
+            .unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0].name, "wx");
+        assert_eq!(workspaces[0].members.len(), 2);
+        assert_eq!(workspaces[0].members[0].repo_name, "wx-frontend");
+        assert!(workspaces[0].path.is_absolute());
+    }
+
+    #[test]
+    fn test_generate_rejects_registered_name() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso-feature");
+        fs::create_dir_all(&working_dir).unwrap();
+
+        let mut entry = create_test_workspace_entry("wx");
+        entry.path = dir.path().to_path_buf();
+        WorkspaceRegistryService::with_base_dir(&base_dir)
+            .register(entry)
+            .unwrap();
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(MockWorktreeManager::new(), base_dir).unwrap();
+
+        assert!(matches!(
+            workspace_generation_service.generate(&working_dir, vec![], "wx"),
+            Err(WxError::WorkspaceAlreadyRegistered(_))
+        ));
+        assert!(!working_dir.join("wx.code-workspace").exists());
+    }
+
+    #[test]
+    fn test_generate_applies_templates() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        let templates_dir = base_dir.join("templates");
+        fs::create_dir_all(templates_dir.join("repos")).unwrap();
+        fs::write(
+            templates_dir.join("default.json"),
+            r#"{"settings": {"editor.tabSize": 2, "editor.formatOnSave": true}}"#,
+        )
+        .unwrap();
+        fs::write(
+            templates_dir.join("repos/wx-frontend.json"),
+            r#"{"extensions": {"recommendations": ["dbaeumer.vscode-eslint"]}, "settings": {"editor.tabSize": 4}}"#,
+        )
+        .unwrap();
+        fs::write(
+            templates_dir.join("fullstack.json"),
+            "{\n  // shared debug setup\n  \"launch\": {\"configurations\": []},\n}",
+        )
+        .unwrap();
+        let repo_path = register_test_bare_repo(&base_dir, "wx-frontend");
+        add_test_remote_branch(&Repository::open_bare(&repo_path).unwrap(), "sso-ui");
+        let selection = || {
+            vec![WorktreeSelection {
+                repo_name: "wx-frontend".to_string(),
+                branch: "sso-ui".to_string(),
+                ..Default::default()
+            }]
+        };
+        let mut settings = WorkspaceSettings::default();
+        settings
+            .values
+            .insert("editor.formatOnSave".to_string(), false.into());
+
+        let missing = WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir.clone())
+            .unwrap()
+            .with_template(Some("missing".to_string()));
+        assert!(matches!(
+            missing.generate(&working_dir, selection(), "sso"),
+            Err(WxError::TemplateNotFound(_))
+        ));
+        assert!(!working_dir.exists());
+
+        WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir)
+            .unwrap()
+            .with_layout(WorktreeLayout::RepoBranch)
+            .with_template(Some("fullstack".to_string()))
+            .with_settings(settings)
+            .generate(&working_dir, selection(), "sso")
+            .unwrap();
+
+        let workspace_file = WorkspaceFileManager
+            .read(&working_dir.join("sso.code-workspace"))
+            .unwrap();
+        assert_eq!(workspace_file.folders[0].path, "wx-frontend-sso-ui");
+        assert_eq!(
+            workspace_file.folders[0].name.as_deref(),
+            Some("wx-frontend")
+        );
+        assert_eq!(
+            serde_json::Value::Object(workspace_file.settings.values),
+            serde_json::json!({"editor.tabSize": 4, "editor.formatOnSave": false})
+        );
+        assert_eq!(
+            workspace_file.extra["extensions"],
+            serde_json::json!({"recommendations": ["dbaeumer.vscode-eslint"]})
+        );
+        assert!(workspace_file.extra.contains_key("launch"));
+    }
+
+    #[test]
+    fn test_generate_uses_default_base() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        let mut frontend = create_test_repository("wx-frontend");
+        frontend.default_base = Some("origin/develop".to_string());
+        create_test_config_file(
+            &base_dir,
+            vec![frontend, create_test_repository("wx-backend")],
+        );
+
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        mock_worktree_manager
+            .expect_create_worktree()
+            .withf(|_, _, branch, base| branch == "sso-ui" && base == &Some("origin/develop"))
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(CreatedWorktree {
+                    name: "wx-frontend".to_string(),
+                    branch_created: true,
+                    base: Some(BranchBase {
+                        reference: "origin/develop".to_string(),
+                        commit: "abc1234".to_string(),
+                        summary: "Develop".to_string(),
+                    }),
+                })
+            });
+        mock_worktree_manager
+            .expect_create_worktree()
+            .withf(|_, _, branch, base| branch == "sso-api" && base == &Some("release"))
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(CreatedWorktree {
+                    name: "wx-backend".to_string(),
+                    branch_created: false,
+                    base: None,
+                })
+            });
+        let selection: Vec<WorktreeSelection> = vec![
+            WorktreeSelection {
+                repo_name: "wx-frontend".to_string(),
+                branch: "sso-ui".to_string(),
+                ..Default::default()
+            },
+            WorktreeSelection {
+                repo_name: "wx-backend".to_string(),
+                branch: "sso-api".to_string(),
+                base: Some("release".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(mock_worktree_manager, base_dir).unwrap();
+        let result = workspace_generation_service
+            .generate(&working_dir, selection, "sso")
+            .unwrap();
+
+        assert_eq!(result.new_branches.len(), 1);
+        assert_eq!(result.new_branches[0].repo_name, "wx-frontend");
+        assert_eq!(result.new_branches[0].base.commit, "abc1234");
+    }
+
+    #[test]
+    fn test_generate_same_branch_in_several_repos() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/auth");
+        fs::create_dir_all(&working_dir).unwrap();
+        for repo_name in ["frontend", "backend"] {
+            let repo_path = register_test_bare_repo(&base_dir, repo_name);
+            add_test_remote_branch(&Repository::open_bare(repo_path).unwrap(), "feature/auth");
+        }
+        let selection: Vec<WorktreeSelection> = ["frontend", "backend"]
+            .iter()
+            .map(|repo_name| WorktreeSelection {
+                repo_name: repo_name.to_string(),
+                branch: "feature/auth".to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir)
+                .unwrap()
+                .with_layout(WorktreeLayout::RepoBranch);
+
+        workspace_generation_service
+            .generate(&working_dir, selection, "auth")
+            .unwrap();
+
+        assert!(working_dir.join("frontend-feature-auth").exists());
+        assert!(working_dir.join("backend-feature-auth").exists());
+    }
+
+    #[test]
+    fn test_generate_rejects_duplicate_directories() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/auth");
+        fs::create_dir_all(&working_dir).unwrap();
+        let selection: Vec<WorktreeSelection> = ["frontend", "backend"]
+            .iter()
+            .map(|repo_name| WorktreeSelection {
+                repo_name: repo_name.to_string(),
+                branch: "feature/auth".to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(MockWorktreeManager::new(), base_dir)
+                .unwrap()
+                .with_layout(WorktreeLayout::Template("{branch}".to_string()));
+
+        assert!(matches!(
+            workspace_generation_service.generate(&working_dir, selection, "auth"),
+            Err(WxError::DuplicateWorktreeDirectory(_))
+        ));
+        assert!(!working_dir.join("auth.code-workspace").exists());
+    }
+
+    #[test]
+    fn test_generate_rejects_directories_outside_workspace() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/auth");
+        fs::create_dir_all(&working_dir).unwrap();
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(MockWorktreeManager::new(), base_dir).unwrap();
+
+        for dir_name in ["../escaped", "/tmp/escaped", ".."] {
+            let selection = vec![WorktreeSelection {
+                repo_name: "frontend".to_string(),
+                branch: "feature/auth".to_string(),
+                dir_name: Some(dir_name.to_string()),
+                ..Default::default()
+            }];
+            assert!(matches!(
+                workspace_generation_service.generate(&working_dir, selection, "auth"),
+                Err(WxError::InvalidDirectoryName(_))
+            ));
+        }
+        assert!(!dir.path().join("work/escaped").exists());
+    }
+
+    #[test]
+    fn test_generate_rolls_back_on_failure() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        let frontend_repo_path = register_test_bare_repo(&base_dir, "wx-frontend");
+        add_test_remote_branch(
+            &Repository::open_bare(&frontend_repo_path).unwrap(),
+            "sso-ui",
+        );
+        // wx-backend is not cloned, so its worktree cannot be created
+        let selection: Vec<WorktreeSelection> = vec![
+            WorktreeSelection {
+                repo_name: "wx-frontend".to_string(),
+                branch: "sso-ui".to_string(),
+                ..Default::default()
+            },
+            WorktreeSelection {
+                repo_name: "wx-backend".to_string(),
+                branch: "sso-api".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+
+        assert!(workspace_generation_service
+            .generate(&working_dir, selection, "sso")
+            .is_err());
+
+        assert!(!working_dir.exists());
+        let frontend_repo = Repository::open_bare(&frontend_repo_path).unwrap();
+        assert!(frontend_repo.worktrees().unwrap().is_empty());
+        assert!(frontend_repo
+            .find_branch("sso-ui", git2::BranchType::Local)
+            .is_err());
+        assert!(WorkspaceRegistryService::with_base_dir(&base_dir)
+            .list()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_generate_reports_rollback_failure() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        create_test_config_file(
+            &base_dir,
+            vec![
+                create_test_repository("wx-frontend"),
+                create_test_repository("wx-backend"),
+            ],
+        );
+        let mut mock_worktree_manager = MockWorktreeManager::new();
+        let mut calls = 0;
+        mock_worktree_manager
+            .expect_create_worktree()
+            .returning(move |_, _, _, _| {
+                calls += 1;
+                if calls == 1 {
+                    Ok(CreatedWorktree {
+                        name: "wx-frontend".to_string(),
+                        branch_created: false,
+                        base: None,
+                    })
+                } else {
+                    Err(WxError::General("clone missing".to_string()))
+                }
+            });
+        mock_worktree_manager
+            .expect_remove_worktree()
+            .times(1)
+            .returning(|_, _| Err(WxError::General("worktree locked".to_string())));
+        let selection: Vec<WorktreeSelection> = vec![
+            WorktreeSelection {
+                repo_name: "wx-frontend".to_string(),
+                branch: "sso-ui".to_string(),
+                ..Default::default()
+            },
+            WorktreeSelection {
+                repo_name: "wx-backend".to_string(),
+                branch: "sso-api".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(mock_worktree_manager, base_dir).unwrap();
+
+        match workspace_generation_service.generate(&working_dir, selection, "sso") {
+            Err(WxError::RollbackFailed {
+                original_error,
+                rollback_error,
+            }) => {
+                assert!(original_error.contains("clone missing"));
+                assert!(rollback_error.contains("worktree locked"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(!working_dir.exists());
+    }
+
+    #[test]
+    fn test_add_worktrees() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        for (repo_name, branch) in [("wx-frontend", "sso-ui"), ("wx-backend", "sso-api")] {
+            let repo_path = register_test_bare_repo(&base_dir, repo_name);
+            add_test_remote_branch(&Repository::open_bare(&repo_path).unwrap(), branch);
+        }
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+        workspace_generation_service
+            .generate(
+                &working_dir,
+                vec![WorktreeSelection {
+                    repo_name: "wx-frontend".to_string(),
+                    branch: "sso-ui".to_string(),
+                    ..Default::default()
+                }],
+                "sso",
+            )
+            .unwrap();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+        let workspace = registry.list().unwrap().remove(0);
+
+        let added = workspace_generation_service
+            .add_worktrees(
+                &workspace,
+                vec![WorktreeSelection {
+                    repo_name: "wx-backend".to_string(),
+                    branch: "sso-api".to_string(),
+                    dir_name: Some("api".to_string()),
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+
+        assert_eq!(added.len(), 1);
+        assert!(working_dir.join("api").exists());
+        let workspace_file = WorkspaceFileManager
+            .read(&workspace.workspace_file)
+            .unwrap();
+        assert_eq!(workspace_file.folders.len(), 2);
+        assert_eq!(workspace_file.folders[1].path, "api");
+        assert_eq!(registry.list().unwrap()[0].members.len(), 2);
+
+        // a directory that is already a member is rejected
+        assert!(matches!(
+            workspace_generation_service.add_worktrees(
+                &registry.list().unwrap()[0],
+                vec![WorktreeSelection {
+                    repo_name: "wx-backend".to_string(),
+                    branch: "sso-api".to_string(),
+                    dir_name: Some("api".to_string()),
+                    ..Default::default()
+                }],
+            ),
+            Err(WxError::DuplicateWorktreeDirectory(_))
+        ));
+    }
+
+    #[test]
+    fn test_remove_worktree() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        for (repo_name, branch) in [("wx-frontend", "sso-ui"), ("wx-backend", "sso-api")] {
+            let repo_path = register_test_bare_repo(&base_dir, repo_name);
+            add_test_remote_branch(&Repository::open_bare(&repo_path).unwrap(), branch);
+        }
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir.clone()).unwrap();
+        workspace_generation_service
+            .generate(
+                &working_dir,
+                vec![
+                    WorktreeSelection {
+                        repo_name: "wx-frontend".to_string(),
+                        branch: "sso-ui".to_string(),
+                        ..Default::default()
+                    },
+                    WorktreeSelection {
+                        repo_name: "wx-backend".to_string(),
+                        branch: "sso-api".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                "sso",
+            )
+            .unwrap();
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+        let workspace = registry.list().unwrap().remove(0);
+        let mut workspace_file = WorkspaceFileManager
+            .read(&workspace.workspace_file)
+            .unwrap();
+        workspace_file
+            .extra
+            .insert("extensions".to_string(), serde_json::json!({}));
+        WorkspaceFileManager
+            .write(&workspace.workspace_file, &workspace_file)
+            .unwrap();
+
+        // uncommitted changes keep the worktree unless forced
+        fs::write(working_dir.join("wx-backend/new.txt"), "wip").unwrap();
+        let backend = workspace.find_member("wx-backend", None).unwrap();
+        assert!(matches!(
+            workspace_generation_service.remove_worktree(&workspace, backend, false),
+            Err(WxError::UncommittedChanges(_))
+        ));
+        assert!(working_dir.join("wx-backend").exists());
+
+        workspace_generation_service
+            .remove_worktree(&workspace, backend, true)
+            .unwrap();
+
+        assert!(!working_dir.join("wx-backend").exists());
+        assert!(DefaultWorktreeManager
+            .list_worktrees(&base_dir.join("wx-backend.git"))
+            .unwrap()
+            .is_empty());
+        let workspace_file = WorkspaceFileManager
+            .read(&workspace.workspace_file)
+            .unwrap();
+        assert_eq!(workspace_file.folders.len(), 1);
+        assert_eq!(workspace_file.folders[0].path, "wx-frontend");
+        assert!(workspace_file.extra.contains_key("extensions"));
+        let members = &registry.list().unwrap()[0].members;
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].repo_name, "wx-frontend");
+    }
+
+    #[test]
+    fn test_generate_writes_editor_formats() {
+        let (dir, base_dir) = setup_test_dirs();
+        let working_dir = dir.path().join("work/sso");
+        for (repo_name, branch) in [("wx-frontend", "sso-ui"), ("wx-backend", "sso-api")] {
+            let repo_path = register_test_bare_repo(&base_dir, repo_name);
+            add_test_remote_branch(&Repository::open_bare(&repo_path).unwrap(), branch);
+        }
+
+        let workspace_generation_service =
+            WorkspaceGenerationService::new(DefaultWorktreeManager, base_dir.clone())
+                .unwrap()
+                .with_formats(vec![WorkspaceFormat::Sublime, WorkspaceFormat::JetBrains]);
+        let result = workspace_generation_service
+            .generate(
+                &working_dir,
+                vec![WorktreeSelection {
+                    repo_name: "wx-frontend".to_string(),
+                    branch: "sso-ui".to_string(),
+                    ..Default::default()
+                }],
+                "sso",
+            )
+            .unwrap();
+
+        let working_dir = working_dir.canonicalize().unwrap();
+        assert_eq!(
+            result.workspace_file,
+            working_dir.join("sso.sublime-project")
+        );
+        assert!(!working_dir.join("sso.code-workspace").exists());
+        assert!(working_dir.join(".idea/wx-frontend.iml").exists());
+        let registry = WorkspaceRegistryService::with_base_dir(&base_dir);
+        let workspace = registry.list().unwrap().remove(0);
+        assert_eq!(
+            workspace.formats,
+            vec![WorkspaceFormat::Sublime, WorkspaceFormat::JetBrains]
+        );
+
+        let members = workspace_generation_service
+            .add_worktrees(
+                &workspace,
+                vec![WorktreeSelection {
+                    repo_name: "wx-backend".to_string(),
+                    branch: "sso-api".to_string(),
+                    ..Default::default()
+                }],
+            )
+            .unwrap();
+        let project = fs::read_to_string(working_dir.join("sso.sublime-project")).unwrap();
+        assert!(project.contains("\"wx-backend\""));
+        let vcs = fs::read_to_string(working_dir.join(".idea/vcs.xml")).unwrap();
+        assert!(vcs.contains("$PROJECT_DIR$/wx-backend"));
+
+        let workspace = registry.list().unwrap().remove(0);
+        workspace_generation_service
+            .remove_worktree(&workspace, &members[0], false)
+            .unwrap();
+        let project = fs::read_to_string(working_dir.join("sso.sublime-project")).unwrap();
+        assert!(!project.contains("wx-backend"));
+        let vcs = fs::read_to_string(working_dir.join(".idea/vcs.xml")).unwrap();
+        assert!(!vcs.contains("wx-backend"));
     }
 }
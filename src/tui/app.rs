@@ -1,9 +1,14 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use ratatui::{
-    style::Stylize,
-    text::Line,
-    widgets::{Block, Paragraph},
-    DefaultTerminal, Frame,
+use crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+use super::{
+    dashboard::{Action, Dashboard},
+    ui,
+};
+use crate::{
+    commands,
+    models::{workspace::CleanTarget, WxError},
+    services::{RepositoryService, WorkspaceRegistryService},
 };
 
 /// The main application which holds the state and logic of the application.
@@ -11,59 +16,154 @@ use ratatui::{
 pub struct App {
     /// Is the application running?
     running: bool,
+    dashboard: Dashboard,
 }
 
 impl App {
-    /// Construct a new instance of [`App`].
-    pub fn new() -> Self {
-        Self::default()
+    /// Construct a new instance of [`App`] with the registered repositories and workspaces.
+    pub fn new() -> Result<Self, WxError> {
+        Ok(Self {
+            running: false,
+            dashboard: Dashboard::new(
+                RepositoryService::new()?.list()?,
+                WorkspaceRegistryService::new()?.list()?,
+            ),
+        })
     }
 
     /// Run the application's main loop.
-    pub fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<(), WxError> {
         self.running = true;
         while self.running {
-            terminal.draw(|frame| self.render(frame))?;
-            self.handle_crossterm_events()?;
+            self.load_status();
+            terminal.draw(|frame| ui::render(&self.dashboard, frame))?;
+            if let Some(action) = self.handle_crossterm_events()? {
+                if let Some(progress) = action.progress() {
+                    self.dashboard.set_message(progress);
+                    terminal.draw(|frame| ui::render(&self.dashboard, frame))?;
+                }
+                self.perform(action);
+            }
         }
         Ok(())
     }
 
-    /// Renders the user interface.
-    fn render(&mut self, frame: &mut Frame) {
-        let title = Line::from("wx - Git worktree and workspace manager")
-            .bold()
-            .blue()
-            .centered();
-        let text = "Welcome to wx!\n\n\
-            A CLI tool for managing Git worktrees and VSCode workspaces.\n\
-            Press `Esc`, `Ctrl-C` or `q` to stop running.";
-        frame.render_widget(
-            Paragraph::new(text)
-                .block(Block::bordered().title(title))
-                .centered(),
-            frame.area(),
-        )
-    }
-
     /// Reads the crossterm events and updates the state of [`App`].
-    fn handle_crossterm_events(&mut self) -> color_eyre::Result<()> {
+    fn handle_crossterm_events(&mut self) -> Result<Option<Action>, WxError> {
         match event::read()? {
-            Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
-            Event::Mouse(_) => {}
-            Event::Resize(_, _) => {}
-            _ => {}
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                self.dashboard.message = None;
+                Ok(self.dashboard.on_key(key))
+            }
+            Event::Mouse(_) => Ok(None),
+            Event::Resize(_, _) => Ok(None),
+            _ => Ok(None),
         }
+    }
+
+    /// Loads the status of the selected workspace if it has not been loaded yet.
+    fn load_status(&mut self) {
+        if let Some(workspace_dir) = self.dashboard.pending_status() {
+            let status =
+                commands::status::execute(Some(&workspace_dir.to_string_lossy()), Vec::new())
+                    .map_err(|e| e.to_string());
+            self.dashboard.set_status(workspace_dir, status);
+        }
+    }
+
+    fn reload(&mut self) -> Result<(), WxError> {
+        self.dashboard.set_data(
+            RepositoryService::new()?.list()?,
+            WorkspaceRegistryService::new()?.list()?,
+        );
         Ok(())
     }
 
-    /// Handles the key events and updates the state of [`App`].
-    fn on_key_event(&mut self, key: KeyEvent) {
-        match (key.modifiers, key.code) {
-            (_, KeyCode::Esc | KeyCode::Char('q'))
-            | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
-            _ => {}
+    /// Performs an action requested from the dashboard and reports its result in the footer.
+    fn perform(&mut self, action: Action) {
+        let result = match action {
+            Action::Quit => {
+                self.quit();
+                return;
+            }
+            Action::Refresh => Ok("Refreshed.".to_string()),
+            Action::NewWorkspace {
+                name,
+                repos,
+                branch,
+            } => commands::new::execute(
+                name,
+                commands::new::NewOptions {
+                    repos: repos
+                        .iter()
+                        .map(|repo| format!("{repo}:{branch}"))
+                        .collect(),
+                    ..Default::default()
+                },
+                |_, _, _| {},
+            )
+            .map(|result| {
+                self.dashboard.clear_marks();
+                format!("Created {}", result.workspace_file.display())
+            }),
+            Action::AddRepository { workspace, spec } => {
+                commands::add::execute(&workspace, &spec, None)
+                    .map(|member| format!("Added {} ({})", member.path.display(), member.branch))
+            }
+            Action::RemoveRepository { workspace, spec } => {
+                commands::remove::execute(&workspace, &spec, false)
+                    .map(|member| format!("Removed {} ({})", member.path.display(), member.branch))
+            }
+            Action::Fetch { workspace } => self.fetch(&workspace),
+            Action::Clean { workspace } => {
+                commands::clean::execute(CleanTarget::Workspace(workspace), false).map(|result| {
+                    let mut message = format!(
+                        "Removed {} worktree(s) and {} workspace file(s)",
+                        result.removed_worktrees.len(),
+                        result.removed_workspace_files.len()
+                    );
+                    for warning in &result.warnings {
+                        message.push_str(". ");
+                        message.push_str(warning);
+                    }
+                    message
+                })
+            }
+            Action::Open { workspace } => commands::open::execute(Some(&workspace), None)
+                .map(|target| format!("Opened {}", target.workspace_file.display())),
+        };
+
+        match result.and_then(|message| self.reload().map(|_| message)) {
+            Ok(message) => self.dashboard.set_message(message),
+            Err(e) => self.dashboard.set_error(e.to_string()),
+        }
+    }
+
+    /// Fetches the bare clones of the repositories checked out in the workspace.
+    fn fetch(&self, workspace: &str) -> Result<String, WxError> {
+        let entry =
+            WorkspaceRegistryService::new()?.resolve(workspace, &std::env::current_dir()?)?;
+        let mut repos: Vec<String> = Vec::new();
+        for member in entry.members {
+            if !repos.contains(&member.repo_name) {
+                repos.push(member.repo_name);
+            }
+        }
+
+        // an empty list would fetch every registered repository
+        if repos.is_empty() {
+            return Ok("No repositories to fetch".to_string());
         }
+
+        let reports = commands::fetch::execute(&repos, None, |_, _, _| {})?;
+        Ok(reports
+            .iter()
+            .map(|report| match &report.error {
+                Some(error) => format!("{}: {}", report.repo_name, error.message),
+                None => format!("{}: fetched", report.repo_name),
+            })
+            .collect::<Vec<_>>()
+            .join(", "))
     }
 
     /// Set running to false to quit the application.
@@ -0,0 +1,536 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::models::{
+    status::WorkspaceStatus,
+    workspace::{WorkspaceEntry, WorkspaceMember},
+    Repository,
+};
+
+/// en: Pane that receives the cursor keys
+///
+/// ja: カーソルキーの操作対象となるペイン
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Pane {
+    #[default]
+    Repositories,
+    Workspaces,
+}
+
+/// en: A row of the workspace pane: a workspace, or one of its worktrees
+///
+/// ja: workspaceペインの行（workspace、またはそのworktree）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Row {
+    Workspace(usize),
+    Member(usize, usize),
+}
+
+/// en: An operation requested from the dashboard, performed by [`super::App`]
+/// Workspaces are identified by their directory
+///
+/// ja: ダッシュボードから要求された操作（[`super::App`] が実行する）
+/// workspaceはディレクトリで識別する
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Refresh,
+    NewWorkspace {
+        name: String,
+        repos: Vec<String>,
+        branch: String,
+    },
+    AddRepository {
+        workspace: String,
+        spec: String,
+    },
+    RemoveRepository {
+        workspace: String,
+        spec: String,
+    },
+    Fetch {
+        workspace: String,
+    },
+    Clean {
+        workspace: String,
+    },
+    Open {
+        workspace: String,
+    },
+}
+
+impl Action {
+    /// en: Message shown while the action runs, or `None` for instant actions
+    ///
+    /// ja: 操作の実行中に表示するメッセージ（即座に終わる操作は `None`）
+    pub fn progress(&self) -> Option<String> {
+        let name = |workspace: &str| {
+            Path::new(workspace)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| workspace.to_string())
+        };
+        match self {
+            Self::Quit | Self::Refresh => None,
+            Self::NewWorkspace { name, .. } => Some(format!("Creating {name}…")),
+            Self::AddRepository { workspace, spec } => {
+                Some(format!("Adding {spec} to {}…", name(workspace)))
+            }
+            Self::RemoveRepository { workspace, spec } => {
+                Some(format!("Removing {spec} from {}…", name(workspace)))
+            }
+            Self::Fetch { workspace } => Some(format!("Fetching {}…", name(workspace))),
+            Self::Clean { workspace } => Some(format!("Cleaning {}…", name(workspace))),
+            Self::Open { workspace } => Some(format!("Opening {}…", name(workspace))),
+        }
+    }
+}
+
+/// en: Text being typed into the footer
+///
+/// ja: フッターに入力中のテキスト
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prompt {
+    NewWorkspaceName,
+    NewWorkspaceBranch { name: String },
+    AddRepository { workspace: String },
+}
+
+impl Prompt {
+    pub fn label(&self) -> String {
+        match self {
+            Self::NewWorkspaceName => "New workspace name".to_string(),
+            Self::NewWorkspaceBranch { name } => format!("Branch for {name}"),
+            Self::AddRepository { workspace } => format!(
+                "Add <repo>[:<branch>] to {}",
+                Path::new(workspace)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Normal,
+    Input {
+        prompt: Prompt,
+        value: String,
+    },
+    Confirm {
+        question: String,
+        action: Action,
+    },
+}
+
+/// en: State of the dashboard, kept apart from rendering and from running the actions
+///
+/// ja: ダッシュボードの状態（描画や操作の実行とは分離して保持する）
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    pub(super) repositories: Vec<Repository>,
+    pub(super) workspaces: Vec<WorkspaceEntry>,
+    /// Status of each workspace directory, loaded when the workspace is first selected
+    pub(super) statuses: HashMap<PathBuf, Result<WorkspaceStatus, String>>,
+    /// Repositories marked with space for the next new workspace
+    pub(super) marked: BTreeSet<String>,
+    pub(super) focus: Pane,
+    pub(super) repository_index: usize,
+    pub(super) row_index: usize,
+    pub(super) mode: Mode,
+    /// Result of the last action, and whether it failed
+    pub(super) message: Option<(String, bool)>,
+}
+
+impl Dashboard {
+    pub fn new(repositories: Vec<Repository>, workspaces: Vec<WorkspaceEntry>) -> Self {
+        let mut dashboard = Self::default();
+        dashboard.set_data(repositories, workspaces);
+        dashboard
+    }
+
+    /// en: Replaces the repositories and workspaces, keeping the cursor in range
+    ///
+    /// ja: リポジトリとworkspaceを置き換え、カーソルを範囲内に収める
+    pub fn set_data(&mut self, repositories: Vec<Repository>, workspaces: Vec<WorkspaceEntry>) {
+        self.marked
+            .retain(|name| repositories.iter().any(|repo| &repo.name == name));
+        self.repositories = repositories;
+        self.workspaces = workspaces;
+        self.statuses.clear();
+        self.repository_index = self
+            .repository_index
+            .min(self.repositories.len().saturating_sub(1));
+        self.row_index = self.row_index.min(self.rows().len().saturating_sub(1));
+    }
+
+    pub fn set_status(&mut self, workspace_dir: PathBuf, status: Result<WorkspaceStatus, String>) {
+        self.statuses.insert(workspace_dir, status);
+    }
+
+    /// en: Directory of the selected workspace when its status has not been loaded yet
+    ///
+    /// ja: 選択中のworkspaceの状態が未読み込みの場合、そのディレクトリを返却する
+    pub fn pending_status(&self) -> Option<PathBuf> {
+        let workspace = self.selected_workspace()?;
+        (!self.statuses.contains_key(&workspace.path)).then(|| workspace.path.clone())
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some((message.into(), false));
+    }
+
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.message = Some((message.into(), true));
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// en: Rows of the workspace pane, each workspace followed by its worktrees
+    ///
+    /// ja: workspaceペインの行（各workspaceの後にそのworktreeが続く）
+    pub fn rows(&self) -> Vec<Row> {
+        self.workspaces
+            .iter()
+            .enumerate()
+            .flat_map(|(index, workspace)| {
+                std::iter::once(Row::Workspace(index)).chain(
+                    (0..workspace.members.len()).map(move |member| Row::Member(index, member)),
+                )
+            })
+            .collect()
+    }
+
+    pub fn selected_repository(&self) -> Option<&Repository> {
+        self.repositories.get(self.repository_index)
+    }
+
+    pub fn selected_workspace(&self) -> Option<&WorkspaceEntry> {
+        match self.rows().get(self.row_index)? {
+            Row::Workspace(index) | Row::Member(index, _) => self.workspaces.get(*index),
+        }
+    }
+
+    pub fn selected_member(&self) -> Option<&WorkspaceMember> {
+        match self.rows().get(self.row_index)? {
+            Row::Member(workspace, member) => self.workspaces.get(*workspace)?.members.get(*member),
+            Row::Workspace(_) => None,
+        }
+    }
+
+    /// en: Updates the state for a key press, returning the action to perform
+    ///
+    /// ja: キー入力に応じて状態を更新し、実行する操作を返却する
+    pub fn on_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers == KeyModifiers::CONTROL
+            && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C'))
+        {
+            return Some(Action::Quit);
+        }
+        match std::mem::take(&mut self.mode) {
+            Mode::Normal => self.on_normal_key(key),
+            Mode::Input { prompt, value } => self.on_input_key(key, prompt, value),
+            Mode::Confirm { action, .. } => {
+                matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')).then_some(action)
+            }
+        }
+    }
+
+    fn on_normal_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Action::Quit),
+            KeyCode::Char('r') => return Some(Action::Refresh),
+            KeyCode::Tab
+            | KeyCode::BackTab
+            | KeyCode::Left
+            | KeyCode::Right
+            | KeyCode::Char('h')
+            | KeyCode::Char('l') => {
+                self.focus = match self.focus {
+                    Pane::Repositories => Pane::Workspaces,
+                    Pane::Workspaces => Pane::Repositories,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::Char(' ') if self.focus == Pane::Repositories => {
+                if let Some(name) = self.selected_repository().map(|repo| repo.name.clone()) {
+                    if !self.marked.remove(&name) {
+                        self.marked.insert(name);
+                    }
+                }
+            }
+            KeyCode::Char('n') => {
+                if self.repositories.is_empty() {
+                    self.set_error("Register a repository first with `wx register <url>`");
+                } else {
+                    self.mode = Mode::Input {
+                        prompt: Prompt::NewWorkspaceName,
+                        value: String::new(),
+                    };
+                }
+            }
+            KeyCode::Char('a') => match (self.selected_workspace(), self.selected_repository()) {
+                (Some(workspace), repo) => {
+                    self.mode = Mode::Input {
+                        prompt: Prompt::AddRepository {
+                            workspace: workspace.path.to_string_lossy().into_owned(),
+                        },
+                        value: repo.map(|repo| repo.name.clone()).unwrap_or_default(),
+                    }
+                }
+                (None, _) => self.set_error("Select a workspace to add a repository to"),
+            },
+            KeyCode::Char('d') => match (self.selected_workspace(), self.selected_member()) {
+                (Some(workspace), Some(member)) => {
+                    self.mode = Mode::Confirm {
+                        question: format!(
+                            "Remove {} ({}) from {}? [y/N]",
+                            member.repo_name, member.branch, workspace.name
+                        ),
+                        action: Action::RemoveRepository {
+                            workspace: workspace.path.to_string_lossy().into_owned(),
+                            spec: format!("{}:{}", member.repo_name, member.branch),
+                        },
+                    }
+                }
+                _ => self.set_error("Select a worktree in the workspace pane to remove it"),
+            },
+            KeyCode::Char('f') => {
+                return self.workspace_action(|workspace| Action::Fetch { workspace })
+            }
+            KeyCode::Char('o') | KeyCode::Enter => {
+                return self.workspace_action(|workspace| Action::Open { workspace })
+            }
+            KeyCode::Char('c') => {
+                if let Some(workspace) = self.selected_workspace() {
+                    self.mode = Mode::Confirm {
+                        question: format!(
+                            "Clean {} and remove its worktrees? [y/N]",
+                            workspace.name
+                        ),
+                        action: Action::Clean {
+                            workspace: workspace.path.to_string_lossy().into_owned(),
+                        },
+                    }
+                } else {
+                    self.set_error("Select a workspace to clean");
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn on_input_key(&mut self, key: KeyEvent, prompt: Prompt, mut value: String) -> Option<Action> {
+        match key.code {
+            KeyCode::Esc => return None,
+            KeyCode::Enter => return self.submit(prompt, value.trim().to_string()),
+            KeyCode::Backspace => {
+                value.pop();
+            }
+            KeyCode::Char(c) => value.push(c),
+            _ => {}
+        }
+        self.mode = Mode::Input { prompt, value };
+        None
+    }
+
+    fn submit(&mut self, prompt: Prompt, value: String) -> Option<Action> {
+        if value.is_empty() {
+            return None;
+        }
+        match prompt {
+            Prompt::NewWorkspaceName => {
+                self.mode = Mode::Input {
+                    prompt: Prompt::NewWorkspaceBranch {
+                        name: value.clone(),
+                    },
+                    value,
+                };
+                None
+            }
+            Prompt::NewWorkspaceBranch { name } => {
+                let repos = if self.marked.is_empty() {
+                    self.selected_repository()
+                        .map(|repo| vec![repo.name.clone()])
+                        .unwrap_or_default()
+                } else {
+                    self.marked.iter().cloned().collect()
+                };
+                Some(Action::NewWorkspace {
+                    name,
+                    repos,
+                    branch: value,
+                })
+            }
+            Prompt::AddRepository { workspace } => Some(Action::AddRepository {
+                workspace,
+                spec: value,
+            }),
+        }
+    }
+
+    fn workspace_action(&mut self, action: impl FnOnce(String) -> Action) -> Option<Action> {
+        match self.selected_workspace() {
+            Some(workspace) => Some(action(workspace.path.to_string_lossy().into_owned())),
+            None => {
+                self.set_error("Select a workspace first");
+                None
+            }
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let (index, len) = match self.focus {
+            Pane::Repositories => (&mut self.repository_index, self.repositories.len()),
+            Pane::Workspaces => {
+                let len = self.rows().len();
+                (&mut self.row_index, len)
+            }
+        };
+        if len > 0 {
+            *index = index.saturating_add_signed(delta).min(len - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use chrono::Utc;
+    use crossterm::event::KeyEvent;
+
+    use super::*;
+    use crate::models::workspace::WorkspaceFormat;
+
+    pub fn create_test_dashboard() -> Dashboard {
+        let repository = |name: &str| {
+            Repository::new(
+                name.to_string(),
+                format!("git@github.com:org/{name}.git"),
+                format!("/home/user/.wx/{name}.git"),
+            )
+        };
+        let member = |repo_name: &str| WorkspaceMember {
+            repo_name: repo_name.to_string(),
+            branch: "feature-auth".to_string(),
+            path: PathBuf::from(format!("/work/feature-auth/{repo_name}")),
+        };
+        Dashboard::new(
+            vec![repository("frontend"), repository("backend")],
+            vec![WorkspaceEntry {
+                name: "feature-auth".to_string(),
+                path: PathBuf::from("/work/feature-auth"),
+                members: vec![member("frontend"), member("backend")],
+                created_at: Utc::now(),
+                workspace_file: PathBuf::from("/work/feature-auth/feature-auth.code-workspace"),
+                formats: vec![WorkspaceFormat::VsCode],
+            }],
+        )
+    }
+
+    fn press(dashboard: &mut Dashboard, code: KeyCode) -> Option<Action> {
+        dashboard.on_key(KeyEvent::from(code))
+    }
+
+    fn type_text(dashboard: &mut Dashboard, text: &str) {
+        for c in text.chars() {
+            press(dashboard, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut dashboard = create_test_dashboard();
+        assert_eq!(dashboard.rows().len(), 3);
+
+        press(&mut dashboard, KeyCode::Down);
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(dashboard.selected_repository().unwrap().name, "backend");
+
+        press(&mut dashboard, KeyCode::Tab);
+        assert_eq!(dashboard.focus, Pane::Workspaces);
+        assert!(dashboard.selected_member().is_none());
+        press(&mut dashboard, KeyCode::Char('j'));
+        assert_eq!(dashboard.selected_member().unwrap().repo_name, "frontend");
+        assert_eq!(
+            dashboard.pending_status(),
+            Some(PathBuf::from("/work/feature-auth"))
+        );
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Char('q')),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn test_new_workspace_from_marked_repositories() {
+        let mut dashboard = create_test_dashboard();
+        press(&mut dashboard, KeyCode::Char(' '));
+        press(&mut dashboard, KeyCode::Down);
+        press(&mut dashboard, KeyCode::Char(' '));
+
+        press(&mut dashboard, KeyCode::Char('n'));
+        type_text(&mut dashboard, "login");
+        assert_eq!(press(&mut dashboard, KeyCode::Enter), None);
+        // the branch prompt starts with the workspace name
+        press(&mut dashboard, KeyCode::Backspace);
+        type_text(&mut dashboard, "n-fix");
+
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Enter),
+            Some(Action::NewWorkspace {
+                name: "login".to_string(),
+                repos: vec!["backend".to_string(), "frontend".to_string()],
+                branch: "login-fix".to_string(),
+            })
+        );
+        assert_eq!(dashboard.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_remove_requires_confirmation() {
+        let mut dashboard = create_test_dashboard();
+        press(&mut dashboard, KeyCode::Tab);
+        press(&mut dashboard, KeyCode::Down);
+        press(&mut dashboard, KeyCode::Down);
+
+        press(&mut dashboard, KeyCode::Char('d'));
+        assert!(matches!(dashboard.mode, Mode::Confirm { .. }));
+        assert_eq!(press(&mut dashboard, KeyCode::Char('n')), None);
+        assert_eq!(dashboard.mode, Mode::Normal);
+
+        press(&mut dashboard, KeyCode::Char('d'));
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Char('y')),
+            Some(Action::RemoveRepository {
+                workspace: "/work/feature-auth".to_string(),
+                spec: "backend:feature-auth".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_workspace_actions_need_a_workspace() {
+        let mut dashboard = Dashboard::new(vec![], vec![]);
+        assert_eq!(press(&mut dashboard, KeyCode::Char('f')), None);
+        assert!(dashboard.message.as_ref().unwrap().1);
+
+        let mut dashboard = create_test_dashboard();
+        assert_eq!(
+            press(&mut dashboard, KeyCode::Char('f')),
+            Some(Action::Fetch {
+                workspace: "/work/feature-auth".to_string()
+            })
+        );
+    }
+}
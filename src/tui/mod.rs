@@ -1 +1,16 @@
 pub mod app;
+mod dashboard;
+mod ui;
+
+use crate::models::WxError;
+
+/// en: Open the full-screen dashboard and restore the terminal when it is closed
+///
+/// ja: フルスクリーンのダッシュボードを開き、終了時にターミナルを元に戻す
+pub fn run() -> Result<(), WxError> {
+    let app = app::App::new()?;
+    let terminal = ratatui::try_init()?;
+    let result = app.run(terminal);
+    ratatui::restore();
+    result
+}
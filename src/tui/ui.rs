@@ -0,0 +1,352 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Row as TableRow, Table},
+    Frame,
+};
+
+use super::dashboard::{Dashboard, Mode, Pane, Row};
+use crate::models::status::WorktreeStatus;
+
+const HELP: &str =
+    "tab switch  space mark  n new  a add  d remove  f fetch  c clean  o open  r refresh  q quit";
+
+/// en: Draws the repository, workspace and details panes with the footer below them
+///
+/// ja: リポジトリ・workspace・詳細の各ペインと、その下のフッターを描画する
+pub fn render(dashboard: &Dashboard, frame: &mut Frame) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [repositories, workspaces, details] = Layout::horizontal([
+        Constraint::Percentage(25),
+        Constraint::Percentage(35),
+        Constraint::Percentage(40),
+    ])
+    .areas(main);
+
+    render_repositories(dashboard, frame, repositories);
+    render_workspaces(dashboard, frame, workspaces);
+    match dashboard.focus {
+        Pane::Repositories => render_repository_details(dashboard, frame, details),
+        Pane::Workspaces => render_workspace_details(dashboard, frame, details),
+    }
+    render_footer(dashboard, frame, footer);
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(Line::from(format!(" {title} ")).bold());
+    if focused {
+        block.border_style(Style::default().fg(Color::Cyan))
+    } else {
+        block.border_style(Style::default().fg(Color::DarkGray))
+    }
+}
+
+fn highlight(focused: bool) -> Style {
+    if focused {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
+    }
+}
+
+fn render_repositories(dashboard: &Dashboard, frame: &mut Frame, area: Rect) {
+    let focused = dashboard.focus == Pane::Repositories;
+    let block = pane("Repositories", focused);
+    if dashboard.repositories.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No registered repositories.\nRun `wx register <url>`.")
+                .dim()
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = dashboard
+        .repositories
+        .iter()
+        .map(|repo| {
+            let mark = if dashboard.marked.contains(&repo.name) {
+                "● "
+            } else {
+                "  "
+            };
+            ListItem::new(Line::from(vec![
+                Span::from(mark).green(),
+                Span::from(repo.name.clone()).cyan(),
+            ]))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(dashboard.repository_index));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(block)
+            .highlight_style(highlight(focused)),
+        area,
+        &mut state,
+    );
+}
+
+fn render_workspaces(dashboard: &Dashboard, frame: &mut Frame, area: Rect) {
+    let focused = dashboard.focus == Pane::Workspaces;
+    let block = pane("Workspaces", focused);
+    if dashboard.workspaces.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No registered workspaces.\nPress `n` to create one.")
+                .dim()
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = dashboard
+        .rows()
+        .into_iter()
+        .map(|row| match row {
+            Row::Workspace(index) => {
+                ListItem::new(Line::from(dashboard.workspaces[index].name.clone()).bold())
+            }
+            Row::Member(index, member) => {
+                let member = &dashboard.workspaces[index].members[member];
+                ListItem::new(Line::from(vec![
+                    Span::from("  "),
+                    Span::from(member.repo_name.clone()).cyan(),
+                    Span::from(format!(" ({})", member.branch)).dim(),
+                ]))
+            }
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(dashboard.row_index));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(block)
+            .highlight_style(highlight(focused)),
+        area,
+        &mut state,
+    );
+}
+
+fn render_repository_details(dashboard: &Dashboard, frame: &mut Frame, area: Rect) {
+    let block = pane("Details", false);
+    let Some(repo) = dashboard.selected_repository() else {
+        frame.render_widget(block, area);
+        return;
+    };
+
+    let mut lines = vec![
+        Line::from(repo.name.clone()).bold(),
+        Line::from(vec![
+            Span::from("remote  ").dim(),
+            Span::from(repo.remote.clone()),
+        ]),
+        Line::from(vec![
+            Span::from("path    ").dim(),
+            Span::from(repo.local_path.clone()),
+        ]),
+    ];
+    if let Some(base) = &repo.default_base {
+        lines.push(Line::from(vec![
+            Span::from("base    ").dim(),
+            Span::from(base.clone()),
+        ]));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from("Used by").dim());
+    let mut used = false;
+    for workspace in &dashboard.workspaces {
+        for member in workspace
+            .members
+            .iter()
+            .filter(|member| member.repo_name == repo.name)
+        {
+            used = true;
+            lines.push(Line::from(vec![
+                Span::from(format!("  {}", workspace.name)).cyan(),
+                Span::from(format!(" ({})", member.branch)).dim(),
+            ]));
+        }
+    }
+    if !used {
+        lines.push(Line::from("  no workspace").dim());
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_workspace_details(dashboard: &Dashboard, frame: &mut Frame, area: Rect) {
+    let block = pane("Details", false);
+    let Some(workspace) = dashboard.selected_workspace() else {
+        frame.render_widget(block, area);
+        return;
+    };
+    let block = block.title_bottom(Line::from(format!(" {} ", workspace.path.display())).dim());
+
+    let status = match dashboard.statuses.get(&workspace.path) {
+        None => {
+            frame.render_widget(Paragraph::new("Loading…").dim().block(block), area);
+            return;
+        }
+        Some(Err(error)) => {
+            frame.render_widget(Paragraph::new(error.clone()).red().block(block), area);
+            return;
+        }
+        Some(Ok(status)) => status,
+    };
+
+    let selected = dashboard.selected_member();
+    let rows = status.worktrees.iter().map(|member| {
+        let worktree = &member.status;
+        let row = TableRow::new(vec![
+            Line::from(member.repo_name.clone()).cyan(),
+            Line::from(branch(worktree)),
+            sync(worktree),
+            changes(worktree),
+        ]);
+        if selected.is_some_and(|selected| selected.path == member.path) {
+            row.add_modifier(Modifier::BOLD)
+        } else {
+            row
+        }
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(9),
+            Constraint::Length(11),
+        ],
+    )
+    .header(TableRow::new(vec!["repo", "branch", "sync", "changes"]).dim())
+    .block(block);
+    frame.render_widget(table, area);
+}
+
+fn branch(worktree: &WorktreeStatus) -> String {
+    if worktree.missing {
+        return "missing".to_string();
+    }
+    worktree
+        .branch
+        .clone()
+        .unwrap_or_else(|| "(detached)".to_string())
+}
+
+fn sync(worktree: &WorktreeStatus) -> Line<'static> {
+    match &worktree.upstream {
+        Some(_) if worktree.ahead > 0 || worktree.behind > 0 => {
+            Line::from(format!("↑{} ↓{}", worktree.ahead, worktree.behind)).yellow()
+        }
+        Some(_) => Line::from("↑0 ↓0").dim(),
+        None => Line::from("-").dim(),
+    }
+}
+
+fn changes(worktree: &WorktreeStatus) -> Line<'static> {
+    let line = Line::from(format!(
+        "+{} ~{} ?{}",
+        worktree.staged, worktree.dirty, worktree.untracked
+    ));
+    if worktree.staged + worktree.dirty + worktree.untracked > 0 {
+        line.yellow()
+    } else {
+        line.dim()
+    }
+}
+
+fn render_footer(dashboard: &Dashboard, frame: &mut Frame, area: Rect) {
+    let line = match &dashboard.mode {
+        Mode::Input { prompt, value } => Line::from(vec![
+            Span::from(format!("{}: ", prompt.label())).cyan(),
+            Span::from(value.clone()),
+            Span::from("▏"),
+        ]),
+        Mode::Confirm { question, .. } => Line::from(question.clone()).yellow(),
+        Mode::Normal => match &dashboard.message {
+            Some((message, true)) => Line::from(message.clone()).red(),
+            Some((message, false)) => Line::from(message.clone()).green(),
+            None => Line::from(HELP).dim(),
+        },
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crossterm::event::{KeyCode, KeyEvent};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::{
+        models::status::{MemberStatus, WorkspaceStatus},
+        tui::dashboard::tests::create_test_dashboard,
+    };
+
+    fn draw(dashboard: &Dashboard) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 12)).unwrap();
+        terminal.draw(|frame| render(dashboard, frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_render_panes() {
+        let screen = draw(&create_test_dashboard());
+
+        assert!(screen.contains("Repositories"));
+        assert!(screen.contains("frontend"));
+        assert!(screen.contains("feature-auth"));
+        assert!(screen.contains("  backend (feature-auth)"));
+        assert!(screen.contains("git@github.com:org/frontend.git"));
+        assert!(screen.contains("q quit"));
+    }
+
+    #[test]
+    fn test_render_workspace_status() {
+        let mut dashboard = create_test_dashboard();
+        dashboard.on_key(KeyEvent::from(KeyCode::Tab));
+        assert!(draw(&dashboard).contains("Loading…"));
+
+        let status = |repo_name: &str, dirty: usize| MemberStatus {
+            repo_name: repo_name.to_string(),
+            path: PathBuf::from(format!("/work/feature-auth/{repo_name}")),
+            status: WorktreeStatus {
+                branch: Some("feature-auth".to_string()),
+                upstream: Some("origin/feature-auth".to_string()),
+                ahead: 2,
+                dirty,
+                ..Default::default()
+            },
+        };
+        dashboard.set_status(
+            PathBuf::from("/work/feature-auth"),
+            Ok(WorkspaceStatus {
+                name: "feature-auth".to_string(),
+                path: PathBuf::from("/work/feature-auth"),
+                worktrees: vec![status("frontend", 3), status("backend", 0)],
+            }),
+        );
+
+        let screen = draw(&dashboard);
+        assert!(screen.contains("↑2 ↓0"));
+        assert!(screen.contains("+0 ~3 ?0"));
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let mut dashboard = create_test_dashboard();
+        dashboard.on_key(KeyEvent::from(KeyCode::Char('n')));
+        dashboard.on_key(KeyEvent::from(KeyCode::Char('x')));
+
+        assert!(draw(&dashboard).contains("New workspace name: x"));
+    }
+}
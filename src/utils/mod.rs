@@ -1,6 +1,8 @@
 // Utility functions
+pub mod parallel;
 pub mod path;
 
+pub use parallel::*;
 pub use path::*;
 
 #[cfg(test)]
@@ -0,0 +1,77 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// en: Number of jobs used when none is given: the number of CPUs
+///
+/// ja: ジョブ数の指定が無い場合に使用する値（CPU数）
+pub fn default_jobs() -> usize {
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+}
+
+/// en: Apply `f` to every item on at most `jobs` threads, returning the results in item order
+/// `f` receives the index of the item as well
+///
+/// ja: 最大 `jobs` 個のスレッドで各要素に `f` を適用し、結果を要素の順に返却する
+/// `f` には要素のインデックスも渡す
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = f(index, item);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_parallel_map_keeps_order_and_bounds_jobs() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let results = parallel_map(&[5, 1, 4, 2, 3], 2, |index, item| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            running.fetch_sub(1, Ordering::SeqCst);
+            (index, item * 10)
+        });
+
+        assert_eq!(results, vec![(0, 50), (1, 10), (2, 40), (3, 20), (4, 30)]);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert!(parallel_map(&[] as &[usize], 4, |_, item| *item).is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use crate::models::WxError;
 
@@ -17,6 +17,16 @@ pub fn get_backup_path() -> Option<PathBuf> {
     get_wx_home().map(|wx_home| wx_home.join("config.backup.json"))
 }
 
+/// en: Expand a leading `~` of the path to the home directory
+///
+/// ja: パス先頭の `~` をホームディレクトリに展開する
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
 /// en: Returns the current working directory path
 ///
 /// ja: 現在いるディレクトリパスを返却する
@@ -24,6 +34,26 @@ pub fn get_current_dir() -> Result<PathBuf, WxError> {
     Ok(std::env::current_dir()?)
 }
 
+/// en: Check that a name is a single directory name: one normal path component, so that
+/// joining it to a directory never leaves that directory
+///
+/// ja: 名前が1つのディレクトリ名（通常のパス要素1つ）か確認する
+/// ディレクトリに連結しても、そのディレクトリの外を指すことが無い
+pub fn is_single_component(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) && !name.contains(['/', '\\'])
+}
+
+/// en: Canonicalize a path when it exists so that symlinked temp dirs compare equal
+///
+/// ja: シンボリックリンクを含むパスを比較できるよう、存在するパスを正規化する
+pub fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
 /// en: Sanitize branch name for use in file system paths
 /// Replaces `/`, `\`, `:`, `*`, `?`, `"`, `<`, `>`, `|`, and spaces with `-`
 ///
@@ -70,4 +100,26 @@ mod tests {
         let path = backup_path.unwrap();
         assert!(path.ends_with("config.backup.json"));
     }
+
+    #[test]
+    fn test_is_single_component() {
+        assert!(is_single_component("web"));
+        assert!(is_single_component("feature-auth.v2"));
+        for name in ["", ".", "..", "../escaped", "/tmp/x", "a/b", "a\\b", "web/"] {
+            assert!(!is_single_component(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            expand_home(Path::new("~/.ssh/id_rsa")),
+            home.join(".ssh/id_rsa")
+        );
+        assert_eq!(
+            expand_home(Path::new("/keys/id_rsa")),
+            PathBuf::from("/keys/id_rsa")
+        );
+    }
 }
@@ -3,7 +3,8 @@ use std::path::{Path, PathBuf};
 
 use tempfile::TempDir;
 
-use crate::models::workspace::WorkspaceFile;
+use crate::infrastructure::config::ConfigManager;
+use crate::models::workspace::{WorkspaceEntry, WorkspaceFile, WorkspaceMember};
 use crate::models::{Config, Repository};
 
 /// en: Set up a temporary directory and base_dir for testing
@@ -44,6 +45,53 @@ pub fn create_test_repository(name: &str) -> Repository {
     )
 }
 
+/// en: Generate a Repository model whose bare repository is `<base_dir>/<name>.git` for testing
+///
+/// ja: bareリポジトリが `<base_dir>/<name>.git` にあるテスト用のRepositoryモデルを生成
+pub fn create_test_repository_in(base_dir: &Path, name: &str) -> Repository {
+    Repository::new(
+        name.to_string(),
+        format!("git@github.com:org/{name}.git"),
+        base_dir
+            .join(format!("{name}.git"))
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+/// en: Create a bare repository in base_dir and register it in config.json for testing
+///
+/// ja: テスト用のbareリポジトリをbase_dirに作成し、config.jsonに登録
+pub fn register_test_bare_repo(base_dir: &Path, name: &str) -> PathBuf {
+    let repo_path = create_test_bare_repo(base_dir, name);
+    let config_manager = ConfigManager::with_base_dir(base_dir);
+    let mut config = config_manager.load().unwrap();
+    config
+        .add_repository(create_test_repository_in(base_dir, name))
+        .unwrap();
+    config_manager.save(&config).unwrap();
+    repo_path
+}
+
+/// en: Generate a WorkspaceEntry model for testing
+///
+/// ja: テスト用のWorkspaceEntryモデルを生成
+pub fn create_test_workspace_entry(name: &str) -> WorkspaceEntry {
+    let path = PathBuf::from(format!("/home/user/work/{name}"));
+    WorkspaceEntry {
+        name: name.to_string(),
+        members: vec![WorkspaceMember {
+            repo_name: "frontend".to_string(),
+            branch: "main".to_string(),
+            path: path.join("main"),
+        }],
+        created_at: chrono::Utc::now(),
+        workspace_file: path.join(format!("{name}.code-workspace")),
+        path,
+        formats: Vec::new(),
+    }
+}
+
 /// en: Create a config.json for testing
 ///
 /// ja: テスト用のconfig.jsonを作成
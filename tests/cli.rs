@@ -0,0 +1,35 @@
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+
+/// Run the wx binary with a fresh home directory
+fn wx(home: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_wx"))
+        .args(args)
+        .env("HOME", home.path())
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_text_errors_exit_with_failure_on_stderr() {
+    let home = TempDir::new().unwrap();
+
+    for args in [&["status", "nope"][..], &["unregister", "foo"][..]] {
+        let output = wx(&home, args);
+        assert_eq!(output.status.code(), Some(1), "wx {args:?}");
+        assert!(output.stdout.is_empty(), "wx {args:?}");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("not found"));
+    }
+}
+
+#[test]
+fn test_json_errors_exit_with_failure_on_stdout() {
+    let home = TempDir::new().unwrap();
+
+    let output = wx(&home, &["--output", "json", "status", "nope"]);
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "workspace_not_found");
+}